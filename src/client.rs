@@ -0,0 +1,32 @@
+//! Helpers for off-chain clients of the bridge
+
+use crate::solana_program::{pubkey::Pubkey, rent::Rent};
+use crate::state::{AccountType, BlockchainId, Lock, LockTx, Signature, TxIndex, User};
+
+/// Offset of `Lock.index` in the lock account data
+pub const LOCK_INDEX_OFFSET: usize = 2;
+/// Offset of `Lock.bridge` in the lock account data
+pub const LOCK_BRIDGE_OFFSET: usize = 82;
+/// Offset of `Lock.source` in the lock account data
+pub const LOCK_SOURCE_OFFSET: usize = 154;
+
+/// Lamports the payer needs for the accounts created by the first signature of a lock:
+/// the lock, its transaction index, the signature, the sender and recipient users and their sent and received lock records
+pub fn total_transfer_rent(rent: &Rent) -> u64 {
+    rent.minimum_balance(Lock::LEN)
+        + rent.minimum_balance(TxIndex::LEN)
+        + rent.minimum_balance(Signature::LEN)
+        + 2 * rent.minimum_balance(User::LEN)
+        + 2 * rent.minimum_balance(LockTx::LEN)
+}
+
+/// Memcmp filters, as `(offset, bytes)`, selecting the lock with the given index within the source blockchain,
+/// lock addresses are derived from the lock id so a lookup by index goes through `getProgramAccounts`
+pub fn lock_by_index_filters(bridge: &Pubkey, source: &BlockchainId, index: u64) -> Vec<(usize, Vec<u8>)> {
+    vec![
+        (0, vec![AccountType::Lock as u8]),
+        (LOCK_INDEX_OFFSET, index.to_le_bytes().to_vec()),
+        (LOCK_BRIDGE_OFFSET, bridge.to_bytes().to_vec()),
+        (LOCK_SOURCE_OFFSET, source.to_vec()),
+    ]
+}
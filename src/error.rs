@@ -18,6 +18,126 @@ pub enum SolBridgeProgramError {
     /// Secp256 instruction losing
     #[error("Secp256 instruction losing")]
     Secp256InstructionLosing,
+    /// Validator is disabled
+    #[error("Validator is inactive")]
+    ValidatorInactive,
+    /// User authority address is occupied by an existing account
+    #[error("User authority account is in use")]
+    UserAuthorityInUse,
+    /// Fee is above 100%
+    #[error("Invalid fee")]
+    InvalidFee,
+    /// No token info for the token source and address
+    #[error("Token not registered")]
+    TokenNotRegistered,
+    /// Lock has enough signatures
+    #[error("Lock already finalized")]
+    LockAlreadyFinalized,
+    /// Transfer amount is zero
+    #[error("Invalid amount")]
+    InvalidAmount,
+    /// Sender and recipient addresses are both zero
+    #[error("Invalid address")]
+    InvalidAddress,
+    /// Transfer source and destination blockchains are the same
+    #[error("Source and destination blockchains are the same")]
+    SameSourceAndDestination,
+    /// Validator is temporarily paused
+    #[error("Validator is paused")]
+    ValidatorPaused,
+    /// Arithmetic operation overflowed
+    #[error("Arithmetic overflow")]
+    Overflow,
+    /// Sender is not in the allowlist of the source blockchain
+    #[error("Sender not allowed")]
+    SenderNotAllowed,
+    /// Existing lock has a different lock id
+    #[error("Lock id mismatch")]
+    LockIdMismatch,
+    /// Existing lock has a different transaction id
+    #[error("Transaction id mismatch")]
+    TxIdMismatch,
+    /// Existing lock has a different token source address
+    #[error("Token source address mismatch")]
+    TokenSourceAddressMismatch,
+    /// Existing lock has a different token source
+    #[error("Token source mismatch")]
+    TokenSourceMismatch,
+    /// Existing lock has a different source blockchain
+    #[error("Source mismatch")]
+    SourceMismatch,
+    /// Existing lock has a different sender
+    #[error("Sender mismatch")]
+    SenderMismatch,
+    /// Existing lock has a different recipient
+    #[error("Recipient mismatch")]
+    RecipientMismatch,
+    /// Existing lock has a different destination blockchain
+    #[error("Destination mismatch")]
+    DestinationMismatch,
+    /// Existing lock has a different amount
+    #[error("Amount mismatch")]
+    AmountMismatch,
+    /// Strict mode requires a route account for the source and destination
+    #[error("Route not configured")]
+    RouteNotConfigured,
+    /// Lock has not collected enough signatures yet
+    #[error("Lock not finalized")]
+    LockNotFinalized,
+    /// Lock tokens have already been released to the recipient
+    #[error("Lock already withdrawn")]
+    LockAlreadyWithdrawn,
+    /// Same account is passed for the lock and the signature
+    #[error("Lock and signature accounts are the same")]
+    DuplicateLockAndSignatureAccount,
+    /// Account data belongs to another kind of account
+    #[error("Invalid account type")]
+    InvalidAccountType,
+    /// Tokens of the lock have not been released yet
+    #[error("Lock not withdrawn")]
+    LockNotWithdrawn,
+    /// Withdrawal of the lock has already been reverted
+    #[error("Unlock already reverted")]
+    UnlockAlreadyReverted,
+    /// Dispute window has passed or disputes are disabled
+    #[error("Dispute window closed")]
+    DisputeWindowClosed,
+    /// Remote transaction is already claimed by another lock
+    #[error("Transaction already locked")]
+    TxAlreadyLocked,
+    /// Validator nonce does not follow the last one used
+    #[error("Stale validator nonce")]
+    StaleNonce,
+    /// Payer is not the validator owner
+    #[error("Unauthorized validator")]
+    UnauthorizedValidator,
+    /// Validator belongs to another blockchain
+    #[error("Validator chain mismatch")]
+    ValidatorChainMismatch,
+    /// Blockchain already has the maximum number of validators
+    #[error("Validator limit reached")]
+    ValidatorLimitReached,
+    /// Threshold can't be met by the registered validators
+    #[error("Invalid threshold")]
+    InvalidThreshold,
+    /// Lock would exceed the daily volume limit of the blockchain
+    #[error("Daily limit exceeded")]
+    DailyLimitExceeded,
+    /// Lock amount is below the minimum of the blockchain
+    #[error("Amount below minimum")]
+    AmountBelowMinimum,
+    /// Withdrawal of the lock can still be reverted
+    #[error("Dispute window open")]
+    DisputeWindowOpen,
+    /// Token account mint differs from the mint registered for the token
+    #[error("Mint mismatch")]
+    MintMismatch,
+    /// Lock tokens were never deposited to the escrow
+    #[error("Lock not deposited")]
+    LockNotDeposited,
+    /// Account of an older version has to be migrated before use
+    #[error("Account needs migration")]
+    AccountNeedsMigration,
 }
 impl From<SolBridgeProgramError> for ProgramError {
     fn from(e: SolBridgeProgramError) -> Self {
@@ -38,6 +158,46 @@ impl PrintProgramError for SolBridgeProgramError {
         match self {
             SolBridgeProgramError::InvalidSignature => msg!("Invalid signature"),
             SolBridgeProgramError::Secp256InstructionLosing => msg!("Secp256 instruction losing"),
+            SolBridgeProgramError::ValidatorInactive => msg!("Validator is inactive"),
+            SolBridgeProgramError::UserAuthorityInUse => msg!("User authority account is in use"),
+            SolBridgeProgramError::InvalidFee => msg!("Invalid fee"),
+            SolBridgeProgramError::TokenNotRegistered => msg!("Token not registered"),
+            SolBridgeProgramError::LockAlreadyFinalized => msg!("Lock already finalized"),
+            SolBridgeProgramError::InvalidAmount => msg!("Invalid amount"),
+            SolBridgeProgramError::InvalidAddress => msg!("Invalid address"),
+            SolBridgeProgramError::SameSourceAndDestination => msg!("Source and destination blockchains are the same"),
+            SolBridgeProgramError::ValidatorPaused => msg!("Validator is paused"),
+            SolBridgeProgramError::Overflow => msg!("Arithmetic overflow"),
+            SolBridgeProgramError::SenderNotAllowed => msg!("Sender not allowed"),
+            SolBridgeProgramError::LockIdMismatch => msg!("Lock id mismatch"),
+            SolBridgeProgramError::TxIdMismatch => msg!("Transaction id mismatch"),
+            SolBridgeProgramError::TokenSourceAddressMismatch => msg!("Token source address mismatch"),
+            SolBridgeProgramError::TokenSourceMismatch => msg!("Token source mismatch"),
+            SolBridgeProgramError::SourceMismatch => msg!("Source mismatch"),
+            SolBridgeProgramError::SenderMismatch => msg!("Sender mismatch"),
+            SolBridgeProgramError::RecipientMismatch => msg!("Recipient mismatch"),
+            SolBridgeProgramError::DestinationMismatch => msg!("Destination mismatch"),
+            SolBridgeProgramError::AmountMismatch => msg!("Amount mismatch"),
+            SolBridgeProgramError::RouteNotConfigured => msg!("Route not configured"),
+            SolBridgeProgramError::LockNotFinalized => msg!("Lock not finalized"),
+            SolBridgeProgramError::LockAlreadyWithdrawn => msg!("Lock already withdrawn"),
+            SolBridgeProgramError::DuplicateLockAndSignatureAccount => msg!("Lock and signature accounts are the same"),
+            SolBridgeProgramError::InvalidAccountType => msg!("Invalid account type"),
+            SolBridgeProgramError::LockNotWithdrawn => msg!("Lock not withdrawn"),
+            SolBridgeProgramError::UnlockAlreadyReverted => msg!("Unlock already reverted"),
+            SolBridgeProgramError::DisputeWindowClosed => msg!("Dispute window closed"),
+            SolBridgeProgramError::TxAlreadyLocked => msg!("Transaction already locked"),
+            SolBridgeProgramError::StaleNonce => msg!("Stale validator nonce"),
+            SolBridgeProgramError::UnauthorizedValidator => msg!("Unauthorized validator"),
+            SolBridgeProgramError::ValidatorChainMismatch => msg!("Validator chain mismatch"),
+            SolBridgeProgramError::ValidatorLimitReached => msg!("Validator limit reached"),
+            SolBridgeProgramError::InvalidThreshold => msg!("Invalid threshold"),
+            SolBridgeProgramError::DailyLimitExceeded => msg!("Daily limit exceeded"),
+            SolBridgeProgramError::AmountBelowMinimum => msg!("Amount below minimum"),
+            SolBridgeProgramError::DisputeWindowOpen => msg!("Dispute window open"),
+            SolBridgeProgramError::MintMismatch => msg!("Mint mismatch"),
+            SolBridgeProgramError::LockNotDeposited => msg!("Lock not deposited"),
+            SolBridgeProgramError::AccountNeedsMigration => msg!("Account needs migration"),
         }
     }
 }
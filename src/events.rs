@@ -0,0 +1,140 @@
+//! Program events
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    entrypoint::ProgramResult,
+    hash::hashv,
+    log::sol_log_data,
+    program_error::ProgramError,
+};
+use crate::state::{Address, BlockchainId};
+
+/// Length of the event discriminator
+pub const EVENT_DISCRIMINATOR_LEN: usize = 8;
+
+/// Anchor event discriminator, the first 8 bytes of `sha256("event:<name>")`
+pub fn event_discriminator(name: &str) -> [u8; EVENT_DISCRIMINATOR_LEN] {
+    let mut discriminator = [0; EVENT_DISCRIMINATOR_LEN];
+    discriminator.copy_from_slice(&hashv(&[b"event:", name.as_bytes()]).to_bytes()[..EVENT_DISCRIMINATOR_LEN]);
+    discriminator
+}
+
+/// Event emitted to the program log in the Anchor format: the event discriminator followed by the borsh encoded event
+#[derive(Clone, Debug, PartialEq)]
+pub enum BridgeEvent {
+    /// New lock created
+    LockCreated(LockCreatedEvent),
+    /// Signature accepted
+    SignatureAdded(SignatureAddedEvent),
+    /// Withdrawn lock closed
+    LockClosed(LockClosedEvent),
+    /// User account created by its first lock
+    UserCreated(UserCreatedEvent),
+}
+
+impl BridgeEvent {
+    /// Event name, used for the discriminator
+    pub fn name(&self) -> &'static str {
+        match self {
+            BridgeEvent::LockCreated(_) => "LockCreated",
+            BridgeEvent::SignatureAdded(_) => "SignatureAdded",
+            BridgeEvent::LockClosed(_) => "LockClosed",
+            BridgeEvent::UserCreated(_) => "UserCreated",
+        }
+    }
+
+    /// Event log data
+    pub fn to_log_data(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut data = event_discriminator(self.name()).to_vec();
+        match self {
+            BridgeEvent::LockCreated(event) => event.serialize(&mut data)?,
+            BridgeEvent::SignatureAdded(event) => event.serialize(&mut data)?,
+            BridgeEvent::LockClosed(event) => event.serialize(&mut data)?,
+            BridgeEvent::UserCreated(event) => event.serialize(&mut data)?,
+        }
+        Ok(data)
+    }
+
+    /// Parse event from the program log data
+    pub fn from_log_data(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < EVENT_DISCRIMINATOR_LEN {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (discriminator, payload) = data.split_at(EVENT_DISCRIMINATOR_LEN);
+        if discriminator == event_discriminator("LockCreated") {
+            Ok(BridgeEvent::LockCreated(LockCreatedEvent::try_from_slice(payload)?))
+        } else if discriminator == event_discriminator("SignatureAdded") {
+            Ok(BridgeEvent::SignatureAdded(SignatureAddedEvent::try_from_slice(payload)?))
+        } else if discriminator == event_discriminator("LockClosed") {
+            Ok(BridgeEvent::LockClosed(LockClosedEvent::try_from_slice(payload)?))
+        } else if discriminator == event_discriminator("UserCreated") {
+            Ok(BridgeEvent::UserCreated(UserCreatedEvent::try_from_slice(payload)?))
+        } else {
+            Err(ProgramError::InvalidArgument)
+        }
+    }
+
+    /// Write event to the program log
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&self.to_log_data()?]);
+        Ok(())
+    }
+}
+
+/// Lock created event
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct LockCreatedEvent {
+    /// Lock id
+    pub lock_id: u64,
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Destination blockchain identifier
+    pub destination: BlockchainId,
+    /// Sender address
+    pub sender: Address,
+    /// Recipient address
+    pub recipient: Address,
+    /// Amount to lock for the transfer
+    pub amount: u128,
+}
+
+/// Signature added event
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct SignatureAddedEvent {
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Lock id
+    pub lock_id: u64,
+    /// Validator index
+    pub validator_index: u64,
+    /// Lock signature count including this signature
+    pub signatures_total: u64,
+}
+
+/// Lock closed event, the last record of a withdrawn lock
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct LockClosedEvent {
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Lock id
+    pub lock_id: u64,
+    /// Recipient address
+    pub recipient: Address,
+    /// Amount to lock for the transfer
+    pub amount: u128,
+    /// Unix timestamp of the withdrawal
+    pub unlock_time: i64,
+}
+
+/// User created event
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct UserCreatedEvent {
+    /// Blockchain identifier of the user
+    pub blockchain_id: BlockchainId,
+    /// User address
+    pub address: Address,
+}
@@ -8,8 +8,22 @@ use solana_program::{
     sysvar,
     system_program,
 };
-use crate::utils::str_to_chain_id;
-use crate::state::{Address, BlockchainId, TxId};
+use crate::error::SolBridgeProgramError;
+use crate::pda;
+use crate::utils::{
+    get_route_address,
+    get_sender_allowed_address,
+    get_tip_vault_address,
+    get_token_info_address,
+    get_tx_index_address,
+    str_to_chain_id,
+    BPS_DENOMINATOR,
+};
+use crate::state::{Address, BlockchainId, KeyType, TxId};
+
+/// Maximum number of blockchains registered by one `AddBlockchainBatch`,
+/// keeps the account creations within the compute budget and the transaction size
+pub const ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES: usize = 8;
 
 /// Instruction definition
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -21,25 +35,45 @@ pub enum BridgeProgramInstruction {
     InitializeBridge,
 
     ///Add new blockchain
+    /// 0. `[W]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[WS]` Bridge owner, pays for the blockchain account
+    /// 3. `[R]`  Bridge authority
+    /// 4. `[R]`  System rent variable
+    /// 5. `[R]`  System program
     AddBlockchain {
         /// blockchain_id
         blockchain_id: BlockchainId,
         /// contract_address
-        contract_address: Address
+        contract_address: Address,
+        /// max_validators
+        max_validators: u64
     },
 
     ///Add new validator
+    /// 0. `[W]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[W]`  Validator account
+    /// 3. `[WS]` Bridge owner, pays for the validator account
+    /// 4. `[R]`  Bridge authority
+    /// 5. `[R]`  System rent variable
+    /// 6. `[R]`  System program
+    /// 7. `[R]`  Validator owner, submits the validator signatures itself
     AddValidator {
         /// blockchain_id
         blockchain_id: BlockchainId,
 
         ///Validator public key
         pub_key: [u8; 32],
+
+        /// Signature scheme of the public key, see `KeyType`
+        key_type: u8,
     },
 
     ///Add new signature
     AddSignature {
-        /// signature
+        /// Validator signature of the lock message hash, checked by a preceding precompile instruction
+        /// when relayed, ignored and stored as zeros when the validator owner pays
         signature: [u8; 65],
 
         /// token_source
@@ -67,11 +101,569 @@ pub enum BridgeProgramInstruction {
         recipient: Address,
 
         /// amount
-        amount: u64,
+        amount: u128,
+
+        /// Is reverted transfer by user
+        revert: bool,
+
+        /// Transfer `amount` of the registered token mint from the sender token account to the escrow when
+        /// the lock is created, the sender token account, the escrow token account, the token program and
+        /// the sender signing as the token account owner follow the transaction index
+        with_token: bool,
+
+        /// Pay the tip set in the tip vault of the sender to the payer when the signature finalizes
+        /// the lock, the tip vault follows the token accounts
+        with_tip: bool,
+
+        /// Validator nonce, above the last one the validator used
+        nonce: u64,
+    },
+
+    ///Enable or disable validator
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Validator account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetValidatorActive {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+
+        /// Validator index
+        index: u64,
+
+        /// Accept signatures from the validator
+        active: bool,
+    },
+
+    ///Update contract address of registered blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    UpdateContractAddress {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// contract_address
+        contract_address: Address
+    },
+
+    ///Set fee deducted from transfers delivered to the blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetDestinationFee {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Fee in basis points
+        destination_fee_bps: u16
+    },
+
+    ///Set number of signatures required to confirm a lock
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetThreshold {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Required signatures
+        threshold: u64
+    },
+
+    ///Set maximum amount of the locks created from the blockchain in a day
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetDailyLimit {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Daily limit, 0 disables the limit
+        daily_limit: u128
+    },
+
+    ///Set minimum amount of the locks created from the blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetMinAmount {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Minimum amount
+        min_amount: u128
+    },
+
+    ///Check if validator has signed the lock or its revert, returns borsh-encoded `bool` as return
+    ///data. The answer is read from the signature account, so it is `false` again once the signature
+    ///is closed with `CloseSignature`
+    /// 0. `[R]`  Bridge account
+    /// 1. `[R]`  Signature account
+    /// 2. `[R]`  Bridge authority
+    HasValidatorSigned {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64,
+        /// Validator index
+        validator_index: u64,
+        /// Check the signature of the revert, part of the signature account seed
+        revert: bool
+    },
+
+    ///Enable or disable wrapped-token mode
+    /// 0. `[W]`  Bridge account
+    /// 1. `[RS]` Bridge owner
+    SetWrappedMode {
+        /// Require registered tokens for new locks
+        enabled: bool
+    },
+
+    ///Register token
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Token info account
+    /// 2. `[WS]` Bridge owner, pays for the token info account
+    /// 3. `[R]`  System rent variable
+    /// 4. `[R]`  System program
+    RegisterToken {
+        /// token_source
+        token_source: BlockchainId,
+        /// token_source_address
+        token_source_address: Address,
+        /// Wrapped token mint
+        mint: Pubkey
+    },
+
+    ///Check that the bridge account is initialized, used to warm up the program
+    /// 0. `[R]`  Bridge account
+    Ping,
+
+    ///Pause or unpause validator
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Validator account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner, or the guardian when pausing
+    SetValidatorPaused {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+
+        /// Validator index
+        index: u64,
+
+        /// Temporarily reject signatures from the validator
+        paused: bool,
+    },
+
+    ///Create token escrow account of the mint owned by bridge authority
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Escrow token account
+    /// 2. `[R]`  Token mint
+    /// 3. `[R]`  Bridge authority
+    /// 4. `[WS]` Bridge owner, pays for the escrow account
+    /// 5. `[R]`  System rent variable
+    /// 6. `[R]`  Token program
+    /// 7. `[R]`  System program
+    InitializeEscrow {
+        /// Token mint
+        mint: Pubkey
+    },
+
+    ///Compare escrow balance to the total amount of deposited and not withdrawn locks of the token,
+    ///returns the borsh encoded `(solvent, deficit)` pair, locks of other tokens are skipped
+    /// 0. `[R]`  Bridge account
+    /// 1. `[R]`  Token info account registering `mint`
+    /// 2. `[R]`  Escrow token account
+    /// 3+ `[R]`  Lock accounts, each passed once
+    CheckEscrowSolvency {
+        /// Token mint
+        mint: Pubkey
+    },
 
+    ///Add sender to the allowlist of the source blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Sender allowlist account
+    /// 2. `[WS]` Bridge owner, pays for the sender allowlist account
+    /// 3. `[R]`  System rent variable
+    /// 4. `[R]`  System program
+    AllowSender {
+        /// source
+        source: BlockchainId,
+        /// sender
+        sender: Address
+    },
+
+    ///Remove sender from the allowlist of the source blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Sender allowlist account
+    /// 2. `[WS]` Bridge owner, pays for the sender allowlist account
+    /// 3. `[R]`  System rent variable
+    /// 4. `[R]`  System program
+    BlockSender {
+        /// source
+        source: BlockchainId,
+        /// sender
+        sender: Address
+    },
+
+    ///Enable or disable sender allowlist of the blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetSenderAllowlistEnforced {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Accept locks only from allowed senders
+        enforced: bool
+    },
+
+    ///Add signature to the existing lock, lock params are read from the lock account
+    /// 0. `[R]`  Bridge account
+    /// 1. `[R]`  Source blockchain account
+    /// 2. `[W]`  Validator account
+    /// 3. `[W]`  Lock account
+    /// 4. `[W]`  Signature account
+    /// 5. `[R]`  Bridge authority
+    /// 6. `[WS]` Validator owner or relayer, pays for the signature account
+    /// 7. `[R]`  System rent variable
+    /// 8. `[R]`  System clock variable
+    /// 9. `[R]`  System program
+    /// 10. `[R]` Instructions sysvar, relayed signatures are checked by preceding precompile instructions
+    /// 11. `[W]` Sender user account, its lock is released once the lock is finalized
+    /// 12. `[R]` Sender user authority
+    /// 13. `[W]` Recipient user account, its lock is released once the lock is finalized
+    /// 14. `[R]` Recipient user authority
+    AddSignatureToExisting {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64,
+        /// Lock is a revert, part of the lock account seed
+        revert: bool,
+        /// Validator index
+        validator_index: u64,
+        /// Validator signature of the lock message hash, checked by a preceding precompile instruction
+        /// when relayed, ignored and stored as zeros when the validator owner pays
+        signature: [u8; 65],
+        /// Validator nonce, above the last one the validator used
+        nonce: u64
+    },
+
+    ///Set fee of the route, overrides the destination blockchain fee
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Route account
+    /// 2. `[WS]` Bridge owner, pays for the route account
+    /// 3. `[R]`  System rent variable
+    /// 4. `[R]`  System program
+    SetRouteFee {
+        /// source
+        source: BlockchainId,
+        /// destination
+        destination: BlockchainId,
+        /// Route fee in basis points, `None` falls back to the destination blockchain fee
+        fee_bps: Option<u16>
+    },
+
+    ///Set protocol fee taken from transfers sent from the blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetFee {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Fee in basis points
+        fee_bp: u16
+    },
+
+    ///Set the validator and protocol shares of the fee taken from transfers sent from the blockchain
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Blockchain account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Bridge owner
+    SetFeeSplit {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// Validator share of the fee in basis points
+        validator_fee_bp: u16,
+        /// Protocol share of the fee in basis points
+        protocol_fee_bp: u16
+    },
+
+    ///Enable or disable strict mode
+    /// 0. `[W]`  Bridge account
+    /// 1. `[RS]` Bridge owner
+    SetStrictMode {
+        /// Require token registration, sender allowlist and route for new locks
+        enabled: bool
+    },
+
+    ///Release net amount of the finalized lock from the escrow to the recipient token account,
+    ///the recipient token account has to hold the mint registered for the token of the lock
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Lock account
+    /// 2. `[R]`  Token info account of the lock token source and address
+    /// 3. `[W]`  Escrow token account
+    /// 4. `[W]`  Recipient token account
+    /// 5. `[R]`  Bridge authority
+    /// 6. `[R]`  Token program
+    /// 7. `[R]`  Clock sysvar
+    Withdraw {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64
+    },
+
+    ///Add signature confirming the revert of an existing lock, marks the sender lock record
+    ///as reverted once the revert collects the blockchain threshold
+    /// 0.  `[R]`  Bridge account
+    /// 1.  `[R]`  Source blockchain account
+    /// 2.  `[W]`  Validator account
+    /// 3.  `[R]`  Lock account
+    /// 4.  `[W]`  Revert lock account
+    /// 5.  `[W]`  Revert signature account
+    /// 6.  `[W]`  Sent lock record of the sender
+    /// 7.  `[R]`  Sender user authority
+    /// 8.  `[R]`  Bridge authority
+    /// 9.  `[WS]` Validator owner or relayer, pays for the revert lock and signature accounts
+    /// 10. `[R]`  System rent variable
+    /// 11. `[R]`  System clock variable
+    /// 12. `[R]`  System program
+    /// 13. `[R]`  Instructions sysvar, relayed signatures are checked by preceding precompile instructions
+    AddRevertSignature {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64,
+        /// Validator index
+        validator_index: u64,
+        /// Index of the sent lock record of the sender
+        sent_index: u64,
+        /// Validator signature of the lock message hash, checked by a preceding precompile instruction
+        /// when relayed, ignored and stored as zeros when the validator owner pays
+        signature: [u8; 65],
+        /// Validator nonce, above the last one the validator used
+        nonce: u64
+    },
+
+    ///Set the dispute window of the bridge, zero disables disputes
+    /// 0. `[W]`  Bridge account
+    /// 1. `[RS]` Owner account
+    SetDisputeWindow {
+        /// Seconds after a withdrawal during which it can be reverted
+        dispute_window: i64
+    },
+
+    ///Revert the withdrawal of a lock within the dispute window
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Lock account
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[RS]` Owner account
+    /// 4. `[R]`  Clock sysvar
+    RevertUnlock {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64
+    },
+
+    ///Add signatures of several validators to the lock in one transaction, the lock is created with
+    ///the first signature and the tip is paid with the finalizing one the same way as `AddSignature`
+    /// 0.  `[W]`  Bridge account
+    /// 1.  `[W]`  Source blockchain account
+    /// 2.  `[R]`  Destination blockchain account
+    /// 3.  `[R]`  Token info account
+    /// 4.  `[R]`  Sender allowlist account
+    /// 5.  `[R]`  Route account
+    /// 6.  `[W]`  Lock account
+    /// 7.  `[R]`  Bridge authority
+    /// 8.  `[W]`  Sender user account
+    /// 9.  `[R]`  Sender user authority
+    /// 10. `[W]`  Recipient user account
+    /// 11. `[R]`  Recipient user authority
+    /// 12. `[W]`  Sent lock account
+    /// 13. `[W]`  Received lock account
+    /// 14. `[WS]` Payer account
+    /// 15. `[R]`  Rent sysvar
+    /// 16. `[R]`  Clock sysvar
+    /// 17. `[R]`  System program
+    /// 18. `[R]`  Instructions sysvar, relayed signatures are checked by preceding precompile instructions
+    /// 19. `[W]`  Transaction index account, PDA of the source and tx id
+    /// 20. `[W]`  Sender token account, when `with_token`
+    /// 21. `[W]`  Escrow token account, when `with_token`
+    /// 22. `[R]`  Token program, when `with_token`
+    /// 23. `[RS]` Sender token account owner, when `with_token`
+    /// 24. `[W]`  Sender tip vault, when `with_tip`
+    /// N+2i. `[W]` Validator account of the i-th signature, N is the count of the accounts above
+    /// N+1+2i. `[W]` Signature account of the i-th signature
+    AddSignatureBatch {
+        /// token_source
+        token_source: BlockchainId,
+        /// token_source_address
+        token_source_address: Address,
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64,
+        /// tx_id
+        tx_id: TxId,
+        /// destination
+        destination: BlockchainId,
+        /// sender
+        sender: Address,
+        /// recipient
+        recipient: Address,
+        /// amount
+        amount: u128,
         /// Is reverted transfer by user
         revert: bool,
+        /// Transfer the lock amount from the sender token account to the escrow when the lock is created
+        with_token: bool,
+        /// Pay the tip of the sender tip vault to the payer when the lock is finalized
+        with_tip: bool,
+        /// Signatures with the index and nonce of the signing validator, in the order of the validator accounts
+        signatures: Vec<([u8; 65], u64, u64)>,
+    },
+
+    ///Close the signature account of a finalized lock and move its rent to the destination account
+    /// 0. `[R]`  Bridge account
+    /// 1. `[R]`  Lock account
+    /// 2. `[W]`  Signature account
+    /// 3. `[W]`  Destination account
+    /// 4. `[R]`  Bridge authority
+    /// 5. `[RS]` Owner account
+    CloseSignature {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64,
+        /// validator_index
+        validator_index: u64
+    },
+
+    ///Close the user account and move its rent to the destination account, the user signs with the
+    ///key of its address or the bridge owner signs for users of remote chains, the locks of the user
+    ///must not be collecting signatures
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  User account
+    /// 2. `[R]`  User authority
+    /// 3. `[W]`  Destination account
+    /// 4. `[RS]` User, the key bytes are the user address, or the bridge owner
+    CloseUser {
+        /// blockchain_id
+        blockchain_id: BlockchainId,
+        /// address
+        address: Address
+    },
+
+    ///Close a withdrawn lock after its dispute window and move its rent to the destination account,
+    ///the lock can't be created again as its transaction stays indexed
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Lock account
+    /// 2. `[W]`  Destination account
+    /// 3. `[R]`  Bridge authority
+    /// 4. `[RS]` Owner account
+    /// 5. `[R]`  Clock sysvar
+    CloseLock {
+        /// source
+        source: BlockchainId,
+        /// lock_id
+        lock_id: u64
+    },
+
+    ///Rewrite a bridge account of the previous version into the current layout, other account types
+    ///can't be migrated yet
+    /// 0. `[W]`  Bridge account
+    /// 1. `[WS]` Owner account, tops up the rent of the grown bridge account
+    /// 2. `[R]`  Rent sysvar
+    /// 3. `[R]`  System program
+    MigrateAccount,
+
+    ///Add several blockchains, at most `ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES`
+    /// 0. `[W]`  Bridge account
+    /// 1. `[WS]` Bridge owner, pays for the blockchain accounts
+    /// 2. `[R]`  Bridge authority
+    /// 3. `[R]`  Rent sysvar
+    /// 4. `[R]`  System program
+    /// 5.. `[W]` Blockchain accounts, in the order of the entries
+    AddBlockchainBatch {
+        /// Blockchain id, contract address and max validators of each blockchain
+        entries: Vec<(BlockchainId, Address, u64)>
+    },
+
+    ///Set the guardian that can pause validators, the default pubkey removes the guardian
+    /// 0. `[W]`  Bridge account
+    /// 1. `[RS]` Bridge owner
+    SetGuardian {
+        /// Guardian account
+        guardian: Pubkey
+    },
+
+    ///Set the blockchain id of Solana, zero disables the recipient check of locks to Solana
+    /// 0. `[W]`  Bridge account
+    /// 1. `[RS]` Bridge owner
+    SetSolanaChainId {
+        /// Solana blockchain id
+        solana_chain_id: BlockchainId
     },
+
+    ///Set the relayer tip of the sender locks and deposit lamports to pay it, the vault is created by
+    ///the first call and only its funder can change the tip afterwards
+    /// 0. `[R]`  Bridge account
+    /// 1. `[W]`  Tip vault account
+    /// 2. `[WS]` Funder, pays for the tip vault account and the deposit
+    /// 3. `[R]`  System rent variable
+    /// 4. `[R]`  System program
+    FundTipVault {
+        /// source
+        source: BlockchainId,
+        /// sender
+        sender: Address,
+        /// Lamports paid to the relayer finalizing a lock of the sender
+        tip: u64,
+        /// Lamports moved from the funder to the vault
+        deposit: u64
+    },
+}
+
+/// Blockchain id of a builder argument, empty and too long ids are rejected
+fn chain_id(blockchain_id: &str) -> Result<BlockchainId, ProgramError> {
+    if blockchain_id.is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    str_to_chain_id(blockchain_id)
+}
+
+/// Reject the zero address
+fn check_address(address: &Address) -> Result<(), ProgramError> {
+    if *address == Address::default() {
+        return Err(SolBridgeProgramError::InvalidAddress.into());
+    }
+    Ok(())
+}
+
+/// Reject fees above 100%
+fn check_fee_bps(fee_bps: u16) -> Result<(), ProgramError> {
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return Err(SolBridgeProgramError::InvalidFee.into());
+    }
+    Ok(())
+}
+
+/// Reject lock params the program refuses to create a lock for
+fn check_lock_params(source: &BlockchainId, destination: &BlockchainId, sender: &Address, recipient: &Address, amount: u128) -> Result<(), ProgramError> {
+    if amount == 0 {
+        return Err(SolBridgeProgramError::InvalidAmount.into());
+    }
+    if *sender == Address::default() && *recipient == Address::default() {
+        return Err(SolBridgeProgramError::InvalidAddress.into());
+    }
+    if source == destination {
+        return Err(SolBridgeProgramError::SameSourceAndDestination.into());
+    }
+    Ok(())
 }
 
 /// Create `InitBridge` instruction
@@ -101,23 +693,61 @@ pub fn add_blockchain(
     program_id: &Pubkey,
     bridge_account: &Pubkey,
     blockchain_account: &Pubkey,
-    payer_account: &Pubkey,
+    owner_account: &Pubkey,
     bridge_authority: &Pubkey,
     blockchain_id: String,
-    contract_address: Address
+    contract_address: Address,
+    max_validators: u64
 ) -> Result<Instruction, ProgramError> {
-    let init_data = BridgeProgramInstruction::AddBlockchain {blockchain_id: str_to_chain_id(blockchain_id.as_str()), contract_address};
+    check_address(&contract_address)?;
+    let init_data = BridgeProgramInstruction::AddBlockchain {blockchain_id: chain_id(&blockchain_id)?, contract_address, max_validators};
     let data = init_data
         .try_to_vec()
         .or(Err(ProgramError::InvalidArgument))?;
     let accounts = vec![
         AccountMeta::new(*bridge_account, false),
         AccountMeta::new(*blockchain_account, false),
-        AccountMeta::new_readonly(*payer_account, true),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `AddBlockchainBatch` instruction
+pub fn add_blockchain_batch(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    entries: &[(String, Address, u64)]
+) -> Result<Instruction, ProgramError> {
+    if entries.is_empty() || entries.len() > ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new(*owner_account, true),
         AccountMeta::new_readonly(*bridge_authority, false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
     ];
+    let mut batch_entries = Vec::with_capacity(entries.len());
+    for (blockchain_id, contract_address, max_validators) in entries {
+        check_address(contract_address)?;
+        let blockchain_id = chain_id(blockchain_id)?;
+        accounts.push(AccountMeta::new(pda::derive_blockchain(program_id, bridge_account, blockchain_id)?, false));
+        batch_entries.push((blockchain_id, *contract_address, *max_validators));
+    }
+    let init_data = BridgeProgramInstruction::AddBlockchainBatch {entries: batch_entries};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -131,12 +761,15 @@ pub fn add_validator(
     bridge_account: &Pubkey,
     blockchain_account: &Pubkey,
     validator_account: &Pubkey,
-    payer_account: &Pubkey,
+    owner_account: &Pubkey,
     bridge_authority: &Pubkey,
+    validator_owner: &Pubkey,
     blockchain_id: String,
-    pub_key: [u8; 32]
+    pub_key: [u8; 32],
+    key_type: KeyType
 ) -> Result<Instruction, ProgramError> {
-    let init_data = BridgeProgramInstruction::AddValidator {blockchain_id: str_to_chain_id(blockchain_id.as_str()), pub_key};
+    check_address(&pub_key)?;
+    let init_data = BridgeProgramInstruction::AddValidator {blockchain_id: chain_id(&blockchain_id)?, pub_key, key_type: key_type as u8};
     let data = init_data
         .try_to_vec()
         .or(Err(ProgramError::InvalidArgument))?;
@@ -144,10 +777,11 @@ pub fn add_validator(
         AccountMeta::new(*bridge_account, false),
         AccountMeta::new(*blockchain_account, false),
         AccountMeta::new(*validator_account, false),
-        AccountMeta::new_readonly(*payer_account, true),
+        AccountMeta::new(*owner_account, true),
         AccountMeta::new_readonly(*bridge_authority, false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*validator_owner, false),
     ];
     Ok(Instruction {
         program_id: *program_id,
@@ -156,11 +790,146 @@ pub fn add_validator(
     })
 }
 
+/// Accounts of the `AddSignature` instruction
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AddSignatureAccounts {
+    /// Bridge account
+    pub bridge: Pubkey,
+    /// Source blockchain account
+    pub blockchain: Pubkey,
+    /// Destination blockchain account
+    pub destination_blockchain: Pubkey,
+    /// Token info account
+    pub token_info: Pubkey,
+    /// Sender allowlist account
+    pub sender_allowed: Pubkey,
+    /// Route account
+    pub route: Pubkey,
+    /// Validator account
+    pub validator: Pubkey,
+    /// Lock account
+    pub lock: Pubkey,
+    /// Signature account
+    pub signature: Pubkey,
+    /// Bridge authority
+    pub bridge_authority: Pubkey,
+    /// Sender user account
+    pub sender_user: Pubkey,
+    /// Sender user authority
+    pub sender_user_authority: Pubkey,
+    /// Recipient user account
+    pub recipient_user: Pubkey,
+    /// Recipient user authority
+    pub recipient_user_authority: Pubkey,
+    /// Sender's user lock tx account
+    pub sent_lock: Pubkey,
+    /// Recipient's user lock tx account
+    pub received_lock: Pubkey,
+    /// Payer, signer
+    pub payer: Pubkey,
+    /// Transaction index, see `utils::get_tx_index_address`
+    pub tx_index: Pubkey,
+    /// Sender token account, escrow account and the signing owner of the sender token account,
+    /// when the lock moves tokens
+    pub token_accounts: Option<(Pubkey, Pubkey, Pubkey)>,
+    /// Sender tip vault, when the relayer tip is claimed
+    pub tip_vault: Option<Pubkey>,
+}
+
+impl AddSignatureAccounts {
+    /// Account metas in the order expected by the processor
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.bridge, false),
+            AccountMeta::new(self.blockchain, false),
+            AccountMeta::new_readonly(self.destination_blockchain, false),
+            AccountMeta::new_readonly(self.token_info, false),
+            AccountMeta::new_readonly(self.sender_allowed, false),
+            AccountMeta::new_readonly(self.route, false),
+            AccountMeta::new(self.validator, false),
+            AccountMeta::new(self.lock, false),
+            AccountMeta::new(self.signature, false),
+            AccountMeta::new_readonly(self.bridge_authority, false),
+            AccountMeta::new(self.sender_user, false),
+            AccountMeta::new_readonly(self.sender_user_authority, false),
+            AccountMeta::new(self.recipient_user, false),
+            AccountMeta::new_readonly(self.recipient_user_authority, false),
+            AccountMeta::new(self.sent_lock, false),
+            AccountMeta::new(self.received_lock, false),
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new(self.tx_index, false)
+        ];
+        if let Some((sender_token_account, escrow_account, sender_token_owner)) = self.token_accounts {
+            accounts.push(AccountMeta::new(sender_token_account, false));
+            accounts.push(AccountMeta::new(escrow_account, false));
+            accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+            accounts.push(AccountMeta::new_readonly(sender_token_owner, true));
+        }
+        if let Some(tip_vault) = self.tip_vault {
+            accounts.push(AccountMeta::new(tip_vault, false));
+        }
+        accounts
+    }
+}
+
+/// Create `AddSignature` instruction from named accounts
+pub fn add_signature_with_accounts(
+    program_id: &Pubkey,
+    accounts: &AddSignatureAccounts,
+    signature: [u8; 65],
+    token_source: String,
+    token_source_address: Address,
+    source: String,
+    tx_id: TxId,
+    lock_id: u64,
+    destination: String,
+    sender: Address,
+    recipient: Address,
+    amount: u128,
+    revert: bool,
+    nonce: u64
+) -> Result<Instruction, ProgramError> {
+    let source = chain_id(&source)?;
+    let destination = chain_id(&destination)?;
+    check_lock_params(&source, &destination, &sender, &recipient, amount)?;
+    let init_data = BridgeProgramInstruction::AddSignature {
+        signature, token_source: chain_id(&token_source)?,
+        token_source_address,
+        source,
+        tx_id,
+        lock_id,
+        destination,
+        sender,
+        recipient,
+        amount,
+        revert,
+        with_token: accounts.token_accounts.is_some(),
+        with_tip: accounts.tip_vault.is_some(),
+        nonce
+    };
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(),
+        data,
+    })
+}
+
 /// Create `AddSignature` instruction
 pub fn add_signature(
     program_id: &Pubkey,
     bridge_account: &Pubkey,
     blockchain_account: &Pubkey,
+    destination_blockchain_account: &Pubkey,
+    token_info_account: &Pubkey,
+    sender_allowed_account: &Pubkey,
+    route_account: &Pubkey,
     validator_account: &Pubkey,
     lock_account: &Pubkey,
     signature_account: &Pubkey,
@@ -181,30 +950,955 @@ pub fn add_signature(
     destination: String,
     sender: Address,
     recipient: Address,
-    amount: u64,
-    revert: bool
+    amount: u128,
+    revert: bool,
+    token_accounts: Option<(&Pubkey, &Pubkey, &Pubkey)>,
+    tip_vault_account: Option<&Pubkey>,
+    nonce: u64
 ) -> Result<Instruction, ProgramError> {
-    let init_data = BridgeProgramInstruction::AddSignature {
-        signature, token_source: str_to_chain_id(token_source.as_str()),
+    let accounts = AddSignatureAccounts {
+        bridge: *bridge_account,
+        blockchain: *blockchain_account,
+        destination_blockchain: *destination_blockchain_account,
+        token_info: *token_info_account,
+        sender_allowed: *sender_allowed_account,
+        route: *route_account,
+        validator: *validator_account,
+        lock: *lock_account,
+        signature: *signature_account,
+        bridge_authority: *bridge_authority,
+        sender_user: *sender_user,
+        sender_user_authority: *sender_user_authority,
+        recipient_user: *recipient_user,
+        recipient_user_authority: *recipient_user_authority,
+        sent_lock: *sent_lock,
+        received_lock: *received_lock,
+        payer: *payer_account,
+        tx_index: get_tx_index_address(program_id, bridge_account, &chain_id(&source)?, &tx_id).0,
+        token_accounts: token_accounts.map(|(sender_token_account, escrow_account, sender_token_owner)| (*sender_token_account, *escrow_account, *sender_token_owner)),
+        tip_vault: tip_vault_account.copied(),
+    };
+    add_signature_with_accounts(
+        program_id,
+        &accounts,
+        signature,
+        token_source,
         token_source_address,
-        source: str_to_chain_id(source.as_str()),
+        source,
         tx_id,
         lock_id,
-        destination: str_to_chain_id(destination.as_str()),
+        destination,
         sender,
         recipient,
         amount,
-        revert
+        revert,
+        nonce
+    )
+}
+
+/// Accounts created or updated by `AddSignature`, as derived by `add_signature_with_pdas`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AddSignaturePdas {
+    /// Lock account
+    pub lock: Pubkey,
+    /// Signature account
+    pub signature: Pubkey,
+    /// Sender user account
+    pub sender_user: Pubkey,
+    /// Recipient user account
+    pub recipient_user: Pubkey,
+    /// Sender's user lock tx account
+    pub sent_lock: Pubkey,
+    /// Recipient's user lock tx account
+    pub received_lock: Pubkey,
+    /// Transaction index
+    pub tx_index: Pubkey,
+}
+
+/// Create `AddSignature` instruction deriving all program accounts, `sent_index` and
+/// `received_index` are the current `sent` and `received` counters of the sender and
+/// recipient users, zero for users that don't exist yet
+pub fn add_signature_with_pdas(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    payer_account: &Pubkey,
+    validator_index: u64,
+    sent_index: u64,
+    received_index: u64,
+    signature: [u8; 65],
+    token_source: String,
+    token_source_address: Address,
+    source: String,
+    tx_id: TxId,
+    lock_id: u64,
+    destination: String,
+    sender: Address,
+    recipient: Address,
+    amount: u128,
+    revert: bool,
+    token_accounts: Option<(&Pubkey, &Pubkey, &Pubkey)>,
+    with_tip: bool,
+    nonce: u64
+) -> Result<(Instruction, AddSignaturePdas), ProgramError> {
+    let token_source_id = chain_id(&token_source)?;
+    let source_id = chain_id(&source)?;
+    let destination_id = chain_id(&destination)?;
+
+    let pdas = AddSignaturePdas {
+        lock: pda::derive_lock(program_id, bridge_account, source_id, lock_id, revert)?,
+        signature: pda::derive_signature(program_id, bridge_account, source_id, lock_id, validator_index, revert)?,
+        sender_user: pda::derive_user(program_id, &sender, source_id)?,
+        recipient_user: pda::derive_user(program_id, &recipient, destination_id)?,
+        sent_lock: pda::derive_lock_tx(program_id, &sender, source_id, sent_index, "sent")?,
+        received_lock: pda::derive_lock_tx(program_id, &recipient, destination_id, received_index, "received")?,
+        tx_index: get_tx_index_address(program_id, bridge_account, &source_id, &tx_id).0,
     };
-    let data = init_data
-        .try_to_vec()
-        .or(Err(ProgramError::InvalidArgument))?;
-    let accounts = vec![
-        AccountMeta::new(*bridge_account, false),
-        AccountMeta::new(*blockchain_account, false),
-        AccountMeta::new(*validator_account, false),
-        AccountMeta::new(*lock_account, false),
+    let accounts = AddSignatureAccounts {
+        bridge: *bridge_account,
+        blockchain: pda::derive_blockchain(program_id, bridge_account, source_id)?,
+        destination_blockchain: pda::derive_blockchain(program_id, bridge_account, destination_id)?,
+        token_info: get_token_info_address(program_id, bridge_account, &token_source_id, &token_source_address).0,
+        sender_allowed: get_sender_allowed_address(program_id, bridge_account, &source_id, &sender).0,
+        route: get_route_address(program_id, bridge_account, &source_id, &destination_id).0,
+        validator: pda::derive_validator(program_id, bridge_account, source_id, validator_index)?,
+        lock: pdas.lock,
+        signature: pdas.signature,
+        bridge_authority: pda::get_bridge_authority(program_id, bridge_account).0,
+        sender_user: pdas.sender_user,
+        sender_user_authority: pda::user_authority(program_id, &sender).0,
+        recipient_user: pdas.recipient_user,
+        recipient_user_authority: pda::user_authority(program_id, &recipient).0,
+        sent_lock: pdas.sent_lock,
+        received_lock: pdas.received_lock,
+        payer: *payer_account,
+        tx_index: pdas.tx_index,
+        token_accounts: token_accounts.map(|(sender_token_account, escrow_account, sender_token_owner)| (*sender_token_account, *escrow_account, *sender_token_owner)),
+        tip_vault: if with_tip { Some(get_tip_vault_address(program_id, bridge_account, &source_id, &sender).0) } else { None },
+    };
+    let instruction = add_signature_with_accounts(
+        program_id,
+        &accounts,
+        signature,
+        token_source,
+        token_source_address,
+        source,
+        tx_id,
+        lock_id,
+        destination,
+        sender,
+        recipient,
+        amount,
+        revert,
+        nonce
+    )?;
+    Ok((instruction, pdas))
+}
+
+/// Create `SetValidatorActive` instruction
+pub fn set_validator_active(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    validator_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    index: u64,
+    active: bool
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetValidatorActive {blockchain_id: chain_id(&blockchain_id)?, index, active};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*validator_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `UpdateContractAddress` instruction
+pub fn update_contract_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    contract_address: Address
+) -> Result<Instruction, ProgramError> {
+    check_address(&contract_address)?;
+    let init_data = BridgeProgramInstruction::UpdateContractAddress {blockchain_id: chain_id(&blockchain_id)?, contract_address};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetDestinationFee` instruction
+pub fn set_destination_fee(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    destination_fee_bps: u16
+) -> Result<Instruction, ProgramError> {
+    check_fee_bps(destination_fee_bps)?;
+    let init_data = BridgeProgramInstruction::SetDestinationFee {blockchain_id: chain_id(&blockchain_id)?, destination_fee_bps};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetThreshold` instruction
+pub fn set_threshold(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    threshold: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetThreshold {blockchain_id: chain_id(&blockchain_id)?, threshold};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetDailyLimit` instruction
+pub fn set_daily_limit(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    daily_limit: u128
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetDailyLimit {blockchain_id: chain_id(&blockchain_id)?, daily_limit};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetMinAmount` instruction
+pub fn set_min_amount(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    min_amount: u128
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetMinAmount {blockchain_id: chain_id(&blockchain_id)?, min_amount};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `HasValidatorSigned` instruction
+pub fn has_validator_signed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    signature_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    source: String,
+    lock_id: u64,
+    validator_index: u64,
+    revert: bool
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::HasValidatorSigned {source: chain_id(&source)?, lock_id, validator_index, revert};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new_readonly(*signature_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetWrappedMode` instruction
+pub fn set_wrapped_mode(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+    enabled: bool
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetWrappedMode {enabled};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `RegisterToken` instruction
+pub fn register_token(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    token_info_account: &Pubkey,
+    owner_account: &Pubkey,
+    token_source: String,
+    token_source_address: Address,
+    mint: Pubkey
+) -> Result<Instruction, ProgramError> {
+    check_address(&token_source_address)?;
+    let init_data = BridgeProgramInstruction::RegisterToken {token_source: chain_id(&token_source)?, token_source_address, mint};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*token_info_account, false),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `Ping` instruction
+pub fn ping(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::Ping;
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetValidatorPaused` instruction
+pub fn set_validator_paused(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    validator_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    index: u64,
+    paused: bool
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetValidatorPaused {blockchain_id: chain_id(&blockchain_id)?, index, paused};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*validator_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `InitializeEscrow` instruction
+pub fn initialize_escrow(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    escrow_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    mint: Pubkey
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::InitializeEscrow {mint};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*escrow_account, false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `CheckEscrowSolvency` instruction
+pub fn check_escrow_solvency(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    token_info_account: &Pubkey,
+    escrow_account: &Pubkey,
+    lock_accounts: &[Pubkey],
+    mint: Pubkey
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::CheckEscrowSolvency {mint};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new_readonly(*token_info_account, false),
+        AccountMeta::new_readonly(*escrow_account, false),
+    ];
+    accounts.extend(lock_accounts.iter().map(|lock_account| AccountMeta::new_readonly(*lock_account, false)));
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `AllowSender` instruction
+pub fn allow_sender(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    sender_allowed_account: &Pubkey,
+    owner_account: &Pubkey,
+    source: String,
+    sender: Address
+) -> Result<Instruction, ProgramError> {
+    check_address(&sender)?;
+    let init_data = BridgeProgramInstruction::AllowSender {source: chain_id(&source)?, sender};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*sender_allowed_account, false),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `BlockSender` instruction
+pub fn block_sender(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    sender_allowed_account: &Pubkey,
+    owner_account: &Pubkey,
+    source: String,
+    sender: Address
+) -> Result<Instruction, ProgramError> {
+    check_address(&sender)?;
+    let init_data = BridgeProgramInstruction::BlockSender {source: chain_id(&source)?, sender};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*sender_allowed_account, false),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetSenderAllowlistEnforced` instruction
+pub fn set_sender_allowlist_enforced(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    enforced: bool
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetSenderAllowlistEnforced {blockchain_id: chain_id(&blockchain_id)?, enforced};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `AddSignatureToExisting` instruction
+pub fn add_signature_to_existing(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    validator_account: &Pubkey,
+    lock_account: &Pubkey,
+    signature_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    payer_account: &Pubkey,
+    sender_user: &Pubkey,
+    sender_user_authority: &Pubkey,
+    recipient_user: &Pubkey,
+    recipient_user_authority: &Pubkey,
+    source: String,
+    lock_id: u64,
+    revert: bool,
+    validator_index: u64,
+    signature: [u8; 65],
+    nonce: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::AddSignatureToExisting {
+        source: chain_id(&source)?,
+        lock_id,
+        revert,
+        validator_index,
+        signature,
+        nonce
+    };
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new_readonly(*blockchain_account, false),
+        AccountMeta::new(*validator_account, false),
+        AccountMeta::new(*lock_account, false),
+        AccountMeta::new(*signature_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new(*payer_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(*sender_user, false),
+        AccountMeta::new_readonly(*sender_user_authority, false),
+        AccountMeta::new(*recipient_user, false),
+        AccountMeta::new_readonly(*recipient_user_authority, false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetRouteFee` instruction
+pub fn set_route_fee(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    route_account: &Pubkey,
+    owner_account: &Pubkey,
+    source: String,
+    destination: String,
+    fee_bps: Option<u16>
+) -> Result<Instruction, ProgramError> {
+    if let Some(fee_bps) = fee_bps {
+        check_fee_bps(fee_bps)?;
+    }
+    let init_data = BridgeProgramInstruction::SetRouteFee {
+        source: chain_id(&source)?,
+        destination: chain_id(&destination)?,
+        fee_bps
+    };
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*route_account, false),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetFee` instruction
+pub fn set_fee(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    fee_bp: u16
+) -> Result<Instruction, ProgramError> {
+    check_fee_bps(fee_bp)?;
+    let init_data = BridgeProgramInstruction::SetFee {blockchain_id: chain_id(&blockchain_id)?, fee_bp};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetFeeSplit` instruction
+pub fn set_fee_split(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    blockchain_id: String,
+    validator_fee_bp: u16,
+    protocol_fee_bp: u16
+) -> Result<Instruction, ProgramError> {
+    check_fee_bps(validator_fee_bp.checked_add(protocol_fee_bp).ok_or(SolBridgeProgramError::InvalidFee)?)?;
+    let init_data = BridgeProgramInstruction::SetFeeSplit {blockchain_id: chain_id(&blockchain_id)?, validator_fee_bp, protocol_fee_bp};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetStrictMode` instruction
+pub fn set_strict_mode(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+    enabled: bool
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetStrictMode {enabled};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `Withdraw` instruction
+pub fn withdraw(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    lock_account: &Pubkey,
+    token_info_account: &Pubkey,
+    escrow_account: &Pubkey,
+    recipient_token_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    source: String,
+    lock_id: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::Withdraw {source: chain_id(&source)?, lock_id};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*lock_account, false),
+        AccountMeta::new_readonly(*token_info_account, false),
+        AccountMeta::new(*escrow_account, false),
+        AccountMeta::new(*recipient_token_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `AddRevertSignature` instruction
+pub fn add_revert_signature(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    validator_account: &Pubkey,
+    lock_account: &Pubkey,
+    revert_lock_account: &Pubkey,
+    signature_account: &Pubkey,
+    sent_lock: &Pubkey,
+    sender_user_authority: &Pubkey,
+    bridge_authority: &Pubkey,
+    payer_account: &Pubkey,
+    source: String,
+    lock_id: u64,
+    validator_index: u64,
+    sent_index: u64,
+    signature: [u8; 65],
+    nonce: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::AddRevertSignature {
+        source: chain_id(&source)?,
+        lock_id,
+        validator_index,
+        sent_index,
+        signature,
+        nonce
+    };
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new_readonly(*blockchain_account, false),
+        AccountMeta::new(*validator_account, false),
+        AccountMeta::new_readonly(*lock_account, false),
+        AccountMeta::new(*revert_lock_account, false),
         AccountMeta::new(*signature_account, false),
+        AccountMeta::new(*sent_lock, false),
+        AccountMeta::new_readonly(*sender_user_authority, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new(*payer_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetDisputeWindow` instruction
+pub fn set_dispute_window(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+    dispute_window: i64
+) -> Result<Instruction, ProgramError> {
+    if dispute_window < 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let init_data = BridgeProgramInstruction::SetDisputeWindow {dispute_window};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `RevertUnlock` instruction
+pub fn revert_unlock(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    lock_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    source: String,
+    lock_id: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::RevertUnlock {source: chain_id(&source)?, lock_id};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*lock_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `AddSignatureBatch` instruction, `validator_accounts` holds the validator and signature
+/// accounts for each of the `signatures`, `token_accounts` and `tip_vault_account` as in `add_signature`
+pub fn add_signature_batch(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    blockchain_account: &Pubkey,
+    destination_blockchain_account: &Pubkey,
+    token_info_account: &Pubkey,
+    sender_allowed_account: &Pubkey,
+    route_account: &Pubkey,
+    lock_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    sender_user: &Pubkey,
+    sender_user_authority: &Pubkey,
+    recipient_user: &Pubkey,
+    recipient_user_authority: &Pubkey,
+    sent_lock: &Pubkey,
+    received_lock: &Pubkey,
+    payer_account: &Pubkey,
+    validator_accounts: &[(Pubkey, Pubkey)],
+    signatures: Vec<([u8; 65], u64, u64)>,
+    token_source: String,
+    token_source_address: Address,
+    source: String,
+    tx_id: TxId,
+    lock_id: u64,
+    destination: String,
+    sender: Address,
+    recipient: Address,
+    amount: u128,
+    revert: bool,
+    token_accounts: Option<(&Pubkey, &Pubkey, &Pubkey)>,
+    tip_vault_account: Option<&Pubkey>
+) -> Result<Instruction, ProgramError> {
+    if signatures.is_empty() || signatures.len() != validator_accounts.len() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let source = chain_id(&source)?;
+    let destination = chain_id(&destination)?;
+    check_lock_params(&source, &destination, &sender, &recipient, amount)?;
+    let init_data = BridgeProgramInstruction::AddSignatureBatch {
+        token_source: chain_id(&token_source)?,
+        token_source_address,
+        source,
+        lock_id,
+        tx_id,
+        destination,
+        sender,
+        recipient,
+        amount,
+        revert,
+        with_token: token_accounts.is_some(),
+        with_tip: tip_vault_account.is_some(),
+        signatures
+    };
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let mut accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new(*blockchain_account, false),
+        AccountMeta::new_readonly(*destination_blockchain_account, false),
+        AccountMeta::new_readonly(*token_info_account, false),
+        AccountMeta::new_readonly(*sender_allowed_account, false),
+        AccountMeta::new_readonly(*route_account, false),
+        AccountMeta::new(*lock_account, false),
         AccountMeta::new_readonly(*bridge_authority, false),
         AccountMeta::new(*sender_user, false),
         AccountMeta::new_readonly(*sender_user_authority, false),
@@ -212,9 +1906,217 @@ pub fn add_signature(
         AccountMeta::new_readonly(*recipient_user_authority, false),
         AccountMeta::new(*sent_lock, false),
         AccountMeta::new(*received_lock, false),
-        AccountMeta::new_readonly(*payer_account, true),
+        AccountMeta::new(*payer_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(get_tx_index_address(program_id, bridge_account, &source, &tx_id).0, false)
+    ];
+    if let Some((sender_token_account, escrow_account, sender_token_owner)) = token_accounts {
+        accounts.push(AccountMeta::new(*sender_token_account, false));
+        accounts.push(AccountMeta::new(*escrow_account, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+        accounts.push(AccountMeta::new_readonly(*sender_token_owner, true));
+    }
+    if let Some(tip_vault) = tip_vault_account {
+        accounts.push(AccountMeta::new(*tip_vault, false));
+    }
+    for (validator_account, signature_account) in validator_accounts {
+        accounts.push(AccountMeta::new(*validator_account, false));
+        accounts.push(AccountMeta::new(*signature_account, false));
+    }
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `CloseSignature` instruction
+pub fn close_signature(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    lock_account: &Pubkey,
+    signature_account: &Pubkey,
+    destination_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    source: String,
+    lock_id: u64,
+    validator_index: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::CloseSignature {source: chain_id(&source)?, lock_id, validator_index};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new_readonly(*lock_account, false),
+        AccountMeta::new(*signature_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `CloseUser` instruction, the signer is the user or the bridge owner
+pub fn close_user(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    user_account: &Pubkey,
+    user_authority: &Pubkey,
+    destination_account: &Pubkey,
+    signer_account: &Pubkey,
+    blockchain_id: String,
+    address: Address
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::CloseUser {blockchain_id: chain_id(&blockchain_id)?, address};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*user_account, false),
+        AccountMeta::new_readonly(*user_authority, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*signer_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `CloseLock` instruction
+pub fn close_lock(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    lock_account: &Pubkey,
+    destination_account: &Pubkey,
+    bridge_authority: &Pubkey,
+    owner_account: &Pubkey,
+    source: String,
+    lock_id: u64
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::CloseLock {source: chain_id(&source)?, lock_id};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(*lock_account, false),
+        AccountMeta::new(*destination_account, false),
+        AccountMeta::new_readonly(*bridge_authority, false),
+        AccountMeta::new_readonly(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `MigrateAccount` instruction
+pub fn migrate_account(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::MigrateAccount;
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new(*owner_account, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetGuardian` instruction
+pub fn set_guardian(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+    guardian: Pubkey
+) -> Result<Instruction, ProgramError> {
+    let init_data = BridgeProgramInstruction::SetGuardian {guardian};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `SetSolanaChainId` instruction, `None` disables the recipient check
+pub fn set_solana_chain_id(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    owner_account: &Pubkey,
+    solana_chain_id: Option<String>
+) -> Result<Instruction, ProgramError> {
+    let solana_chain_id = match solana_chain_id {
+        Some(solana_chain_id) => chain_id(&solana_chain_id)?,
+        None => BlockchainId::default(),
+    };
+    let init_data = BridgeProgramInstruction::SetSolanaChainId {solana_chain_id};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new(*bridge_account, false),
+        AccountMeta::new_readonly(*owner_account, true),
+    ];
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create `FundTipVault` instruction
+pub fn fund_tip_vault(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    funder_account: &Pubkey,
+    source: String,
+    sender: Address,
+    tip: u64,
+    deposit: u64
+) -> Result<Instruction, ProgramError> {
+    check_address(&sender)?;
+    let source = chain_id(&source)?;
+    let init_data = BridgeProgramInstruction::FundTipVault {source, sender, tip, deposit};
+    let data = init_data
+        .try_to_vec()
+        .or(Err(ProgramError::InvalidArgument))?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*bridge_account, false),
+        AccountMeta::new(get_tip_vault_address(program_id, bridge_account, &source, &sender).0, false),
+        AccountMeta::new(*funder_account, true),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
-        AccountMeta::new_readonly(system_program::id(), false)
+        AccountMeta::new_readonly(system_program::id(), false),
     ];
     Ok(Instruction {
         program_id: *program_id,
@@ -3,13 +3,21 @@
 //! A minimal Solana program template
 
 pub mod error;
+pub mod events;
 pub mod instruction;
+pub mod pda;
 pub mod processor;
 pub mod state;
 pub mod utils;
 
+#[cfg(feature = "client")]
+pub mod client;
+
 /// Current program version
-pub const PROGRAM_VERSION: u8 = 1;
+pub const PROGRAM_VERSION: u8 = 2;
+
+/// Version of accounts written by the previous release, upgraded by `MigrateAccount`
+pub const PREVIOUS_VERSION: u8 = 1;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
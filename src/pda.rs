@@ -0,0 +1,54 @@
+//! Program derived addresses of the bridge accounts, for use by clients
+
+use crate::solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use crate::state::{Address, BlockchainId};
+use crate::utils::{
+    blockchain_account_seed,
+    validator_account_seed,
+    lock_account_seed,
+    signature_account_seed,
+    user_account_seed,
+    lock_tx_account_seed,
+};
+
+/// Bridge authority, owner of the blockchain, validator, lock and signature accounts
+pub use crate::utils::get_bridge_authority;
+
+/// User authority, owner of the user and lock tx accounts
+pub use crate::utils::user_authority;
+
+/// Blockchain account of `blockchain_id`
+pub fn derive_blockchain(program_id: &Pubkey, bridge: &Pubkey, blockchain_id: BlockchainId) -> Result<Pubkey, ProgramError> {
+    let (bridge_authority, _) = get_bridge_authority(program_id, bridge);
+    Ok(Pubkey::create_with_seed(&bridge_authority, &blockchain_account_seed(blockchain_id)?, program_id)?)
+}
+
+/// Validator account `index` of `blockchain_id`
+pub fn derive_validator(program_id: &Pubkey, bridge: &Pubkey, blockchain_id: BlockchainId, index: u64) -> Result<Pubkey, ProgramError> {
+    let (bridge_authority, _) = get_bridge_authority(program_id, bridge);
+    Ok(Pubkey::create_with_seed(&bridge_authority, &validator_account_seed(blockchain_id, index)?, program_id)?)
+}
+
+/// Lock account of `lock_id` from `source`, `revert` selects the revert lock
+pub fn derive_lock(program_id: &Pubkey, bridge: &Pubkey, source: BlockchainId, lock_id: u64, revert: bool) -> Result<Pubkey, ProgramError> {
+    let (bridge_authority, _) = get_bridge_authority(program_id, bridge);
+    Ok(Pubkey::create_with_seed(&bridge_authority, &lock_account_seed(source, lock_id, revert)?, program_id)?)
+}
+
+/// Signature account of validator `validator_index` for a lock
+pub fn derive_signature(program_id: &Pubkey, bridge: &Pubkey, source: BlockchainId, lock_id: u64, validator_index: u64, revert: bool) -> Result<Pubkey, ProgramError> {
+    let (bridge_authority, _) = get_bridge_authority(program_id, bridge);
+    Ok(Pubkey::create_with_seed(&bridge_authority, &signature_account_seed(source, lock_id, validator_index, revert)?, program_id)?)
+}
+
+/// User account of `user_address` on `blockchain_id`
+pub fn derive_user(program_id: &Pubkey, user_address: &Address, blockchain_id: BlockchainId) -> Result<Pubkey, ProgramError> {
+    let (authority, _) = user_authority(program_id, user_address);
+    Ok(Pubkey::create_with_seed(&authority, &user_account_seed(blockchain_id)?, program_id)?)
+}
+
+/// Sent or received lock record, `tx_type` is "sent" or "received"
+pub fn derive_lock_tx(program_id: &Pubkey, user_address: &Address, blockchain_id: BlockchainId, index: u64, tx_type: &str) -> Result<Pubkey, ProgramError> {
+    let (authority, _) = user_authority(program_id, user_address);
+    Ok(Pubkey::create_with_seed(&authority, &lock_tx_account_seed(blockchain_id, index, tx_type)?, program_id)?)
+}
@@ -1,9 +1,13 @@
 //! Program state processor
 
 use crate::{
-    instruction::BridgeProgramInstruction,
-    state::{Bridge, Blockchain, Validator, Lock, Signature, User, LockTx, BlockchainId, Address, TxId},
-    utils::*
+    error::SolBridgeProgramError,
+    events::{BridgeEvent, LockClosedEvent, LockCreatedEvent, SignatureAddedEvent, UserCreatedEvent},
+    instruction::{BridgeProgramInstruction, ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES},
+    state::{AccountType, Bridge, BridgeV1, Blockchain, Validator, Lock, Signature, User, LockTx, TokenInfo, SenderAllowed, Route, TxIndex, TipVault, Packed, Versioned, KeyType, BlockchainId, Address, TxId},
+    utils::*,
+    PREVIOUS_VERSION,
+    PROGRAM_VERSION
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
@@ -11,17 +15,30 @@ use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
     msg,
+    program::{invoke, invoke_signed, set_return_data},
+    program_pack::Pack,
     program_error::ProgramError,
     pubkey::Pubkey,
-    sysvar::{rent::Rent, Sysvar},
+    system_instruction,
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
+use std::convert::TryFrom;
+
+/// Accounts of `AddSignatureBatch` preceding the validator and signature accounts
+const ADD_SIGNATURE_BATCH_LOCK_ACCOUNTS: usize = 20;
+
+/// Sender token account, escrow account, token program and sender token owner of a token lock
+const ADD_SIGNATURE_TOKEN_ACCOUNTS: usize = 4;
+
+/// Length of the blockchain volume window in seconds
+const VOLUME_WINDOW: i64 = 86_400;
 
 /// Program state handler.
 pub struct Processor {}
 impl Processor {
     /// Initialize the bridge
     pub fn process_init_bridge(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         accounts: &[AccountInfo],
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
@@ -35,7 +52,8 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let bridge_account_data = Bridge::try_from_slice(&bridge_account_info.data.borrow())?;
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data = Bridge::unpack(&bridge_account_info.data.borrow())?;
         bridge_account_data.check_initialized(false)?;
 
         if !rent.is_exempt(
@@ -45,7 +63,8 @@ impl Processor {
             return Err(ProgramError::AccountNotRentExempt);
         }
 
-        let bridge = Bridge::new(*owner_account_info.key);
+        let (_, authority_bump) = get_bridge_authority(program_id, bridge_account_info.key);
+        let bridge = Bridge::new(*owner_account_info.key, authority_bump);
         bridge.serialize(&mut *bridge_account_info.data.borrow_mut())?;
         Ok(())
     }
@@ -55,47 +74,94 @@ impl Processor {
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         blockchain_id: BlockchainId,
-        contract_address: Address
+        contract_address: Address,
+        max_validators: u64
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let bridge_account_info = next_account_info(account_info_iter)?;
         let blockchain_account_info = next_account_info(account_info_iter)?;
-        let payer_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
         let bridge_authority_info = next_account_info(account_info_iter)?;
         let rent_account_info = next_account_info(account_info_iter)?;
         let rent = &Rent::from_account_info(rent_account_info)?;
 
-        let bump_seed = validate_bridge_authority_and_get_bump_seed(
-            program_id,
-            bridge_account_info.key,
-            &bridge_authority_info.key,
-        )?;
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
 
-        let seed = check_and_get_blockchain_account_seed(
+        let bump_seed = validate_bridge_authority_with_bump_seed(
             program_id,
-            blockchain_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
             bridge_authority_info.key,
-            blockchain_account_info.key
         )?;
 
-        create_account_with_seed(
-            payer_info,
+        Self::create_blockchain(
+            program_id,
+            bridge_account_info,
             blockchain_account_info,
+            owner_account_info,
             bridge_authority_info,
-            seed,
-            Blockchain::LEN,
+            bump_seed,
             rent,
+            blockchain_id,
+            contract_address,
+            max_validators
+        )?;
+
+        bridge_account_data.blockchains = checked_increment(bridge_account_data.blockchains)?;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process add blockchain batch
+    pub fn process_add_blockchain_batch(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        entries: Vec<(BlockchainId, Address, u64)>
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        let _system_program_info = next_account_info(account_info_iter)?;
+
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        if entries.is_empty() || entries.len() > ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES {
+            msg!("Batch must have 1 to {} entries", ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES);
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let bump_seed = validate_bridge_authority_with_bump_seed(
             program_id,
-            bridge_account_info.key.as_ref(),
-            bump_seed,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
         )?;
 
-        let blockchain = Blockchain::new(
-            *bridge_account_info.key,
-            blockchain_id,
-            contract_address);
-        blockchain.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+        for (blockchain_id, contract_address, max_validators) in entries {
+            let blockchain_account_info = next_account_info(account_info_iter)?;
+            Self::create_blockchain(
+                program_id,
+                bridge_account_info,
+                blockchain_account_info,
+                owner_account_info,
+                bridge_authority_info,
+                bump_seed,
+                rent,
+                blockchain_id,
+                contract_address,
+                max_validators
+            )?;
+            bridge_account_data.blockchains = checked_increment(bridge_account_data.blockchains)?;
+        }
+
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
         Ok(())
     }
 
@@ -105,27 +171,45 @@ impl Processor {
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         blockchain_id: BlockchainId,
-        pub_key: [u8; 32]
+        pub_key: [u8; 32],
+        key_type: u8
     ) -> ProgramResult {
+        let key_type = KeyType::try_from(key_type)?;
         let account_info_iter = &mut accounts.iter();
 
         let bridge_account_info = next_account_info(account_info_iter)?;
         let blockchain_account_info = next_account_info(account_info_iter)?;
         let validator_account_info = next_account_info(account_info_iter)?;
-        let payer_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
         let bridge_authority_info = next_account_info(account_info_iter)?;
         let rent_account_info = next_account_info(account_info_iter)?;
         let rent = &Rent::from_account_info(rent_account_info)?;
+        let _system_program_info = next_account_info(account_info_iter)?;
+        let validator_owner_info = next_account_info(account_info_iter)?;
 
-        let mut blockchain_account_data = Blockchain::try_from_slice(&blockchain_account_info.data.borrow_mut())?;
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        assert_owned_by(blockchain_account_info, program_id)?;
+        let mut blockchain_account_data = Blockchain::unpack(&blockchain_account_info.data.borrow_mut())?;
         blockchain_account_data.check_initialized(true)?;
 
+        if blockchain_account_data.bridge != *bridge_account_info.key {
+            msg!("Blockchain does not belong to the bridge");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if blockchain_account_data.validators >= blockchain_account_data.max_validators {
+            msg!("Blockchain has reached its validator limit");
+            return Err(SolBridgeProgramError::ValidatorLimitReached.into());
+        }
+
         let validator_index = blockchain_account_data.validators;
 
-        let bump_seed = validate_bridge_authority_and_get_bump_seed(
+        let bump_seed = validate_bridge_authority_with_bump_seed(
             program_id,
             bridge_account_info.key,
-            &bridge_authority_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
         )?;
 
         let seed = check_and_get_validator_account_seed(
@@ -137,7 +221,7 @@ impl Processor {
         )?;
 
         create_account_with_seed(
-            payer_info,
+            owner_account_info,
             validator_account_info,
             bridge_authority_info,
             seed,
@@ -148,14 +232,15 @@ impl Processor {
             bump_seed,
         )?;
 
-        blockchain_account_data.validators += 1;
+        blockchain_account_data.validators = checked_increment(blockchain_account_data.validators)?;
         blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
 
         let validator = Validator::new(
             blockchain_id,
             validator_index,
             pub_key,
-            *payer_info.key);
+            key_type,
+            *validator_owner_info.key);
         validator.serialize(&mut *validator_account_info.data.borrow_mut())?;
 
         Ok(())
@@ -174,13 +259,20 @@ impl Processor {
         destination: BlockchainId,
         sender: Address,
         recipient: Address,
-        amount: u64,
-        revert: bool
+        amount: u128,
+        revert: bool,
+        with_token: bool,
+        with_tip: bool,
+        nonce: u64
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
         let bridge_account_info = next_account_info(account_info_iter)?;
         let blockchain_account_info = next_account_info(account_info_iter)?;
+        let destination_blockchain_account_info = next_account_info(account_info_iter)?;
+        let token_info_account_info = next_account_info(account_info_iter)?;
+        let sender_allowed_account_info = next_account_info(account_info_iter)?;
+        let route_account_info = next_account_info(account_info_iter)?;
         let validator_account_info = next_account_info(account_info_iter)?;
         let lock_account_info = next_account_info(account_info_iter)?;
         let signature_account_info = next_account_info(account_info_iter)?;
@@ -194,36 +286,94 @@ impl Processor {
         let payer_info = next_account_info(account_info_iter)?;
         let rent_account_info = next_account_info(account_info_iter)?;
         let rent = &Rent::from_account_info(rent_account_info)?;
+        let clock_account_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(clock_account_info)?;
+        let _system_program_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+        let tx_index_info = next_account_info(account_info_iter)?;
+        let token_accounts = if with_token {
+            Some((
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?
+            ))
+        } else {
+            None
+        };
+        let tip_vault_info = if with_tip {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
 
         if !payer_info.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        let bridge_account_data: Bridge = Bridge::try_from_slice(&bridge_account_info.data.borrow())?;
+        if lock_account_info.key == signature_account_info.key {
+            return Err(SolBridgeProgramError::DuplicateLockAndSignatureAccount.into());
+        }
+
+        if amount == 0 {
+            return Err(SolBridgeProgramError::InvalidAmount.into());
+        }
+
+        if sender == Address::default() && recipient == Address::default() {
+            return Err(SolBridgeProgramError::InvalidAddress.into());
+        }
+
+        if source == destination {
+            return Err(SolBridgeProgramError::SameSourceAndDestination.into());
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data: Bridge = Bridge::unpack(&bridge_account_info.data.borrow())?;
         bridge_account_data.check_initialized(true)?;
 
-        let mut blockchain_account_data: Blockchain = Blockchain::try_from_slice(&blockchain_account_info.data.borrow_mut())?;
+        assert_owned_by(blockchain_account_info, program_id)?;
+        let mut blockchain_account_data: Blockchain = Blockchain::unpack(&blockchain_account_info.data.borrow_mut())?;
         blockchain_account_data.check_initialized(true)?;
 
-        let validator_account_data: Validator = Validator::try_from_slice(&validator_account_info.data.borrow())?;
+        assert_owned_by(validator_account_info, program_id)?;
+        let mut validator_account_data: Validator = Validator::unpack(&validator_account_info.data.borrow())?;
         validator_account_data.check_initialized(true)?;
 
-        if validator_account_data.owner != *payer_info.key {
-            msg!("Payer is not the validator");
-            return Err(ProgramError::InvalidArgument);
-        }
-
-        if validator_account_data.blockchain_id != source {
-            msg!("Invalid validator type");
-            return Err(ProgramError::InvalidArgument);
-        }
+        let message_hash = lock_message_hash(
+            &token_source,
+            &token_source_address,
+            &source,
+            &tx_id,
+            lock_id,
+            &destination,
+            &sender,
+            &recipient,
+            amount,
+            revert,
+            nonce
+        );
+        let verified_signature = Self::check_validator_signature(&validator_account_data, payer_info, source, &message_hash, &signature, instructions_info)?;
+        Self::use_validator_nonce(validator_account_info, &mut validator_account_data, nonce)?;
 
-        let bump_seed = validate_bridge_authority_and_get_bump_seed(
+        let bump_seed = validate_bridge_authority_with_bump_seed(
             program_id,
             bridge_account_info.key,
-            &bridge_authority_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_blockchain_account_seed(
+            program_id,
+            source,
+            bridge_authority_info.key,
+            blockchain_account_info.key
         )?;
 
+        if blockchain_account_data.bridge != *bridge_account_info.key {
+            msg!("Blockchain does not belong to the bridge");
+            return Err(ProgramError::InvalidArgument);
+        }
+
         let lock_seed = check_and_get_lock_account_seed(
             program_id,
             source,
@@ -234,6 +384,70 @@ impl Processor {
         )?;
 
         let mut lock_account_data = if lock_account_info.data_is_empty() {
+            check_and_get_blockchain_account_seed(
+                program_id,
+                destination,
+                bridge_authority_info.key,
+                destination_blockchain_account_info.key
+            )?;
+            assert_owned_by(destination_blockchain_account_info, program_id)?;
+            let destination_blockchain_account_data = Blockchain::unpack(&destination_blockchain_account_info.data.borrow())?;
+            destination_blockchain_account_data.check_initialized(true)?;
+            let fee_bps = Self::get_route_fee_bps(
+                program_id,
+                bridge_account_info.key,
+                route_account_info,
+                source,
+                destination
+            )?.unwrap_or(destination_blockchain_account_data.destination_fee_bps);
+
+            if bridge_account_data.strict_mode && route_account_info.data_is_empty() {
+                return Err(SolBridgeProgramError::RouteNotConfigured.into());
+            }
+            let destination_fee = calculate_fee(amount, fee_bps)?;
+            let fee = calculate_fee(amount, blockchain_account_data.fee_bp)?;
+            if destination_fee.checked_add(fee).ok_or(SolBridgeProgramError::Overflow)? > amount {
+                return Err(SolBridgeProgramError::InvalidFee.into());
+            }
+            if bridge_account_data.solana_chain_id != BlockchainId::default() && destination == bridge_account_data.solana_chain_id {
+                Self::check_solana_recipient(bridge_account_info, bridge_authority_info, recipient)?;
+            }
+
+            let (validator_fee, protocol_fee) = split_fee(
+                fee,
+                blockchain_account_data.validator_fee_bp,
+                blockchain_account_data.protocol_fee_bp
+            )?;
+
+            if bridge_account_data.wrapped_mode || bridge_account_data.strict_mode {
+                Self::check_token_registered(
+                    program_id,
+                    bridge_account_info.key,
+                    token_info_account_info,
+                    token_source,
+                    token_source_address
+                )?;
+            }
+
+            if blockchain_account_data.enforce_sender_allowlist || bridge_account_data.strict_mode {
+                Self::check_sender_allowed(
+                    program_id,
+                    bridge_account_info.key,
+                    sender_allowed_account_info,
+                    source,
+                    sender
+                )?;
+            }
+
+            if !revert {
+                if amount < blockchain_account_data.min_amount {
+                    msg!("Amount {} is below the minimum {}", amount, blockchain_account_data.min_amount);
+                    return Err(SolBridgeProgramError::AmountBelowMinimum.into());
+                }
+                Self::record_volume(&mut blockchain_account_data, amount, clock)?;
+                Self::index_lock_tx(program_id, bridge_account_info.key, source, tx_id, tx_index_info, lock_account_info, payer_info, rent)?;
+            }
+
             create_account_with_seed(
                 payer_info,
                 lock_account_info,
@@ -247,13 +461,33 @@ impl Processor {
             )?;
 
             let index = blockchain_account_data.locks;
-            blockchain_account_data.locks += 1;
+            blockchain_account_data.locks = checked_increment(blockchain_account_data.locks)?;
             blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
 
-            let mut sender_user_data = Self::get_or_create_user_data(program_id, source, sender, sender_user_authority_info, sender_user_info, payer_info, rent)?;
-            let mut recipient_user_data = Self::get_or_create_user_data(program_id, destination, recipient, recipient_user_authority_info, recipient_user_info, payer_info, rent)?;
+            let mut lock_account_data = Lock {
+                validator_fee,
+                protocol_fee,
+                ..Lock::new(
+                    index,
+                    lock_id,
+                    tx_id,
+                    *bridge_account_info.key,
+                    token_source_address,
+                    token_source,
+                    source,
+                    sender,
+                    recipient,
+                    destination,
+                    amount,
+                    destination_fee,
+                    fee,
+                    clock.unix_timestamp)
+            };
 
-            if sent_lock_info.lamports() > 0 {
+            let (mut sender_user_data, sender_user_created) = Self::get_or_create_user_data(program_id, source, sender, sender_user_authority_info, sender_user_info, payer_info, rent)?;
+            let (mut recipient_user_data, recipient_user_created) = Self::get_or_create_user_data(program_id, destination, recipient, recipient_user_authority_info, recipient_user_info, payer_info, rent)?;
+
+            if sent_lock_info.lamports() > 0 && sent_lock_info.owner != program_id {
                 msg!("Sent lock account is initialized");
                 return Err(ProgramError::AccountAlreadyInitialized);
             }
@@ -265,13 +499,12 @@ impl Processor {
                                          sender_user_authority_info,
                                          sender,
                                          sender_user_data.sent,
+                                         &lock_account_data,
                                          tx_id,
-                                         source,
-                                         lock_id,
-                                         false,
+                                         revert,
                                          "sent", rent)?;
 
-            if received_lock_info.lamports() > 0 {
+            if received_lock_info.lamports() > 0 && received_lock_info.owner != program_id {
                 msg!("Received lock account is initialized");
                 return Err(ProgramError::AccountAlreadyInitialized);
             }
@@ -283,168 +516,2682 @@ impl Processor {
                                          recipient_user_authority_info,
                                          recipient,
                                          recipient_user_data.received,
+                                         &lock_account_data,
                                          tx_id,
-                                         source,
-                                         lock_id,
-                                         false,
+                                         revert,
                                          "received",
                                          rent)?;
 
-            sender_user_data.sent += 1;
-            recipient_user_data.received += 1;
+            sender_user_data.sent = checked_increment(sender_user_data.sent)?;
+            recipient_user_data.received = checked_increment(recipient_user_data.received)?;
+            if !revert {
+                sender_user_data.in_flight = checked_increment(sender_user_data.in_flight)?;
+                recipient_user_data.in_flight = checked_increment(recipient_user_data.in_flight)?;
+            }
+
+            sender_user_data.total_sent_amount = sender_user_data.total_sent_amount
+                .checked_add(amount)
+                .ok_or(SolBridgeProgramError::Overflow)?;
+            recipient_user_data.total_received_amount = recipient_user_data.total_received_amount
+                .checked_add(amount)
+                .ok_or(SolBridgeProgramError::Overflow)?;
 
             sender_user_data.serialize(&mut *sender_user_info.data.borrow_mut())?;
             recipient_user_data.serialize(&mut *recipient_user_info.data.borrow_mut())?;
 
-            Lock::new(
-                index,
+            if let Some((sender_token_account_info, escrow_account_info, token_program_info, sender_token_owner_info)) = token_accounts {
+                Self::transfer_to_escrow(
+                    program_id,
+                    bridge_account_info,
+                    token_info_account_info,
+                    token_source,
+                    token_source_address,
+                    sender_token_account_info,
+                    sender_token_owner_info,
+                    escrow_account_info,
+                    token_program_info,
+                    sender,
+                    u64::try_from(amount).map_err(|_| SolBridgeProgramError::Overflow)?
+                )?;
+                lock_account_data.deposited = true;
+            }
+
+            BridgeEvent::LockCreated(LockCreatedEvent {
                 lock_id,
-                tx_id,
-                *bridge_account_info.key,
-                token_source_address,
-                token_source,
                 source,
+                destination,
                 sender,
                 recipient,
-                destination,
-                amount)
+                amount,
+            }).emit()?;
+
+            for (created, user_data) in [(sender_user_created, &sender_user_data), (recipient_user_created, &recipient_user_data)] {
+                if created {
+                    BridgeEvent::UserCreated(UserCreatedEvent {
+                        blockchain_id: user_data.blockchain_id,
+                        address: user_data.address,
+                    }).emit()?;
+                }
+            }
+
+            lock_account_data
         } else {
-            Lock::try_from_slice(&lock_account_info.data.borrow_mut())?
+            assert_owned_by(lock_account_info, program_id)?;
+            Lock::unpack(&lock_account_info.data.borrow_mut())?
         };
 
         lock_account_data.check_initialized(true)?;
 
-        if
-            lock_account_data.lock_id != lock_id ||
-            lock_account_data.tx_id != tx_id ||
-            lock_account_data.token_source_address != token_source_address ||
-            lock_account_data.token_source != token_source ||
-            lock_account_data.source != source ||
-            lock_account_data.sender != sender ||
-            lock_account_data.recipient != recipient ||
-            lock_account_data.destination != destination ||
-            lock_account_data.amount != amount
-        {
-            msg!("Existing lock does not match with the params");
-            return Err(ProgramError::InvalidArgument);
+        if lock_account_data.finalized {
+            return Err(SolBridgeProgramError::LockAlreadyFinalized.into());
         }
 
-        let signature_seed = check_and_get_signature_account_seed(
-            program_id,
+        Self::check_lock_params(
+            &lock_account_data,
+            token_source,
+            token_source_address,
             source,
+            tx_id,
             lock_id,
-            validator_account_data.index,
-            revert,
-            bridge_authority_info.key,
-            signature_account_info.key
+            destination,
+            sender,
+            recipient,
+            amount
         )?;
 
-        create_account_with_seed(
-            payer_info,
-            signature_account_info,
-            bridge_authority_info,
-            signature_seed,
-            Signature::LEN,
-            rent,
+        Self::add_lock_signature(
             program_id,
-            bridge_account_info.key.as_ref(),
+            bridge_account_info,
+            bridge_authority_info,
             bump_seed,
+            &blockchain_account_data,
+            validator_account_info,
+            &validator_account_data,
+            lock_account_info,
+            &mut lock_account_data,
+            signature_account_info,
+            payer_info,
+            rent,
+            clock,
+            revert,
+            verified_signature
         )?;
 
-        let signature = Signature::new(
-            source,
-            lock_id,
-            *bridge_account_info.key,
-            signature,
-            *validator_account_info.key,
-            validator_account_data.index);
-        signature.serialize(&mut *signature_account_info.data.borrow_mut())?;
+        if lock_account_data.finalized && !revert {
+            Self::release_user_lock(program_id, source, sender, sender_user_authority_info, sender_user_info)?;
+            Self::release_user_lock(program_id, destination, recipient, recipient_user_authority_info, recipient_user_info)?;
+        }
 
-        lock_account_data.signatures += 1;
-        lock_account_data.serialize(&mut *lock_account_info.data.borrow_mut())?;
+        if let Some(tip_vault_info) = tip_vault_info {
+            if lock_account_data.finalized {
+                Self::pay_tip(program_id, bridge_account_info, tip_vault_info, payer_info, rent, source, sender)?;
+            } else {
+                msg!("Tip is paid when the lock is finalized");
+            }
+        }
 
         Ok(())
     }
 
-    fn get_or_create_user_data<'a>(program_id: &Pubkey, blockchain_id: BlockchainId, user_address: Address, user_authority_info: & AccountInfo<'a>, user_info: & AccountInfo<'a>, payer_info: & AccountInfo<'a>, rent: & Rent) -> Result<User, ProgramError> {
-        msg!("get_or_create_user_data");
-        let bump_seed = validate_user_address_authority_and_get_bump_seed(program_id, user_address, user_authority_info.key)?;
-        let seed = check_and_get_user_account_seed(program_id, blockchain_id, user_authority_info.key, user_info.key)?;
-        return if user_info.data_is_empty() {
-            create_account_with_seed(
-                payer_info,
-                user_info,
-                user_authority_info,
-                seed,
-                User::LEN,
-                rent,
-                program_id,
-                user_address.as_ref(),
-                bump_seed,
-            )?;
-            Ok(User::new(blockchain_id, user_address))
-        } else {
-            Ok(User::try_from_slice(&user_info.data.borrow_mut())?)
-        }
-    }
-
-    fn create_lock_tx_account<'a>(
+    /// Add signatures of several validators, each signature is added the same way as `AddSignature`
+    /// with the validator and signature accounts of the entry, the lock is created with the first one
+    /// and the token and tip accounts are passed to every entry
+    pub fn process_add_signature_batch(
         program_id: &Pubkey,
-        blockchain_id: BlockchainId,
-        payer_info: &AccountInfo<'a>,
-        lock_tx_info: &AccountInfo<'a>,
-        lock_info: &AccountInfo<'a>,
-        user_authority_info: &AccountInfo<'a>,
-        user_address: Address, index: u64,
-        tx_id: TxId,
+        accounts: &[AccountInfo],
+        token_source: BlockchainId,
+        token_source_address: Address,
         source: BlockchainId,
+        tx_id: TxId,
         lock_id: u64,
-        reverted: bool,
-        tx_type: &str,
-        rent: &Rent) -> ProgramResult {
-        let bump_seed = validate_user_address_authority_and_get_bump_seed(program_id, user_address, user_authority_info.key)?;
-        let seed = check_and_get_lock_tx_account_seed(program_id, blockchain_id, index, tx_type, user_authority_info.key, lock_tx_info.key)?;
+        destination: BlockchainId,
+        sender: Address,
+        recipient: Address,
+        amount: u128,
+        revert: bool,
+        with_token: bool,
+        with_tip: bool,
+        signatures: Vec<([u8; 65], u64, u64)>
+    ) -> ProgramResult {
+        if signatures.is_empty() {
+            msg!("No signatures");
+            return Err(ProgramError::InvalidArgument);
+        }
 
-        create_account_with_seed(
-            payer_info,
-            lock_tx_info,
-            user_authority_info,
-            seed,
-            LockTx::LEN,
-            rent,
-            program_id,
-            user_address.as_ref(),
-            bump_seed,
-        )?;
+        let validator_accounts_len = signatures.len()
+            .checked_mul(2)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        let lock_accounts_len = accounts.len()
+            .checked_sub(validator_accounts_len)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let expected_lock_accounts_len = ADD_SIGNATURE_BATCH_LOCK_ACCOUNTS
+            + if with_token { ADD_SIGNATURE_TOKEN_ACCOUNTS } else { 0 }
+            + if with_tip { 1 } else { 0 };
+        if lock_accounts_len != expected_lock_accounts_len {
+            msg!("Expected {} lock accounts, got {}", expected_lock_accounts_len, lock_accounts_len);
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (lock_accounts, validator_accounts) = accounts.split_at(lock_accounts_len);
+
+        for ((signature, validator_index, nonce), validator_signature_accounts) in signatures.into_iter().zip(validator_accounts.chunks(2)) {
+            let validator_account_info = &validator_signature_accounts[0];
+            let signature_account_info = &validator_signature_accounts[1];
+
+            assert_owned_by(validator_account_info, program_id)?;
+            if Validator::unpack(&validator_account_info.data.borrow())?.index != validator_index {
+                msg!("Invalid validator index");
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            let mut add_signature_accounts = Vec::with_capacity(lock_accounts.len() + 2);
+            add_signature_accounts.extend_from_slice(&lock_accounts[..6]);
+            add_signature_accounts.push(validator_account_info.clone());
+            add_signature_accounts.push(lock_accounts[6].clone());
+            add_signature_accounts.push(signature_account_info.clone());
+            add_signature_accounts.extend_from_slice(&lock_accounts[7..]);
+
+            Self::process_add_signature(
+                program_id,
+                &add_signature_accounts,
+                signature,
+                token_source,
+                token_source_address,
+                source,
+                tx_id,
+                lock_id,
+                destination,
+                sender,
+                recipient,
+                amount,
+                revert,
+                with_token,
+                with_tip,
+                nonce
+            )?;
+        }
 
-        LockTx::new(tx_id, source, lock_id, *lock_info.key, reverted).serialize(&mut *lock_tx_info.data.borrow_mut())?;
         Ok(())
     }
 
-    /// Processes an instruction
-    pub fn process_instruction(
+    /// Add signature to the existing lock, lock params are taken from the lock account
+    pub fn process_add_signature_to_existing(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        input: &[u8],
+        source: BlockchainId,
+        lock_id: u64,
+        revert: bool,
+        validator_index: u64,
+        signature: [u8; 65],
+        nonce: u64
     ) -> ProgramResult {
-        let instruction = BridgeProgramInstruction::try_from_slice(input)
-            .or(Err(ProgramError::InvalidInstructionData))?;
-        match instruction {
-            BridgeProgramInstruction::InitializeBridge => {
-                msg!("Instruction: InitializeBridge");
-                Self::process_init_bridge(program_id, accounts)
-            },
-            BridgeProgramInstruction::AddBlockchain {contract_address, blockchain_id} => {
-                msg!("Instruction: AddBlockchain");
-                Self::process_add_blockchain(program_id, accounts, blockchain_id, contract_address)
-            },
-            BridgeProgramInstruction::AddValidator {blockchain_id, pub_key} => {
-                msg!("Instruction: AddBlockchain");
-                Self::process_add_validator(program_id, accounts, blockchain_id, pub_key)
-            }
-            BridgeProgramInstruction::AddSignature {signature, token_source, token_source_address, source, tx_id, lock_id, destination,sender,  recipient, amount, revert} => {
-                msg!("Instruction: AddBlockchain");
-                Self::process_add_signature(program_id, accounts, signature, token_source, token_source_address, source, tx_id, lock_id, destination, sender, recipient, amount, revert)
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let validator_account_info = next_account_info(account_info_iter)?;
+        let lock_account_info = next_account_info(account_info_iter)?;
+        let signature_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        let clock_account_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(clock_account_info)?;
+        let _system_program_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+        let sender_user_info = next_account_info(account_info_iter)?;
+        let sender_user_authority_info = next_account_info(account_info_iter)?;
+        let recipient_user_info = next_account_info(account_info_iter)?;
+        let recipient_user_authority_info = next_account_info(account_info_iter)?;
+
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if lock_account_info.key == signature_account_info.key {
+            return Err(SolBridgeProgramError::DuplicateLockAndSignatureAccount.into());
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data: Bridge = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        assert_owned_by(blockchain_account_info, program_id)?;
+        let blockchain_account_data: Blockchain = Blockchain::unpack(&blockchain_account_info.data.borrow())?;
+        blockchain_account_data.check_initialized(true)?;
+
+        assert_owned_by(validator_account_info, program_id)?;
+        let mut validator_account_data: Validator = Validator::unpack(&validator_account_info.data.borrow())?;
+        validator_account_data.check_initialized(true)?;
+
+        if validator_account_data.index != validator_index {
+            msg!("Invalid validator index");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let bump_seed = validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_blockchain_account_seed(
+            program_id,
+            source,
+            bridge_authority_info.key,
+            blockchain_account_info.key
+        )?;
+
+        if blockchain_account_data.bridge != *bridge_account_info.key {
+            msg!("Blockchain does not belong to the bridge");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            revert,
+            bridge_authority_info.key,
+            lock_account_info.key
+        )?;
+
+        if lock_account_info.data_is_empty() {
+            msg!("Lock does not exist");
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        assert_owned_by(lock_account_info, program_id)?;
+        let mut lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+        lock_account_data.check_initialized(true)?;
+
+        if lock_account_data.finalized {
+            return Err(SolBridgeProgramError::LockAlreadyFinalized.into());
+        }
+
+        let message_hash = lock_message_hash(
+            &lock_account_data.token_source,
+            &lock_account_data.token_source_address,
+            &lock_account_data.source,
+            &lock_account_data.tx_id,
+            lock_account_data.lock_id,
+            &lock_account_data.destination,
+            &lock_account_data.sender,
+            &lock_account_data.recipient,
+            lock_account_data.amount,
+            revert,
+            nonce
+        );
+        let verified_signature = Self::check_validator_signature(&validator_account_data, payer_info, source, &message_hash, &signature, instructions_info)?;
+        Self::use_validator_nonce(validator_account_info, &mut validator_account_data, nonce)?;
+
+        Self::add_lock_signature(
+            program_id,
+            bridge_account_info,
+            bridge_authority_info,
+            bump_seed,
+            &blockchain_account_data,
+            validator_account_info,
+            &validator_account_data,
+            lock_account_info,
+            &mut lock_account_data,
+            signature_account_info,
+            payer_info,
+            rent,
+            clock,
+            revert,
+            verified_signature
+        )?;
+
+        if lock_account_data.finalized && !revert {
+            Self::release_user_lock(program_id, lock_account_data.source, lock_account_data.sender, sender_user_authority_info, sender_user_info)?;
+            Self::release_user_lock(program_id, lock_account_data.destination, lock_account_data.recipient, recipient_user_authority_info, recipient_user_info)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add signature confirming the revert of an existing lock
+    pub fn process_add_revert_signature(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        lock_id: u64,
+        validator_index: u64,
+        sent_index: u64,
+        signature: [u8; 65],
+        nonce: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let validator_account_info = next_account_info(account_info_iter)?;
+        let lock_account_info = next_account_info(account_info_iter)?;
+        let revert_lock_account_info = next_account_info(account_info_iter)?;
+        let signature_account_info = next_account_info(account_info_iter)?;
+        let sent_lock_info = next_account_info(account_info_iter)?;
+        let sender_user_authority_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        let clock_account_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(clock_account_info)?;
+        let _system_program_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+
+        if !payer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if revert_lock_account_info.key == signature_account_info.key {
+            return Err(SolBridgeProgramError::DuplicateLockAndSignatureAccount.into());
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data: Bridge = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        assert_owned_by(blockchain_account_info, program_id)?;
+        let blockchain_account_data: Blockchain = Blockchain::unpack(&blockchain_account_info.data.borrow())?;
+        blockchain_account_data.check_initialized(true)?;
+
+        assert_owned_by(validator_account_info, program_id)?;
+        let mut validator_account_data: Validator = Validator::unpack(&validator_account_info.data.borrow())?;
+        validator_account_data.check_initialized(true)?;
+
+        if validator_account_data.index != validator_index {
+            msg!("Invalid validator index");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let bump_seed = validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_blockchain_account_seed(
+            program_id,
+            source,
+            bridge_authority_info.key,
+            blockchain_account_info.key
+        )?;
+
+        if blockchain_account_data.bridge != *bridge_account_info.key {
+            msg!("Blockchain does not belong to the bridge");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            false,
+            bridge_authority_info.key,
+            lock_account_info.key
+        )?;
+
+        if lock_account_info.data_is_empty() {
+            msg!("Lock does not exist");
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        assert_owned_by(lock_account_info, program_id)?;
+        let lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+        lock_account_data.check_initialized(true)?;
+
+        if lock_account_data.withdrawn {
+            return Err(SolBridgeProgramError::LockAlreadyWithdrawn.into());
+        }
+
+        validate_user_address_authority_and_get_bump_seed(program_id, lock_account_data.sender, sender_user_authority_info.key)?;
+        check_and_get_sent_lock_account_seed(
+            program_id,
+            source,
+            sender_user_authority_info.key,
+            sent_index,
+            sent_lock_info.key
+        )?;
+
+        assert_owned_by(sent_lock_info, program_id)?;
+        let mut sent_lock_data = LockTx::unpack(&sent_lock_info.data.borrow())?;
+        sent_lock_data.check_initialized(true)?;
+
+        if sent_lock_data.lock_account != *lock_account_info.key {
+            msg!("Sent lock record belongs to another lock");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let message_hash = lock_message_hash(
+            &lock_account_data.token_source,
+            &lock_account_data.token_source_address,
+            &lock_account_data.source,
+            &lock_account_data.tx_id,
+            lock_account_data.lock_id,
+            &lock_account_data.destination,
+            &lock_account_data.sender,
+            &lock_account_data.recipient,
+            lock_account_data.amount,
+            true,
+            nonce
+        );
+        let verified_signature = Self::check_validator_signature(&validator_account_data, payer_info, source, &message_hash, &signature, instructions_info)?;
+        Self::use_validator_nonce(validator_account_info, &mut validator_account_data, nonce)?;
+
+        let revert_lock_seed = check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            true,
+            bridge_authority_info.key,
+            revert_lock_account_info.key
+        )?;
+
+        let mut revert_lock_account_data = if revert_lock_account_info.data_is_empty() {
+            create_account_with_seed(
+                payer_info,
+                revert_lock_account_info,
+                bridge_authority_info,
+                revert_lock_seed,
+                Lock::LEN,
+                rent,
+                program_id,
+                bridge_account_info.key.as_ref(),
+                bump_seed,
+            )?;
+
+            Lock {
+                validator_fee: lock_account_data.validator_fee,
+                protocol_fee: lock_account_data.protocol_fee,
+                ..Lock::new(
+                    lock_account_data.index,
+                    lock_account_data.lock_id,
+                    lock_account_data.tx_id,
+                    lock_account_data.bridge,
+                    lock_account_data.token_source_address,
+                    lock_account_data.token_source,
+                    lock_account_data.source,
+                    lock_account_data.sender,
+                    lock_account_data.recipient,
+                    lock_account_data.destination,
+                    lock_account_data.amount,
+                    lock_account_data.destination_fee,
+                    lock_account_data.fee,
+                    clock.unix_timestamp)
+            }
+        } else {
+            assert_owned_by(revert_lock_account_info, program_id)?;
+            Lock::unpack(&revert_lock_account_info.data.borrow())?
+        };
+
+        revert_lock_account_data.check_initialized(true)?;
+
+        if revert_lock_account_data.finalized {
+            return Err(SolBridgeProgramError::LockAlreadyFinalized.into());
+        }
+
+        Self::add_lock_signature(
+            program_id,
+            bridge_account_info,
+            bridge_authority_info,
+            bump_seed,
+            &blockchain_account_data,
+            validator_account_info,
+            &validator_account_data,
+            revert_lock_account_info,
+            &mut revert_lock_account_data,
+            signature_account_info,
+            payer_info,
+            rent,
+            clock,
+            true,
+            verified_signature
+        )?;
+
+        if revert_lock_account_data.finalized {
+            msg!("Lock reverted");
+            sent_lock_data.reverted = true;
+            sent_lock_data.serialize(&mut *sent_lock_info.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    /// Process set validator active
+    pub fn process_set_validator_active(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        index: u64,
+        active: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let validator_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_validator_account_seed(
+            program_id,
+            blockchain_id,
+            index,
+            bridge_authority_info.key,
+            validator_account_info.key
+        )?;
+
+        assert_owned_by(validator_account_info, program_id)?;
+        let mut validator_account_data = Validator::unpack(&validator_account_info.data.borrow())?;
+        validator_account_data.check_initialized(true)?;
+
+        validator_account_data.active = active;
+        validator_account_data.serialize(&mut *validator_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set validator paused
+    pub fn process_set_validator_paused(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        index: u64,
+        paused: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let validator_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let bridge_account_data = if paused {
+            Self::check_bridge_owner_or_guardian(program_id, bridge_account_info, owner_account_info)?
+        } else {
+            Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?
+        };
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_validator_account_seed(
+            program_id,
+            blockchain_id,
+            index,
+            bridge_authority_info.key,
+            validator_account_info.key
+        )?;
+
+        assert_owned_by(validator_account_info, program_id)?;
+        let mut validator_account_data = Validator::unpack(&validator_account_info.data.borrow())?;
+        validator_account_data.check_initialized(true)?;
+
+        validator_account_data.paused = paused;
+        validator_account_data.serialize(&mut *validator_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process update contract address
+    pub fn process_update_contract_address(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        contract_address: Address
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        blockchain_account_data.contract_address = contract_address;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set destination fee
+    pub fn process_set_destination_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        destination_fee_bps: u16
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        if destination_fee_bps as u64 > BPS_DENOMINATOR {
+            return Err(SolBridgeProgramError::InvalidFee.into());
+        }
+
+        blockchain_account_data.destination_fee_bps = destination_fee_bps;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set fee
+    pub fn process_set_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        fee_bp: u16
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        if fee_bp as u64 > BPS_DENOMINATOR {
+            return Err(SolBridgeProgramError::InvalidFee.into());
+        }
+
+        blockchain_account_data.fee_bp = fee_bp;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set fee split
+    pub fn process_set_fee_split(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        validator_fee_bp: u16,
+        protocol_fee_bp: u16
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        if validator_fee_bp as u64 + protocol_fee_bp as u64 > BPS_DENOMINATOR {
+            return Err(SolBridgeProgramError::InvalidFee.into());
+        }
+
+        blockchain_account_data.validator_fee_bp = validator_fee_bp;
+        blockchain_account_data.protocol_fee_bp = protocol_fee_bp;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set threshold
+    pub fn process_set_threshold(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        threshold: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        if threshold > blockchain_account_data.validators {
+            msg!("Threshold exceeds the number of validators");
+            return Err(SolBridgeProgramError::InvalidThreshold.into());
+        }
+
+        blockchain_account_data.threshold = threshold;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set daily limit
+    pub fn process_set_daily_limit(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        daily_limit: u128
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        blockchain_account_data.daily_limit = daily_limit;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set min amount
+    pub fn process_set_min_amount(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        min_amount: u128
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        blockchain_account_data.min_amount = min_amount;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process has validator signed, closed signatures aren't seen
+    pub fn process_has_validator_signed(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        lock_id: u64,
+        validator_index: u64,
+        revert: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let signature_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_signature_account_seed(
+            program_id,
+            source,
+            lock_id,
+            validator_index,
+            revert,
+            bridge_authority_info.key,
+            signature_account_info.key
+        )?;
+
+        let signed = !signature_account_info.data_is_empty();
+        set_return_data(&signed.try_to_vec()?);
+
+        Ok(())
+    }
+
+    /// Process ping
+    pub fn process_ping(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo]
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data: Bridge = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        Ok(())
+    }
+
+    /// Process set wrapped mode
+    pub fn process_set_wrapped_mode(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+        bridge_account_data.wrapped_mode = enabled;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set strict mode
+    pub fn process_set_strict_mode(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+        bridge_account_data.strict_mode = enabled;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set guardian
+    pub fn process_set_guardian(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        guardian: Pubkey
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+        bridge_account_data.guardian = guardian;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set solana chain id
+    pub fn process_set_solana_chain_id(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        solana_chain_id: BlockchainId
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+        bridge_account_data.solana_chain_id = solana_chain_id;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process fund tip vault
+    pub fn process_fund_tip_vault(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        sender: Address,
+        tip: u64,
+        deposit: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let tip_vault_info = next_account_info(account_info_iter)?;
+        let funder_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !funder_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        Bridge::unpack(&bridge_account_info.data.borrow())?.check_initialized(true)?;
+
+        let bump_seed = validate_tip_vault_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &source,
+            &sender,
+            tip_vault_info.key
+        )?;
+
+        if tip_vault_info.data_is_empty() {
+            create_program_account(
+                funder_info,
+                tip_vault_info,
+                TipVault::LEN,
+                rent,
+                program_id,
+                &[bridge_account_info.key.as_ref(), b"tip", source.as_ref(), sender.as_ref(), &[bump_seed]],
+            )?;
+        } else {
+            assert_owned_by(tip_vault_info, program_id)?;
+            let tip_vault_data = TipVault::unpack(&tip_vault_info.data.borrow())?;
+            tip_vault_data.check_initialized(true)?;
+            if tip_vault_data.funder != *funder_info.key {
+                msg!("Signer is not the tip vault funder");
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+
+        if deposit > 0 {
+            invoke(
+                &system_instruction::transfer(funder_info.key, tip_vault_info.key, deposit),
+                &[
+                    funder_info.clone(),
+                    tip_vault_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+
+        let tip_vault = TipVault::new(*bridge_account_info.key, source, sender, *funder_info.key, tip);
+        tip_vault.serialize(&mut *tip_vault_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set dispute window
+    pub fn process_set_dispute_window(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        dispute_window: i64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        if dispute_window < 0 {
+            msg!("Dispute window must not be negative");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+        bridge_account_data.dispute_window = dispute_window;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process register token
+    pub fn process_register_token(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        token_source: BlockchainId,
+        token_source_address: Address,
+        mint: Pubkey
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let token_info_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+
+        Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        let bump_seed = validate_token_info_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &token_source,
+            &token_source_address,
+            token_info_account_info.key
+        )?;
+
+        create_program_account(
+            owner_account_info,
+            token_info_account_info,
+            TokenInfo::LEN,
+            rent,
+            program_id,
+            &[bridge_account_info.key.as_ref(), b"token", token_source.as_ref(), token_source_address.as_ref(), &[bump_seed]],
+        )?;
+
+        let token_info = TokenInfo::new(
+            *bridge_account_info.key,
+            token_source,
+            token_source_address,
+            mint);
+        token_info.serialize(&mut *token_info_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process initialize escrow
+    pub fn process_initialize_escrow(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint: Pubkey
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let escrow_account_info = next_account_info(account_info_iter)?;
+        let mint_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        if *mint_account_info.key != mint {
+            msg!("Mint account does not match the instruction");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let bump_seed = validate_escrow_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &mint,
+            escrow_account_info.key
+        )?;
+
+        create_program_account(
+            owner_account_info,
+            escrow_account_info,
+            spl_token::state::Account::LEN,
+            rent,
+            &spl_token::id(),
+            &[bridge_account_info.key.as_ref(), b"escrow", mint.as_ref(), &[bump_seed]],
+        )?;
+
+        invoke(
+            &spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                escrow_account_info.key,
+                mint_account_info.key,
+                bridge_authority_info.key,
+            )?,
+            &[
+                escrow_account_info.clone(),
+                mint_account_info.clone(),
+                bridge_authority_info.clone(),
+                rent_account_info.clone(),
+                token_program_info.clone(),
+            ],
+        )
+    }
+
+    /// Process check escrow solvency, the deposited and not withdrawn locks of the token registered
+    /// in the token info are compared to the escrow balance of its mint, returns the solvency flag
+    /// and the deficit
+    pub fn process_check_escrow_solvency(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        mint: Pubkey
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let token_info_account_info = next_account_info(account_info_iter)?;
+        let escrow_account_info = next_account_info(account_info_iter)?;
+        let lock_account_infos = account_info_iter.as_slice();
+
+        if token_info_account_info.data_is_empty() {
+            return Err(SolBridgeProgramError::TokenNotRegistered.into());
+        }
+
+        assert_owned_by(token_info_account_info, program_id)?;
+        let token_info_account_data = TokenInfo::unpack(&token_info_account_info.data.borrow())?;
+        token_info_account_data.check_initialized(true)?;
+        validate_token_info_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &token_info_account_data.token_source,
+            &token_info_account_data.token_source_address,
+            token_info_account_info.key
+        )?;
+
+        if token_info_account_data.mint != mint {
+            msg!("Mint {} differs from the registered mint {}", mint, token_info_account_data.mint);
+            return Err(SolBridgeProgramError::MintMismatch.into());
+        }
+
+        validate_escrow_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &mint,
+            escrow_account_info.key
+        )?;
+
+        assert_owned_by(escrow_account_info, &spl_token::id())?;
+        let escrow_account_data = spl_token::state::Account::unpack(&escrow_account_info.data.borrow())?;
+
+        let mut outstanding: u128 = 0;
+        for (index, lock_account_info) in lock_account_infos.iter().enumerate() {
+            if lock_account_infos[..index].iter().any(|other| other.key == lock_account_info.key) {
+                msg!("Lock {} is passed more than once", lock_account_info.key);
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            assert_owned_by(lock_account_info, program_id)?;
+            let lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+            lock_account_data.check_initialized(true)?;
+
+            if lock_account_data.bridge != *bridge_account_info.key {
+                msg!("Lock does not belong to the bridge");
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            if lock_account_data.token_source != token_info_account_data.token_source
+                || lock_account_data.token_source_address != token_info_account_data.token_source_address {
+                continue;
+            }
+
+            if lock_account_data.deposited && !lock_account_data.withdrawn {
+                outstanding = outstanding
+                    .checked_add(lock_account_data.amount)
+                    .ok_or(SolBridgeProgramError::Overflow)?;
+            }
+        }
+
+        let deficit = outstanding.saturating_sub(escrow_account_data.amount as u128);
+        msg!("Escrow deficit: {}", deficit);
+        set_return_data(&(deficit == 0, deficit).try_to_vec()?);
+
+        Ok(())
+    }
+
+    /// Process withdraw, the recipient receives the net amount of the lock while the destination
+    /// and protocol fees stay in the escrow, so the escrow keeps backing the fees recorded on the lock
+    pub fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        lock_id: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let lock_account_info = next_account_info(account_info_iter)?;
+        let token_info_account_info = next_account_info(account_info_iter)?;
+        let escrow_account_info = next_account_info(account_info_iter)?;
+        let recipient_token_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+        let clock_account_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(clock_account_info)?;
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        let bump_seed = validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            false,
+            bridge_authority_info.key,
+            lock_account_info.key
+        )?;
+
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_owned_by(lock_account_info, program_id)?;
+        let mut lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+        lock_account_data.check_initialized(true)?;
+
+        if !lock_account_data.finalized {
+            return Err(SolBridgeProgramError::LockNotFinalized.into());
+        }
+
+        if !lock_account_data.deposited {
+            return Err(SolBridgeProgramError::LockNotDeposited.into());
+        }
+
+        if lock_account_data.withdrawn {
+            return Err(SolBridgeProgramError::LockAlreadyWithdrawn.into());
+        }
+
+        assert_owned_by(recipient_token_account_info, &spl_token::id())?;
+        let recipient_token_account_data = spl_token::state::Account::unpack(&recipient_token_account_info.data.borrow())?;
+
+        if recipient_token_account_data.owner.to_bytes() != lock_account_data.recipient {
+            msg!("Token account is not owned by the recipient");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let token_info_account_data = Self::check_token_registered(
+            program_id,
+            bridge_account_info.key,
+            token_info_account_info,
+            lock_account_data.token_source,
+            lock_account_data.token_source_address
+        )?;
+
+        if recipient_token_account_data.mint != token_info_account_data.mint {
+            msg!("Token account mint {} differs from the registered mint {}", recipient_token_account_data.mint, token_info_account_data.mint);
+            return Err(SolBridgeProgramError::MintMismatch.into());
+        }
+
+        validate_escrow_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &token_info_account_data.mint,
+            escrow_account_info.key
+        )?;
+
+        lock_account_data.withdrawn = true;
+        lock_account_data.unlock_time = clock.unix_timestamp;
+        lock_account_data.serialize(&mut *lock_account_info.data.borrow_mut())?;
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                escrow_account_info.key,
+                recipient_token_account_info.key,
+                bridge_authority_info.key,
+                &[],
+                u64::try_from(lock_account_data.net_amount()?).map_err(|_| SolBridgeProgramError::Overflow)?,
+            )?,
+            &[
+                escrow_account_info.clone(),
+                recipient_token_account_info.clone(),
+                bridge_authority_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[bridge_account_info.key.as_ref(), &[bump_seed]]],
+        )
+    }
+
+    /// Process revert unlock
+    pub fn process_revert_unlock(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        lock_id: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let lock_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let clock_account_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(clock_account_info)?;
+
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            false,
+            bridge_authority_info.key,
+            lock_account_info.key
+        )?;
+
+        assert_owned_by(lock_account_info, program_id)?;
+        let mut lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+        lock_account_data.check_initialized(true)?;
+
+        if !lock_account_data.withdrawn {
+            return Err(SolBridgeProgramError::LockNotWithdrawn.into());
+        }
+
+        if lock_account_data.unlock_reverted {
+            return Err(SolBridgeProgramError::UnlockAlreadyReverted.into());
+        }
+
+        let dispute_deadline = lock_account_data.unlock_time
+            .checked_add(bridge_account_data.dispute_window)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        if bridge_account_data.dispute_window == 0 || clock.unix_timestamp > dispute_deadline {
+            return Err(SolBridgeProgramError::DisputeWindowClosed.into());
+        }
+
+        lock_account_data.unlock_reverted = true;
+        lock_account_data.serialize(&mut *lock_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process close signature
+    pub fn process_close_signature(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        lock_id: u64,
+        validator_index: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let lock_account_info = next_account_info(account_info_iter)?;
+        let signature_account_info = next_account_info(account_info_iter)?;
+        let destination_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            false,
+            bridge_authority_info.key,
+            lock_account_info.key
+        )?;
+
+        check_and_get_signature_account_seed(
+            program_id,
+            source,
+            lock_id,
+            validator_index,
+            false,
+            bridge_authority_info.key,
+            signature_account_info.key
+        )?;
+
+        if signature_account_info.key == destination_account_info.key {
+            msg!("Destination is the signature account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        assert_owned_by(lock_account_info, program_id)?;
+        let lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+        lock_account_data.check_initialized(true)?;
+
+        if !lock_account_data.finalized {
+            return Err(SolBridgeProgramError::LockNotFinalized.into());
+        }
+
+        assert_owned_by(signature_account_info, program_id)?;
+        let signature_account_data = Signature::unpack(&signature_account_info.data.borrow())?;
+        signature_account_data.check_initialized(true)?;
+
+        close_account(signature_account_info, destination_account_info)
+    }
+
+    /// Process close lock
+    pub fn process_close_lock(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        lock_id: u64
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let lock_account_info = next_account_info(account_info_iter)?;
+        let destination_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let clock_account_info = next_account_info(account_info_iter)?;
+        let clock = &Clock::from_account_info(clock_account_info)?;
+
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_lock_account_seed(
+            program_id,
+            source,
+            lock_id,
+            false,
+            bridge_authority_info.key,
+            lock_account_info.key
+        )?;
+
+        if lock_account_info.key == destination_account_info.key {
+            msg!("Destination is the lock account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        assert_owned_by(lock_account_info, program_id)?;
+        let lock_account_data = Lock::unpack(&lock_account_info.data.borrow())?;
+        lock_account_data.check_initialized(true)?;
+
+        if !lock_account_data.finalized {
+            return Err(SolBridgeProgramError::LockNotFinalized.into());
+        }
+
+        if !lock_account_data.withdrawn {
+            return Err(SolBridgeProgramError::LockNotWithdrawn.into());
+        }
+
+        if lock_account_data.unlock_reverted {
+            return Err(SolBridgeProgramError::UnlockAlreadyReverted.into());
+        }
+
+        let dispute_deadline = lock_account_data.unlock_time
+            .checked_add(bridge_account_data.dispute_window)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        if bridge_account_data.dispute_window > 0 && clock.unix_timestamp <= dispute_deadline {
+            return Err(SolBridgeProgramError::DisputeWindowOpen.into());
+        }
+
+        close_account(lock_account_info, destination_account_info)?;
+
+        BridgeEvent::LockClosed(LockClosedEvent {
+            source,
+            lock_id,
+            recipient: lock_account_data.recipient,
+            amount: lock_account_data.amount,
+            unlock_time: lock_account_data.unlock_time,
+        }).emit()
+    }
+
+    /// Process migrate account, a bridge of the previous layout is read as `BridgeV1`, grown to the
+    /// current size with the owner topping up the rent and rewritten with defaults for the new fields
+    pub fn process_migrate_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+
+        if !owner_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        if bridge_account_info.data_len() != BridgeV1::LEN {
+            msg!("Bridge account of {} bytes is not of the previous layout", bridge_account_info.data_len());
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let previous_bridge_data = BridgeV1::unpack(&bridge_account_info.data.borrow())?;
+
+        if previous_bridge_data.account_type != AccountType::Bridge as u8 {
+            return Err(SolBridgeProgramError::InvalidAccountType.into());
+        }
+
+        if previous_bridge_data.version != PREVIOUS_VERSION {
+            msg!("Account version {} can't be migrated", previous_bridge_data.version);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if previous_bridge_data.owner != *owner_account_info.key {
+            msg!("Signer is not the bridge owner");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let (_, authority_bump) = get_bridge_authority(program_id, bridge_account_info.key);
+        let bridge_account_data = previous_bridge_data.migrate(authority_bump);
+
+        let rent_top_up = Bridge::rent_exempt_balance(rent).saturating_sub(bridge_account_info.lamports());
+        if rent_top_up > 0 {
+            invoke(
+                &system_instruction::transfer(owner_account_info.key, bridge_account_info.key, rent_top_up),
+                &[
+                    owner_account_info.clone(),
+                    bridge_account_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+
+        bridge_account_info.realloc(Bridge::LEN, true)?;
+        bridge_account_data.serialize(&mut *bridge_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process close user, the records of the user are left behind and taken over by the user if it
+    /// is created again
+    pub fn process_close_user(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        address: Address
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let user_account_info = next_account_info(account_info_iter)?;
+        let user_authority_info = next_account_info(account_info_iter)?;
+        let destination_account_info = next_account_info(account_info_iter)?;
+        let signer_info = next_account_info(account_info_iter)?;
+
+        if !signer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        // The key of the address signs for itself, users of remote chains have no key here and are
+        // closed by the bridge owner
+        let solana_user = bridge_account_data.solana_chain_id != BlockchainId::default()
+            && blockchain_id == bridge_account_data.solana_chain_id;
+        if signer_info.key.to_bytes() != address {
+            if solana_user {
+                msg!("Signer is not the user");
+                return Err(ProgramError::InvalidArgument);
+            }
+            Self::check_bridge_owner(program_id, bridge_account_info, signer_info)?;
+        }
+
+        validate_user_address_authority_and_get_bump_seed(program_id, address, user_authority_info.key)?;
+        check_and_get_user_account_seed(program_id, blockchain_id, user_authority_info.key, user_account_info.key)?;
+
+        if user_account_info.key == destination_account_info.key {
+            msg!("Destination is the user account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        assert_owned_by(user_account_info, program_id)?;
+        let user_account_data = User::unpack(&user_account_info.data.borrow())?;
+        user_account_data.check_initialized(true)?;
+
+        if user_account_data.blockchain_id != blockchain_id || user_account_data.address != address {
+            msg!("User account belongs to another user");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if user_account_data.in_flight > 0 {
+            msg!("{} locks of the user are in flight", user_account_data.in_flight);
+            return Err(SolBridgeProgramError::LockNotFinalized.into());
+        }
+
+        close_account(user_account_info, destination_account_info)
+    }
+
+    /// Process allow or block sender
+    pub fn process_set_sender_allowed(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        sender: Address,
+        allowed: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let sender_allowed_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+
+        Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        let bump_seed = validate_sender_allowed_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &source,
+            &sender,
+            sender_allowed_account_info.key
+        )?;
+
+        if sender_allowed_account_info.data_is_empty() {
+            create_program_account(
+                owner_account_info,
+                sender_allowed_account_info,
+                SenderAllowed::LEN,
+                rent,
+                program_id,
+                &[bridge_account_info.key.as_ref(), b"sender", source.as_ref(), sender.as_ref(), &[bump_seed]],
+            )?;
+        } else {
+            assert_owned_by(sender_allowed_account_info, program_id)?;
+        }
+
+        let sender_allowed = SenderAllowed::new(
+            *bridge_account_info.key,
+            source,
+            sender,
+            allowed);
+        sender_allowed.serialize(&mut *sender_allowed_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set route fee
+    pub fn process_set_route_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        source: BlockchainId,
+        destination: BlockchainId,
+        fee_bps: Option<u16>
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let route_account_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+        let rent_account_info = next_account_info(account_info_iter)?;
+        let rent = &Rent::from_account_info(rent_account_info)?;
+
+        Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        if let Some(fee_bps) = fee_bps {
+            if fee_bps as u64 > BPS_DENOMINATOR {
+                return Err(SolBridgeProgramError::InvalidFee.into());
+            }
+        }
+
+        let bump_seed = validate_route_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &source,
+            &destination,
+            route_account_info.key
+        )?;
+
+        if route_account_info.data_is_empty() {
+            create_program_account(
+                owner_account_info,
+                route_account_info,
+                Route::LEN,
+                rent,
+                program_id,
+                &[bridge_account_info.key.as_ref(), b"route", source.as_ref(), destination.as_ref(), &[bump_seed]],
+            )?;
+        } else {
+            assert_owned_by(route_account_info, program_id)?;
+        }
+
+        let route = Route::new(
+            *bridge_account_info.key,
+            source,
+            destination,
+            fee_bps);
+        route.serialize(&mut *route_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Process set sender allowlist enforced
+    pub fn process_set_sender_allowlist_enforced(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        blockchain_id: BlockchainId,
+        enforced: bool
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let bridge_account_info = next_account_info(account_info_iter)?;
+        let blockchain_account_info = next_account_info(account_info_iter)?;
+        let bridge_authority_info = next_account_info(account_info_iter)?;
+        let owner_account_info = next_account_info(account_info_iter)?;
+
+        let mut blockchain_account_data = Self::get_blockchain_data_as_owner(
+            program_id,
+            bridge_account_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            owner_account_info,
+            blockchain_id
+        )?;
+
+        blockchain_account_data.enforce_sender_allowlist = enforced;
+        blockchain_account_data.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn check_lock_params(
+        lock_account_data: &Lock,
+        token_source: BlockchainId,
+        token_source_address: Address,
+        source: BlockchainId,
+        tx_id: TxId,
+        lock_id: u64,
+        destination: BlockchainId,
+        sender: Address,
+        recipient: Address,
+        amount: u128
+    ) -> ProgramResult {
+        if lock_account_data.lock_id != lock_id {
+            msg!("Lock id: stored {}, provided {}", lock_account_data.lock_id, lock_id);
+            return Err(SolBridgeProgramError::LockIdMismatch.into());
+        }
+        if lock_account_data.tx_id != tx_id {
+            msg!("Tx id: stored {:?}, provided {:?}", lock_account_data.tx_id, tx_id);
+            return Err(SolBridgeProgramError::TxIdMismatch.into());
+        }
+        if lock_account_data.token_source_address != token_source_address {
+            msg!("Token source address: stored {:?}, provided {:?}", lock_account_data.token_source_address, token_source_address);
+            return Err(SolBridgeProgramError::TokenSourceAddressMismatch.into());
+        }
+        if lock_account_data.token_source != token_source {
+            msg!("Token source: stored {:?}, provided {:?}", lock_account_data.token_source, token_source);
+            return Err(SolBridgeProgramError::TokenSourceMismatch.into());
+        }
+        if lock_account_data.source != source {
+            msg!("Source: stored {:?}, provided {:?}", lock_account_data.source, source);
+            return Err(SolBridgeProgramError::SourceMismatch.into());
+        }
+        if lock_account_data.sender != sender {
+            msg!("Sender: stored {:?}, provided {:?}", lock_account_data.sender, sender);
+            return Err(SolBridgeProgramError::SenderMismatch.into());
+        }
+        if lock_account_data.recipient != recipient {
+            msg!("Recipient: stored {:?}, provided {:?}", lock_account_data.recipient, recipient);
+            return Err(SolBridgeProgramError::RecipientMismatch.into());
+        }
+        if lock_account_data.destination != destination {
+            msg!("Destination: stored {:?}, provided {:?}", lock_account_data.destination, destination);
+            return Err(SolBridgeProgramError::DestinationMismatch.into());
+        }
+        if lock_account_data.amount != amount {
+            msg!("Amount: stored {}, provided {}", lock_account_data.amount, amount);
+            return Err(SolBridgeProgramError::AmountMismatch.into());
+        }
+        Ok(())
+    }
+
+    fn check_sender_allowed(
+        program_id: &Pubkey,
+        bridge_account: &Pubkey,
+        sender_allowed_account_info: &AccountInfo,
+        source: BlockchainId,
+        sender: Address
+    ) -> ProgramResult {
+        validate_sender_allowed_and_get_bump_seed(
+            program_id,
+            bridge_account,
+            &source,
+            &sender,
+            sender_allowed_account_info.key
+        )?;
+
+        if sender_allowed_account_info.data_is_empty() {
+            return Err(SolBridgeProgramError::SenderNotAllowed.into());
+        }
+
+        assert_owned_by(sender_allowed_account_info, program_id)?;
+        let sender_allowed_account_data = SenderAllowed::unpack(&sender_allowed_account_info.data.borrow())?;
+        sender_allowed_account_data.check_initialized(true)?;
+
+        if !sender_allowed_account_data.allowed {
+            return Err(SolBridgeProgramError::SenderNotAllowed.into());
+        }
+        Ok(())
+    }
+
+    fn check_token_registered(
+        program_id: &Pubkey,
+        bridge_account: &Pubkey,
+        token_info_account_info: &AccountInfo,
+        token_source: BlockchainId,
+        token_source_address: Address
+    ) -> Result<TokenInfo, ProgramError> {
+        validate_token_info_and_get_bump_seed(
+            program_id,
+            bridge_account,
+            &token_source,
+            &token_source_address,
+            token_info_account_info.key
+        )?;
+
+        if token_info_account_info.data_is_empty() {
+            return Err(SolBridgeProgramError::TokenNotRegistered.into());
+        }
+
+        assert_owned_by(token_info_account_info, program_id)?;
+        let token_info_account_data = TokenInfo::unpack(&token_info_account_info.data.borrow())?;
+        token_info_account_data.check_initialized(true)?;
+
+        Ok(token_info_account_data)
+    }
+
+    fn get_route_fee_bps(
+        program_id: &Pubkey,
+        bridge_account: &Pubkey,
+        route_account_info: &AccountInfo,
+        source: BlockchainId,
+        destination: BlockchainId
+    ) -> Result<Option<u16>, ProgramError> {
+        validate_route_and_get_bump_seed(
+            program_id,
+            bridge_account,
+            &source,
+            &destination,
+            route_account_info.key
+        )?;
+
+        if route_account_info.data_is_empty() {
+            return Ok(None);
+        }
+
+        assert_owned_by(route_account_info, program_id)?;
+        let route_account_data = Route::unpack(&route_account_info.data.borrow())?;
+        route_account_data.check_initialized(true)?;
+
+        Ok(if route_account_data.fee_override { Some(route_account_data.fee_bps) } else { None })
+    }
+
+    /// Move `amount` of the registered token mint from the sender token account to the escrow,
+    /// the sender owns the token account and signs the transfer
+    fn transfer_to_escrow<'a>(
+        program_id: &Pubkey,
+        bridge_account_info: &AccountInfo<'a>,
+        token_info_account_info: &AccountInfo<'a>,
+        token_source: BlockchainId,
+        token_source_address: Address,
+        sender_token_account_info: &AccountInfo<'a>,
+        sender_token_owner_info: &AccountInfo<'a>,
+        escrow_account_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        sender: Address,
+        amount: u64
+    ) -> ProgramResult {
+        if *token_program_info.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_owned_by(sender_token_account_info, &spl_token::id())?;
+        let sender_token_account_data = spl_token::state::Account::unpack(&sender_token_account_info.data.borrow())?;
+
+        if sender_token_account_data.owner.to_bytes() != sender {
+            msg!("Token account is not owned by the sender");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if *sender_token_owner_info.key != sender_token_account_data.owner {
+            msg!("Token owner account does not match the token account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if !sender_token_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let token_info_account_data = Self::check_token_registered(
+            program_id,
+            bridge_account_info.key,
+            token_info_account_info,
+            token_source,
+            token_source_address
+        )?;
+
+        if sender_token_account_data.mint != token_info_account_data.mint {
+            msg!("Token account mint {} differs from the registered mint {}", sender_token_account_data.mint, token_info_account_data.mint);
+            return Err(SolBridgeProgramError::MintMismatch.into());
+        }
+
+        validate_escrow_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &token_info_account_data.mint,
+            escrow_account_info.key
+        )?;
+
+        invoke(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                sender_token_account_info.key,
+                escrow_account_info.key,
+                sender_token_owner_info.key,
+                &[],
+                amount,
+            )?,
+            &[
+                sender_token_account_info.clone(),
+                escrow_account_info.clone(),
+                sender_token_owner_info.clone(),
+                token_program_info.clone(),
+            ],
+        )
+    }
+
+    fn check_validator_can_sign(validator_data: &Validator, payer_account_info: &AccountInfo, source: BlockchainId) -> ProgramResult {
+        if validator_data.owner != *payer_account_info.key {
+            msg!("Payer is not the validator");
+            return Err(SolBridgeProgramError::UnauthorizedValidator.into());
+        }
+
+        Self::check_validator_state(validator_data, source)
+    }
+
+    fn check_validator_state(validator_data: &Validator, source: BlockchainId) -> ProgramResult {
+        if validator_data.blockchain_id != source {
+            msg!("Invalid validator type");
+            return Err(SolBridgeProgramError::ValidatorChainMismatch.into());
+        }
+
+        if !validator_data.active {
+            return Err(SolBridgeProgramError::ValidatorInactive.into());
+        }
+
+        if validator_data.paused {
+            return Err(SolBridgeProgramError::ValidatorPaused.into());
+        }
+
+        Ok(())
+    }
+
+    /// Check a signature submitted by a relayer rather than the validator owner was verified by
+    /// a precompile instruction preceding the current one: secp256k1 over the EIP-191 message, ed25519 over the hash
+    fn check_relayed_signature(
+        validator_data: &Validator,
+        message_hash: &[u8; 32],
+        signature: &[u8; 65],
+        instructions_info: &AccountInfo
+    ) -> ProgramResult {
+        let key_type = KeyType::try_from(validator_data.key_type)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let verified = match key_type {
+            KeyType::Secp256k1 => {
+                let message = [ETH_SIGNED_MESSAGE_PREFIX, message_hash.as_ref()].concat();
+                has_secp256k1_signature(instructions_info, &validator_data.pub_key, &message, signature)?
+            }
+            KeyType::Ed25519 => has_ed25519_signature(instructions_info, &validator_data.pub_key, message_hash, &signature[..64])?,
+        };
+        if !verified {
+            return Err(SolBridgeProgramError::Secp256InstructionLosing.into());
+        }
+        Ok(())
+    }
+
+    /// Check the validator signs the lock, the same way for new locks, existing locks and reverts:
+    /// the validator owner paying for the signature attests with the transaction signature, any other
+    /// payer relays a signature checked by a preceding precompile instruction. Returns the signature
+    /// bytes kept on the signature account, zeros for the owner attestation
+    fn check_validator_signature(
+        validator_data: &Validator,
+        payer_account_info: &AccountInfo,
+        source: BlockchainId,
+        message_hash: &[u8; 32],
+        signature: &[u8; 65],
+        instructions_info: &AccountInfo
+    ) -> Result<[u8; 65], ProgramError> {
+        if validator_data.owner == *payer_account_info.key {
+            Self::check_validator_can_sign(validator_data, payer_account_info, source)?;
+            return Ok([0; 65]);
+        }
+
+        Self::check_relayed_signature(validator_data, message_hash, signature, instructions_info)?;
+        Self::check_validator_state(validator_data, source)?;
+        Ok(*signature)
+    }
+
+    /// Record the nonce of a signature, it has to be above the last nonce used by the validator so
+    /// nonces skipped by signatures that never land don't block the validator
+    fn use_validator_nonce(validator_account_info: &AccountInfo, validator_data: &mut Validator, nonce: u64) -> ProgramResult {
+        if nonce <= validator_data.nonce {
+            msg!("Validator nonce {} is not above {}", nonce, validator_data.nonce);
+            return Err(SolBridgeProgramError::StaleNonce.into());
+        }
+        validator_data.nonce = nonce;
+        validator_data.serialize(&mut *validator_account_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn add_lock_signature<'a>(
+        program_id: &Pubkey,
+        bridge_account_info: &AccountInfo<'a>,
+        bridge_authority_info: &AccountInfo<'a>,
+        bump_seed: u8,
+        blockchain_data: &Blockchain,
+        validator_account_info: &AccountInfo<'a>,
+        validator_data: &Validator,
+        lock_account_info: &AccountInfo<'a>,
+        lock_data: &mut Lock,
+        signature_account_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        rent: &Rent,
+        clock: &Clock,
+        revert: bool,
+        signature: [u8; 65]
+    ) -> ProgramResult {
+        check_and_get_validator_account_seed(
+            program_id,
+            validator_data.blockchain_id,
+            validator_data.index,
+            bridge_authority_info.key,
+            validator_account_info.key
+        )?;
+
+        let signature_seed = check_and_get_signature_account_seed(
+            program_id,
+            lock_data.source,
+            lock_data.lock_id,
+            validator_data.index,
+            revert,
+            bridge_authority_info.key,
+            signature_account_info.key
+        )?;
+
+        if !signature_account_info.data_is_empty() {
+            msg!("Validator {} has already signed the lock", validator_data.index);
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        create_account_with_seed(
+            payer_info,
+            signature_account_info,
+            bridge_authority_info,
+            signature_seed,
+            Signature::LEN,
+            rent,
+            program_id,
+            bridge_account_info.key.as_ref(),
+            bump_seed,
+        )?;
+
+        let signature = Signature::new(
+            lock_data.source,
+            lock_data.lock_id,
+            *bridge_account_info.key,
+            signature,
+            *validator_account_info.key,
+            validator_data.index,
+            clock.unix_timestamp);
+        signature.serialize(&mut *signature_account_info.data.borrow_mut())?;
+
+        let signatures = if revert {
+            lock_data.revert_signatures = checked_increment(lock_data.revert_signatures)?;
+            lock_data.revert_signatures
+        } else {
+            lock_data.signatures = checked_increment(lock_data.signatures)?;
+            lock_data.signatures
+        };
+        if !lock_data.finalized
+            && blockchain_data.threshold > 0
+            && signatures >= blockchain_data.threshold {
+            msg!("Lock finalized");
+            lock_data.finalized = true;
+        }
+        lock_data.serialize(&mut *lock_account_info.data.borrow_mut())?;
+
+        BridgeEvent::SignatureAdded(SignatureAddedEvent {
+            source: lock_data.source,
+            lock_id: lock_data.lock_id,
+            validator_index: validator_data.index,
+            signatures_total: signatures,
+        }).emit()?;
+
+        Ok(())
+    }
+
+    /// Pay the tip set by the funder of the sender tip vault to the payer, the vault keeps its rent
+    fn pay_tip(
+        program_id: &Pubkey,
+        bridge_account_info: &AccountInfo,
+        tip_vault_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        rent: &Rent,
+        source: BlockchainId,
+        sender: Address
+    ) -> ProgramResult {
+        validate_tip_vault_and_get_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            &source,
+            &sender,
+            tip_vault_info.key
+        )?;
+        assert_owned_by(tip_vault_info, program_id)?;
+        let tip_vault_data = TipVault::unpack(&tip_vault_info.data.borrow())?;
+        tip_vault_data.check_initialized(true)?;
+
+        let remaining = tip_vault_info.lamports().checked_sub(tip_vault_data.tip);
+        if remaining.map_or(true, |remaining| remaining < TipVault::rent_exempt_balance(rent)) {
+            msg!("Tip vault balance is insufficient");
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        let payer_lamports = payer_info.lamports()
+            .checked_add(tip_vault_data.tip)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        **tip_vault_info.lamports.borrow_mut() -= tip_vault_data.tip;
+        **payer_info.lamports.borrow_mut() = payer_lamports;
+
+        Ok(())
+    }
+
+    fn get_blockchain_data_as_owner(
+        program_id: &Pubkey,
+        bridge_account_info: &AccountInfo,
+        blockchain_account_info: &AccountInfo,
+        bridge_authority_info: &AccountInfo,
+        owner_account_info: &AccountInfo,
+        blockchain_id: BlockchainId
+    ) -> Result<Blockchain, ProgramError> {
+        let bridge_account_data = Self::check_bridge_owner(program_id, bridge_account_info, owner_account_info)?;
+
+        validate_bridge_authority_with_bump_seed(
+            program_id,
+            bridge_account_info.key,
+            bridge_account_data.authority_bump,
+            bridge_authority_info.key,
+        )?;
+
+        check_and_get_blockchain_account_seed(
+            program_id,
+            blockchain_id,
+            bridge_authority_info.key,
+            blockchain_account_info.key
+        )?;
+
+        assert_owned_by(blockchain_account_info, program_id)?;
+        let blockchain_account_data = Blockchain::unpack(&blockchain_account_info.data.borrow())?;
+        blockchain_account_data.check_initialized(true)?;
+
+        if blockchain_account_data.bridge != *bridge_account_info.key {
+            msg!("Blockchain does not belong to the bridge");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(blockchain_account_data)
+    }
+
+    fn check_bridge_owner(program_id: &Pubkey, bridge_account_info: &AccountInfo, owner_account_info: &AccountInfo) -> Result<Bridge, ProgramError> {
+        if !owner_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        if bridge_account_data.owner != *owner_account_info.key {
+            msg!("Signer is not the bridge owner");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(bridge_account_data)
+    }
+
+    fn create_blockchain<'a>(
+        program_id: &Pubkey,
+        bridge_account_info: &AccountInfo<'a>,
+        blockchain_account_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        bridge_authority_info: &AccountInfo<'a>,
+        bump_seed: u8,
+        rent: &Rent,
+        blockchain_id: BlockchainId,
+        contract_address: Address,
+        max_validators: u64
+    ) -> ProgramResult {
+        let seed = check_and_get_blockchain_account_seed(
+            program_id,
+            blockchain_id,
+            bridge_authority_info.key,
+            blockchain_account_info.key
+        )?;
+
+        create_account_with_seed(
+            payer_info,
+            blockchain_account_info,
+            bridge_authority_info,
+            seed,
+            Blockchain::LEN,
+            rent,
+            program_id,
+            bridge_account_info.key.as_ref(),
+            bump_seed,
+        )?;
+
+        let blockchain = Blockchain::new(
+            *bridge_account_info.key,
+            blockchain_id,
+            contract_address,
+            max_validators);
+        blockchain.serialize(&mut *blockchain_account_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Recipient of a lock to Solana must be an account that can own the delivered tokens,
+    /// the curve can't be checked on-chain so the default address and the bridge accounts are rejected
+    fn check_solana_recipient(
+        bridge_account_info: &AccountInfo,
+        bridge_authority_info: &AccountInfo,
+        recipient: Address
+    ) -> ProgramResult {
+        let recipient = Pubkey::new_from_array(recipient);
+        if recipient == Pubkey::default() || recipient == *bridge_account_info.key || recipient == *bridge_authority_info.key {
+            msg!("Recipient {} is not a valid Solana account", recipient);
+            return Err(SolBridgeProgramError::InvalidAddress.into());
+        }
+        Ok(())
+    }
+
+    fn check_bridge_owner_or_guardian(program_id: &Pubkey, bridge_account_info: &AccountInfo, signer_account_info: &AccountInfo) -> Result<Bridge, ProgramError> {
+        if !signer_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_owned_by(bridge_account_info, program_id)?;
+        let bridge_account_data = Bridge::unpack(&bridge_account_info.data.borrow())?;
+        bridge_account_data.check_initialized(true)?;
+
+        if bridge_account_data.owner != *signer_account_info.key && bridge_account_data.guardian != *signer_account_info.key {
+            msg!("Signer is neither the bridge owner nor the guardian");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(bridge_account_data)
+    }
+
+    /// User data of the address, creating the account if needed, the flag tells whether it was created
+    fn get_or_create_user_data<'a>(program_id: &Pubkey, blockchain_id: BlockchainId, user_address: Address, user_authority_info: & AccountInfo<'a>, user_info: & AccountInfo<'a>, payer_info: & AccountInfo<'a>, rent: & Rent) -> Result<(User, bool), ProgramError> {
+        msg!("get_or_create_user_data");
+        check_user_authority_unused(user_authority_info)?;
+        let bump_seed = validate_user_address_authority_and_get_bump_seed(program_id, user_address, user_authority_info.key)?;
+        let seed = check_and_get_user_account_seed(program_id, blockchain_id, user_authority_info.key, user_info.key)?;
+        return if user_info.data_is_empty() {
+            create_account_with_seed(
+                payer_info,
+                user_info,
+                user_authority_info,
+                seed,
+                User::LEN,
+                rent,
+                program_id,
+                user_address.as_ref(),
+                bump_seed,
+            )?;
+            Ok((User::new(blockchain_id, user_address), true))
+        } else {
+            assert_owned_by(user_info, program_id)?;
+            let user_data = User::unpack(&user_info.data.borrow())?;
+            user_data.check_initialized(true)?;
+            if user_data.blockchain_id != blockchain_id || user_data.address != user_address {
+                msg!("User account belongs to another user");
+                return Err(ProgramError::InvalidArgument);
+            }
+            Ok((user_data, false))
+        }
+    }
+
+    /// Release the lock finalized for the user, the user can't be closed while its locks are in flight
+    fn release_user_lock(program_id: &Pubkey, blockchain_id: BlockchainId, user_address: Address, user_authority_info: &AccountInfo, user_info: &AccountInfo) -> ProgramResult {
+        validate_user_address_authority_and_get_bump_seed(program_id, user_address, user_authority_info.key)?;
+        check_and_get_user_account_seed(program_id, blockchain_id, user_authority_info.key, user_info.key)?;
+        assert_owned_by(user_info, program_id)?;
+        let mut user_data = User::unpack(&user_info.data.borrow())?;
+        user_data.check_initialized(true)?;
+        user_data.in_flight = user_data.in_flight
+            .checked_sub(1)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        user_data.serialize(&mut *user_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Add the lock amount to the volume of the current window, starting a new window once a day has passed
+    fn record_volume(blockchain_data: &mut Blockchain, amount: u128, clock: &Clock) -> ProgramResult {
+        let window_end = blockchain_data.volume_window_start
+            .checked_add(VOLUME_WINDOW)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        if clock.unix_timestamp >= window_end {
+            blockchain_data.volume_window_start = clock.unix_timestamp;
+            blockchain_data.volume_in_window = 0;
+        }
+
+        let volume = blockchain_data.volume_in_window
+            .checked_add(amount)
+            .ok_or(SolBridgeProgramError::Overflow)?;
+        if blockchain_data.daily_limit > 0 && volume > blockchain_data.daily_limit {
+            msg!("Volume {} exceeds the daily limit {}", volume, blockchain_data.daily_limit);
+            return Err(SolBridgeProgramError::DailyLimitExceeded.into());
+        }
+        blockchain_data.volume_in_window = volume;
+        Ok(())
+    }
+
+    /// Record the lock created for the remote transaction, a transaction claimed by another lock
+    /// or by a closed lock is rejected
+    fn index_lock_tx<'a>(
+        program_id: &Pubkey,
+        bridge: &Pubkey,
+        source: BlockchainId,
+        tx_id: TxId,
+        tx_index_info: &AccountInfo<'a>,
+        lock_account_info: &AccountInfo<'a>,
+        payer_info: &AccountInfo<'a>,
+        rent: &Rent
+    ) -> ProgramResult {
+        let bump_seed = validate_tx_index_and_get_bump_seed(program_id, bridge, &source, &tx_id, tx_index_info.key)?;
+
+        if !tx_index_info.data_is_empty() {
+            assert_owned_by(tx_index_info, program_id)?;
+            let tx_index_data = TxIndex::unpack(&tx_index_info.data.borrow())?;
+            tx_index_data.check_initialized(true)?;
+            if tx_index_data.lock_account != *lock_account_info.key {
+                msg!("Transaction is locked by {}", tx_index_data.lock_account);
+            } else {
+                msg!("Lock has been closed");
+            }
+            return Err(SolBridgeProgramError::TxAlreadyLocked.into());
+        }
+
+        create_program_account(
+            payer_info,
+            tx_index_info,
+            TxIndex::LEN,
+            rent,
+            program_id,
+            &[bridge.as_ref(), b"tx", source.as_ref(), &tx_id[..32], &tx_id[32..], &[bump_seed]],
+        )?;
+
+        let tx_index = TxIndex::new(*bridge, source, tx_id, *lock_account_info.key);
+        tx_index.serialize(&mut *tx_index_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn create_lock_tx_account<'a>(
+        program_id: &Pubkey,
+        blockchain_id: BlockchainId,
+        payer_info: &AccountInfo<'a>,
+        lock_tx_info: &AccountInfo<'a>,
+        lock_info: &AccountInfo<'a>,
+        user_authority_info: &AccountInfo<'a>,
+        user_address: Address, index: u64,
+        lock: &Lock,
+        tx_id: TxId,
+        reverted: bool,
+        tx_type: &str,
+        rent: &Rent) -> ProgramResult {
+        if lock.tx_id != tx_id {
+            msg!("Tx id: lock {:?}, provided {:?}", lock.tx_id, tx_id);
+            return Err(SolBridgeProgramError::TxIdMismatch.into());
+        }
+
+        let bump_seed = validate_user_address_authority_and_get_bump_seed(program_id, user_address, user_authority_info.key)?;
+        let seed = check_and_get_lock_tx_account_seed(program_id, blockchain_id, index, tx_type, user_authority_info.key, lock_tx_info.key)?;
+
+        if lock_tx_info.lamports() > 0 {
+            // The record is left by a closed user, the user created again counts from zero and takes it over
+            LockTx::unpack(&lock_tx_info.data.borrow())?.check_initialized(true)?;
+        } else {
+            create_account_with_seed(
+                payer_info,
+                lock_tx_info,
+                user_authority_info,
+                seed,
+                LockTx::LEN,
+                rent,
+                program_id,
+                user_address.as_ref(),
+                bump_seed,
+            )?;
+        }
+
+        LockTx::new(lock.tx_id, lock.source, lock.lock_id, *lock_info.key, reverted).serialize(&mut *lock_tx_info.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Processes an instruction
+    pub fn process_instruction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        input: &[u8],
+    ) -> ProgramResult {
+        let instruction = BridgeProgramInstruction::try_from_slice(input)
+            .or(Err(ProgramError::InvalidInstructionData))?;
+        match instruction {
+            BridgeProgramInstruction::InitializeBridge => {
+                msg!("Instruction: InitializeBridge");
+                Self::process_init_bridge(program_id, accounts)
+            },
+            BridgeProgramInstruction::AddBlockchain {contract_address, blockchain_id, max_validators} => {
+                msg!("Instruction: AddBlockchain");
+                Self::process_add_blockchain(program_id, accounts, blockchain_id, contract_address, max_validators)
+            },
+            BridgeProgramInstruction::AddValidator {blockchain_id, pub_key, key_type} => {
+                msg!("Instruction: AddValidator");
+                Self::process_add_validator(program_id, accounts, blockchain_id, pub_key, key_type)
+            }
+            BridgeProgramInstruction::AddSignature {signature, token_source, token_source_address, source, tx_id, lock_id, destination,sender,  recipient, amount, revert, with_token, with_tip, nonce} => {
+                msg!("Instruction: AddSignature");
+                Self::process_add_signature(program_id, accounts, signature, token_source, token_source_address, source, tx_id, lock_id, destination, sender, recipient, amount, revert, with_token, with_tip, nonce)
+            }
+            BridgeProgramInstruction::SetValidatorActive {blockchain_id, index, active} => {
+                msg!("Instruction: SetValidatorActive");
+                Self::process_set_validator_active(program_id, accounts, blockchain_id, index, active)
+            }
+            BridgeProgramInstruction::UpdateContractAddress {blockchain_id, contract_address} => {
+                msg!("Instruction: UpdateContractAddress");
+                Self::process_update_contract_address(program_id, accounts, blockchain_id, contract_address)
+            }
+            BridgeProgramInstruction::SetDestinationFee {blockchain_id, destination_fee_bps} => {
+                msg!("Instruction: SetDestinationFee");
+                Self::process_set_destination_fee(program_id, accounts, blockchain_id, destination_fee_bps)
+            }
+            BridgeProgramInstruction::SetDailyLimit {blockchain_id, daily_limit} => {
+                msg!("Instruction: SetDailyLimit");
+                Self::process_set_daily_limit(program_id, accounts, blockchain_id, daily_limit)
+            }
+            BridgeProgramInstruction::SetMinAmount {blockchain_id, min_amount} => {
+                msg!("Instruction: SetMinAmount");
+                Self::process_set_min_amount(program_id, accounts, blockchain_id, min_amount)
+            }
+            BridgeProgramInstruction::SetThreshold {blockchain_id, threshold} => {
+                msg!("Instruction: SetThreshold");
+                Self::process_set_threshold(program_id, accounts, blockchain_id, threshold)
+            }
+            BridgeProgramInstruction::HasValidatorSigned {source, lock_id, validator_index, revert} => {
+                msg!("Instruction: HasValidatorSigned");
+                Self::process_has_validator_signed(program_id, accounts, source, lock_id, validator_index, revert)
+            }
+            BridgeProgramInstruction::SetWrappedMode {enabled} => {
+                msg!("Instruction: SetWrappedMode");
+                Self::process_set_wrapped_mode(program_id, accounts, enabled)
+            }
+            BridgeProgramInstruction::RegisterToken {token_source, token_source_address, mint} => {
+                msg!("Instruction: RegisterToken");
+                Self::process_register_token(program_id, accounts, token_source, token_source_address, mint)
+            }
+            BridgeProgramInstruction::Ping => {
+                msg!("Instruction: Ping");
+                Self::process_ping(program_id, accounts)
+            }
+            BridgeProgramInstruction::SetValidatorPaused {blockchain_id, index, paused} => {
+                msg!("Instruction: SetValidatorPaused");
+                Self::process_set_validator_paused(program_id, accounts, blockchain_id, index, paused)
+            }
+            BridgeProgramInstruction::InitializeEscrow {mint} => {
+                msg!("Instruction: InitializeEscrow");
+                Self::process_initialize_escrow(program_id, accounts, mint)
+            }
+            BridgeProgramInstruction::CheckEscrowSolvency {mint} => {
+                msg!("Instruction: CheckEscrowSolvency");
+                Self::process_check_escrow_solvency(program_id, accounts, mint)
+            }
+            BridgeProgramInstruction::AllowSender {source, sender} => {
+                msg!("Instruction: AllowSender");
+                Self::process_set_sender_allowed(program_id, accounts, source, sender, true)
+            }
+            BridgeProgramInstruction::BlockSender {source, sender} => {
+                msg!("Instruction: BlockSender");
+                Self::process_set_sender_allowed(program_id, accounts, source, sender, false)
+            }
+            BridgeProgramInstruction::SetSenderAllowlistEnforced {blockchain_id, enforced} => {
+                msg!("Instruction: SetSenderAllowlistEnforced");
+                Self::process_set_sender_allowlist_enforced(program_id, accounts, blockchain_id, enforced)
+            }
+            BridgeProgramInstruction::AddSignatureToExisting {source, lock_id, revert, validator_index, signature, nonce} => {
+                msg!("Instruction: AddSignatureToExisting");
+                Self::process_add_signature_to_existing(program_id, accounts, source, lock_id, revert, validator_index, signature, nonce)
+            }
+            BridgeProgramInstruction::SetRouteFee {source, destination, fee_bps} => {
+                msg!("Instruction: SetRouteFee");
+                Self::process_set_route_fee(program_id, accounts, source, destination, fee_bps)
+            }
+            BridgeProgramInstruction::SetFee {blockchain_id, fee_bp} => {
+                msg!("Instruction: SetFee");
+                Self::process_set_fee(program_id, accounts, blockchain_id, fee_bp)
+            }
+            BridgeProgramInstruction::SetFeeSplit {blockchain_id, validator_fee_bp, protocol_fee_bp} => {
+                msg!("Instruction: SetFeeSplit");
+                Self::process_set_fee_split(program_id, accounts, blockchain_id, validator_fee_bp, protocol_fee_bp)
+            }
+            BridgeProgramInstruction::SetStrictMode {enabled} => {
+                msg!("Instruction: SetStrictMode");
+                Self::process_set_strict_mode(program_id, accounts, enabled)
+            }
+            BridgeProgramInstruction::Withdraw {source, lock_id} => {
+                msg!("Instruction: Withdraw");
+                Self::process_withdraw(program_id, accounts, source, lock_id)
+            }
+            BridgeProgramInstruction::AddRevertSignature {source, lock_id, validator_index, sent_index, signature, nonce} => {
+                msg!("Instruction: AddRevertSignature");
+                Self::process_add_revert_signature(program_id, accounts, source, lock_id, validator_index, sent_index, signature, nonce)
+            }
+            BridgeProgramInstruction::SetDisputeWindow {dispute_window} => {
+                msg!("Instruction: SetDisputeWindow");
+                Self::process_set_dispute_window(program_id, accounts, dispute_window)
+            }
+            BridgeProgramInstruction::RevertUnlock {source, lock_id} => {
+                msg!("Instruction: RevertUnlock");
+                Self::process_revert_unlock(program_id, accounts, source, lock_id)
+            }
+            BridgeProgramInstruction::CloseLock {source, lock_id} => {
+                msg!("Instruction: CloseLock");
+                Self::process_close_lock(program_id, accounts, source, lock_id)
+            }
+            BridgeProgramInstruction::AddSignatureBatch {token_source, token_source_address, source, lock_id, tx_id, destination, sender, recipient, amount, revert, with_token, with_tip, signatures} => {
+                msg!("Instruction: AddSignatureBatch");
+                Self::process_add_signature_batch(program_id, accounts, token_source, token_source_address, source, tx_id, lock_id, destination, sender, recipient, amount, revert, with_token, with_tip, signatures)
+            }
+            BridgeProgramInstruction::CloseSignature {source, lock_id, validator_index} => {
+                msg!("Instruction: CloseSignature");
+                Self::process_close_signature(program_id, accounts, source, lock_id, validator_index)
+            }
+            BridgeProgramInstruction::CloseUser {blockchain_id, address} => {
+                msg!("Instruction: CloseUser");
+                Self::process_close_user(program_id, accounts, blockchain_id, address)
+            }
+            BridgeProgramInstruction::MigrateAccount => {
+                msg!("Instruction: MigrateAccount");
+                Self::process_migrate_account(program_id, accounts)
+            }
+            BridgeProgramInstruction::AddBlockchainBatch {entries} => {
+                msg!("Instruction: AddBlockchainBatch");
+                Self::process_add_blockchain_batch(program_id, accounts, entries)
+            }
+            BridgeProgramInstruction::SetGuardian {guardian} => {
+                msg!("Instruction: SetGuardian");
+                Self::process_set_guardian(program_id, accounts, guardian)
+            }
+            BridgeProgramInstruction::SetSolanaChainId {solana_chain_id} => {
+                msg!("Instruction: SetSolanaChainId");
+                Self::process_set_solana_chain_id(program_id, accounts, solana_chain_id)
+            }
+            BridgeProgramInstruction::FundTipVault {source, sender, tip, deposit} => {
+                msg!("Instruction: FundTipVault");
+                Self::process_fund_tip_vault(program_id, accounts, source, sender, tip, deposit)
             }
         }
     }
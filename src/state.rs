@@ -1,100 +1,403 @@
 //! State transition types
 //!
+//! Program accounts are allocated with exactly `LEN` bytes. Account data is
+//! loaded with `unpack`, which fails with `InvalidAccountData` on short buffers
+//! and on trailing bytes, so an over-allocated account is rejected instead of
+//! being silently accepted.
+//!
+//! Every account starts with an `AccountType` tag, `Versioned::check_initialized` rejects
+//! data of another account kind with `InvalidAccountType`.
 use crate::PROGRAM_VERSION;
+use crate::error::SolBridgeProgramError;
+use crate::utils::{chain_id_to_str, str_to_chain_id, BPS_DENOMINATOR};
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     pubkey::Pubkey,
     program_error::ProgramError,
     entrypoint::ProgramResult,
+    rent::Rent,
     msg
 };
+use std::{convert::TryFrom, fmt, str::FromStr};
 
 pub type TxId = [u8; 64];
 pub type Address = [u8; 32];
-pub type BlockchainId = [u8; 4];
+/// Blockchain identifier length, shorter identifiers are padded with trailing zero bytes
+pub const BLOCKCHAIN_ID_LEN: usize = 8;
+pub type BlockchainId = [u8; BLOCKCHAIN_ID_LEN];
+
+/// Blockchain identifier parsed from and displayed as a string, serialized as the raw `BlockchainId`
+#[derive(Clone, Copy, Debug, Default, BorshSerialize, BorshDeserialize, PartialEq, Eq, Hash)]
+pub struct ChainId(pub BlockchainId);
+
+impl FromStr for ChainId {
+    type Err = ProgramError;
+
+    /// Pads the id with trailing zero bytes, ids longer than `BLOCKCHAIN_ID_LEN` bytes are rejected
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        str_to_chain_id(s).map(ChainId)
+    }
+}
+
+impl fmt::Display for ChainId {
+    /// Writes the id without the trailing zero bytes
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match chain_id_to_str(&self.0) {
+            Ok(chain_id) => f.write_str(chain_id),
+            Err(_) => f.write_str(String::from_utf8_lossy(&self.0).trim_end_matches(0 as char)),
+        }
+    }
+}
+
+impl From<ChainId> for BlockchainId {
+    fn from(chain_id: ChainId) -> Self {
+        chain_id.0
+    }
+}
+
+impl From<BlockchainId> for ChainId {
+    fn from(blockchain_id: BlockchainId) -> Self {
+        ChainId(blockchain_id)
+    }
+}
+
+/// Tag stored in the first byte of every account, tells the account kinds apart
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountType {
+    /// Account is not initialized
+    Uninitialized,
+    /// Bridge account
+    Bridge,
+    /// Blockchain account
+    Blockchain,
+    /// Validator account
+    Validator,
+    /// Lock account
+    Lock,
+    /// Signature account
+    Signature,
+    /// User account
+    User,
+    /// Sent or received lock record
+    LockTx,
+    /// Registered token account
+    TokenInfo,
+    /// Sender allowlist entry
+    SenderAllowed,
+    /// Route account
+    Route,
+    /// Remote transaction index
+    TxIndex,
+    /// Relayer tip vault of the sender
+    TipVault,
+}
+
+impl TryFrom<u8> for AccountType {
+    type Error = ProgramError;
+
+    fn try_from(account_type: u8) -> Result<Self, Self::Error> {
+        match account_type {
+            0 => Ok(AccountType::Uninitialized),
+            1 => Ok(AccountType::Bridge),
+            2 => Ok(AccountType::Blockchain),
+            3 => Ok(AccountType::Validator),
+            4 => Ok(AccountType::Lock),
+            5 => Ok(AccountType::Signature),
+            6 => Ok(AccountType::User),
+            7 => Ok(AccountType::LockTx),
+            8 => Ok(AccountType::TokenInfo),
+            9 => Ok(AccountType::SenderAllowed),
+            10 => Ok(AccountType::Route),
+            11 => Ok(AccountType::TxIndex),
+            12 => Ok(AccountType::TipVault),
+            _ => Err(SolBridgeProgramError::InvalidAccountType.into()),
+        }
+    }
+}
+
+/// Signature scheme of a validator public key
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyType {
+    /// Ethereum address of a secp256k1 key, signatures are recovered on-chain
+    Secp256k1,
+    /// Ed25519 key, signatures are checked by a preceding ed25519 precompile instruction
+    Ed25519,
+}
+
+impl TryFrom<u8> for KeyType {
+    type Error = ProgramError;
+
+    fn try_from(key_type: u8) -> Result<Self, Self::Error> {
+        match key_type {
+            0 => Ok(KeyType::Secp256k1),
+            1 => Ok(KeyType::Ed25519),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+/// Serialized size of account data
+pub trait Packed {
+    /// Struct size
+    const LEN: usize;
+
+    /// Lamports needed to keep an account of this type rent-exempt
+    fn rent_exempt_balance(rent: &Rent) -> u64 {
+        rent.minimum_balance(Self::LEN)
+    }
+
+    /// Deserialize account data, rejecting short and over-allocated buffers
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> where Self: BorshDeserialize + Sized {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::try_from_slice(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// Versioned account data, the version tells initialized accounts apart
+pub trait Versioned {
+    /// Account kind stored in the tag
+    const ACCOUNT_TYPE: AccountType;
+
+    /// Data version
+    fn version(&self) -> u8;
+
+    /// Account type tag
+    fn account_type(&self) -> u8;
+
+    /// is initialized account method, an initialized account has the current version, older ones
+    /// have to go through `MigrateAccount` first, and must also carry the expected tag
+    fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
+        if expect_initialized && self.version() == 0 {
+            msg!("Account not initialized");
+            return Err(ProgramError::UninitializedAccount);
+        } else if expect_initialized && self.version() < PROGRAM_VERSION {
+            msg!("Account version {} needs migration to {}", self.version(), PROGRAM_VERSION);
+            return Err(SolBridgeProgramError::AccountNeedsMigration.into());
+        } else if expect_initialized && self.version() > PROGRAM_VERSION {
+            msg!("Account version {} is newer than the program", self.version());
+            return Err(ProgramError::InvalidAccountData);
+        } else if !expect_initialized && self.version() != 0 {
+            msg!("Account already initialized");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if expect_initialized && self.account_type() != Self::ACCOUNT_TYPE as u8 {
+            msg!("Invalid account type");
+            return Err(SolBridgeProgramError::InvalidAccountType.into());
+        }
+        Ok(())
+    }
+}
 
 
 /// Information about the bridge
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct Bridge {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
     /// Bridge owner account, signs secure instructions to the bridge
     pub owner: Pubkey,
+    /// Wrapped-token mode, locks require a registered token
+    pub wrapped_mode: bool,
+    /// Strict mode, new locks require a registered token, an allowed sender
+    /// and a configured route regardless of the wrapped mode and the
+    /// blockchain sender allowlist flag
+    pub strict_mode: bool,
+    /// Seconds after a withdrawal during which the owner can revert the unlock,
+    /// zero disables disputes
+    pub dispute_window: i64,
+    /// Guardian account, can pause validators but not unpause them or change the bridge
+    pub guardian: Pubkey,
+    /// Canonical bump seed of the bridge authority
+    pub authority_bump: u8,
+    /// Blockchain id of Solana, recipients of locks to it are checked as Solana accounts,
+    /// zero disables the check
+    pub solana_chain_id: BlockchainId,
+    /// Number of blockchains added to the bridge
+    pub blockchains: u64,
 }
 
 impl Bridge {
     /// Struct size
-    pub const LEN: usize = 33;
+    pub const LEN: usize = 93;
     /// Create new bridge entity
-    pub fn new(owner: Pubkey) -> Self {
+    pub fn new(owner: Pubkey, authority_bump: u8) -> Self {
         Self {
+            account_type: AccountType::Bridge as u8,
             version: PROGRAM_VERSION,
             owner,
+            wrapped_mode: false,
+            strict_mode: false,
+            dispute_window: 0,
+            guardian: Pubkey::default(),
+            authority_bump,
+            solana_chain_id: BlockchainId::default(),
+            blockchains: 0,
         }
     }
+}
 
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
+impl Packed for Bridge {
+    const LEN: usize = Bridge::LEN;
+}
+
+impl Versioned for Bridge {
+    const ACCOUNT_TYPE: AccountType = AccountType::Bridge;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Bridge layout of `PREVIOUS_VERSION`, read by `MigrateAccount`
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct BridgeV1 {
+    /// Account type tag
+    pub account_type: u8,
+    /// Data version
+    pub version: u8,
+    /// Bridge owner account
+    pub owner: Pubkey,
+    /// Wrapped-token mode
+    pub wrapped_mode: bool,
+    /// Strict mode
+    pub strict_mode: bool,
+    /// Dispute window in seconds
+    pub dispute_window: i64,
+}
+
+impl BridgeV1 {
+    /// Struct size
+    pub const LEN: usize = 44;
+
+    /// Convert into the current layout, the fields added since keep their defaults
+    /// apart from the bump seed of the bridge authority
+    pub fn migrate(self, authority_bump: u8) -> Bridge {
+        Bridge {
+            owner: self.owner,
+            wrapped_mode: self.wrapped_mode,
+            strict_mode: self.strict_mode,
+            dispute_window: self.dispute_window,
+            ..Bridge::new(self.owner, authority_bump)
         }
-        Ok(())
     }
 }
 
+impl Packed for BridgeV1 {
+    const LEN: usize = BridgeV1::LEN;
+}
+
 ///Information about blockchain
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct Blockchain {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
 
     /// Associated bridge address
     pub bridge: Pubkey,
 
-    /// Blockchain id (4 bytes of UTF-8)
+    /// Blockchain id (up to 8 bytes of UTF-8)
     pub blockchain_id: BlockchainId,
 
     /// Number of validators
     pub validators: u64,
 
-    /// Number of locks
+    /// Number of locks from this blockchain, the index of the next lock
     pub locks: u64,
 
     /// Address of contract for the bridge
-    pub contract_address: Address
+    pub contract_address: Address,
+
+    /// Fee in basis points deducted from transfers delivered to this blockchain
+    pub destination_fee_bps: u16,
+
+    /// Number of signatures required to finalize a lock, 0 disables finalization
+    pub threshold: u64,
+
+    /// Accept locks only from allowed senders
+    pub enforce_sender_allowlist: bool,
+
+    /// Protocol fee in basis points taken from transfers sent from this blockchain
+    pub fee_bp: u16,
+
+    /// Maximum number of validators that can be registered
+    pub max_validators: u64,
+
+    /// Start of the current volume window
+    pub volume_window_start: i64,
+
+    /// Amount of the locks created in the current volume window
+    pub volume_in_window: u128,
+
+    /// Maximum amount of the locks created in a volume window, 0 disables the limit
+    pub daily_limit: u128,
+
+    /// Minimum amount of a lock
+    pub min_amount: u128,
+
+    /// Share of the lock fee in basis points routed to the validators
+    pub validator_fee_bp: u16,
+
+    /// Share of the lock fee in basis points routed to the protocol
+    pub protocol_fee_bp: u16
 
 }
 
 impl Blockchain {
     /// Struct size
-    pub const LEN: usize = 85;
+    pub const LEN: usize = 171;
     /// Create new blockchain entity
-    pub fn new(bridge: Pubkey, blockchain_id: BlockchainId, contract_address: Address) -> Self {
+    pub fn new(bridge: Pubkey, blockchain_id: BlockchainId, contract_address: Address, max_validators: u64) -> Self {
         Self {
+            account_type: AccountType::Blockchain as u8,
             version: PROGRAM_VERSION,
             bridge,
             blockchain_id,
             locks: 0,
             validators: 0,
-            contract_address
+            contract_address,
+            destination_fee_bps: 0,
+            threshold: 0,
+            enforce_sender_allowlist: false,
+            fee_bp: 0,
+            max_validators,
+            volume_window_start: 0,
+            volume_in_window: 0,
+            daily_limit: 0,
+            min_amount: 0,
+            validator_fee_bp: 0,
+            protocol_fee_bp: BPS_DENOMINATOR as u16
         }
     }
+}
 
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
-        }
-        Ok(())
+impl Packed for Blockchain {
+    const LEN: usize = Blockchain::LEN;
+}
+
+impl Versioned for Blockchain {
+    const ACCOUNT_TYPE: AccountType = AccountType::Blockchain;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
     }
 }
 
@@ -102,10 +405,12 @@ impl Blockchain {
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct Validator {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
 
-    /// Blockchain id (4 bytes of UTF-8)
+    /// Blockchain id (up to 8 bytes of UTF-8)
     pub blockchain_id: BlockchainId,
 
     /// Validator index
@@ -115,33 +420,55 @@ pub struct Validator {
     pub pub_key: [u8; 32],
 
     /// Validator owner
-    pub owner: Pubkey
+    pub owner: Pubkey,
+
+    /// Signatures are accepted only from active validators
+    pub active: bool,
+
+    /// Signatures are temporarily rejected from paused validators
+    pub paused: bool,
+
+    /// Signature scheme of `pub_key`, see `KeyType`
+    pub key_type: u8,
+
+    /// Last nonce used by the validator in `AddSignature`, each signature has to use a greater one
+    pub nonce: u64
 
 }
 
 impl Validator {
     /// Struct size
-    pub const LEN: usize = 77;
+    pub const LEN: usize = 93;
     /// Create new validator entity
-    pub fn new(blockchain_id: BlockchainId, index: u64, pub_key: [u8; 32], owner: Pubkey) -> Self {
+    pub fn new(blockchain_id: BlockchainId, index: u64, pub_key: [u8; 32], key_type: KeyType, owner: Pubkey) -> Self {
         Self {
+            account_type: AccountType::Validator as u8,
             version: PROGRAM_VERSION,
             blockchain_id,
             index,
             pub_key,
-            owner
+            owner,
+            active: true,
+            paused: false,
+            key_type: key_type as u8,
+            nonce: 0
         }
     }
+}
 
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
-        }
-        Ok(())
+impl Packed for Validator {
+    const LEN: usize = Validator::LEN;
+}
+
+impl Versioned for Validator {
+    const ACCOUNT_TYPE: AccountType = AccountType::Validator;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
     }
 }
 
@@ -150,10 +477,12 @@ impl Validator {
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct Lock {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
 
-    /// Lock index within the bridge
+    /// Lock index within the source blockchain, the value of `Blockchain.locks` at creation
     pub index: u64,
 
     /// Lock id
@@ -184,18 +513,52 @@ pub struct Lock {
     pub destination: BlockchainId,
 
     /// Amount to lock for the transfer
-    pub amount: u64,
+    pub amount: u128,
+
+    /// Forward signature count
+    pub signatures: u64,
+
+    /// Destination fee, snapshotted when the lock is created
+    pub destination_fee: u128,
+
+    /// Signatures reached the blockchain threshold
+    pub finalized: bool,
+
+    /// Unix timestamp of the lock creation
+    pub created_at: i64,
+
+    /// Fee taken from the transfer, snapshotted when the lock is created
+    pub fee: u128,
+
+    /// Tokens have been released from the escrow to the recipient
+    pub withdrawn: bool,
+
+    /// Unix timestamp of the withdrawal
+    pub unlock_time: i64,
+
+    /// Withdrawal has been reverted by the owner within the dispute window
+    pub unlock_reverted: bool,
 
-    /// Signature count
-    pub signatures: u64
+    /// Part of the fee routed to the validators
+    pub validator_fee: u128,
+
+    /// Part of the fee routed to the protocol
+    pub protocol_fee: u128,
+
+    /// Revert signature count, reaches the threshold independently of the forward signatures
+    pub revert_signatures: u64,
+
+    /// Sender tokens have been transferred to the escrow when the lock was created
+    pub deposited: bool
 }
 
 impl Lock {
     /// Struct size
-    pub const LEN: usize = 237;
+    pub const LEN: usize = 350;
     /// Create new validator entity
-    pub fn new(index: u64, lock_id: u64, tx_id: TxId, bridge: Pubkey, token_source_address: Address, token_source: BlockchainId, source: BlockchainId, sender: Address, recipient: Address, destination: BlockchainId, amount: u64) -> Self {
+    pub fn new(index: u64, lock_id: u64, tx_id: TxId, bridge: Pubkey, token_source_address: Address, token_source: BlockchainId, source: BlockchainId, sender: Address, recipient: Address, destination: BlockchainId, amount: u128, destination_fee: u128, fee: u128, created_at: i64) -> Self {
         Self {
+            account_type: AccountType::Lock as u8,
             version: PROGRAM_VERSION,
             index,
             lock_id,
@@ -208,26 +571,76 @@ impl Lock {
             recipient,
             destination,
             amount,
-            signatures: 0
+            signatures: 0,
+            destination_fee,
+            finalized: false,
+            created_at,
+            fee,
+            withdrawn: false,
+            unlock_time: 0,
+            unlock_reverted: false,
+            validator_fee: 0,
+            protocol_fee: 0,
+            revert_signatures: 0,
+            deposited: false
         }
     }
 
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
+    /// Amount the recipient receives after the destination and protocol fees,
+    /// fails with `InvalidFee` when the fees exceed the amount
+    pub fn net_amount(&self) -> Result<u128, ProgramError> {
+        self.amount
+            .checked_sub(self.destination_fee)
+            .and_then(|amount| amount.checked_sub(self.fee))
+            .ok_or_else(|| SolBridgeProgramError::InvalidFee.into())
+    }
+
+    /// Summary of the lock progress against the threshold of the source blockchain
+    pub fn status(&self, threshold: u64) -> LockStatus {
+        LockStatus {
+            signatures: self.signatures,
+            threshold,
+            finalized: self.finalized,
+            withdrawn: self.withdrawn,
         }
-        Ok(())
     }
 }
 
+impl Packed for Lock {
+    const LEN: usize = Lock::LEN;
+}
+
+impl Versioned for Lock {
+    const ACCOUNT_TYPE: AccountType = AccountType::Lock;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// High-level status of a lock, not stored on-chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LockStatus {
+    /// Signatures collected
+    pub signatures: u64,
+    /// Signatures required to finalize the lock
+    pub threshold: u64,
+    /// Signatures reached the threshold
+    pub finalized: bool,
+    /// Tokens have been released to the recipient
+    pub withdrawn: bool,
+}
+
 /// Signature info
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct Signature {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
     /// Source blockchain identifier
@@ -236,44 +649,55 @@ pub struct Signature {
     pub lock_id: u64,
     /// Bridge reference
     pub bridge: Pubkey,
-    /// Oracle signature
+    /// Verified validator signature of the lock message hash, zeros when the validator owner
+    /// submitted the signature and attested with the transaction signature instead
     pub signature: [u8; 65],
     /// Validator public key
     pub validator: Pubkey,
     /// Validator index
-    pub validator_index: u64
+    pub validator_index: u64,
+    /// Unix timestamp of the signature submission
+    pub signed_at: i64
 }
 
 impl Signature {
     /// Struct size
-    pub const LEN: usize = 150;
+    pub const LEN: usize = 163;
     /// Create new validator entity
     pub fn new(source: BlockchainId,
                lock_id: u64,
                bridge: Pubkey,
                signature: [u8; 65],
                validator: Pubkey,
-               validator_index: u64) -> Self {
+               validator_index: u64,
+               signed_at: i64) -> Self {
         Self {
+            account_type: AccountType::Signature as u8,
             version: PROGRAM_VERSION,
             source,
             lock_id,
             bridge,
             signature,
             validator,
-            validator_index
+            validator_index,
+            signed_at
         }
     }
+}
+
+impl Packed for Signature {
+    const LEN: usize = Signature::LEN;
+}
+
+impl Versioned for Signature {
+    const ACCOUNT_TYPE: AccountType = AccountType::Signature;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
 
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
-        }
-        Ok(())
+    fn account_type(&self) -> u8 {
+        self.account_type
     }
 }
 
@@ -282,6 +706,8 @@ impl Signature {
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct User {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
     /// Blockchain ID
@@ -291,34 +717,48 @@ pub struct User {
     /// Number of sent transactions
     pub sent: u64,
     /// Number of received transactions
-    pub received: u64
+    pub received: u64,
+    /// Total amount sent
+    pub total_sent_amount: u128,
+    /// Total amount received
+    pub total_received_amount: u128,
+    /// Number of sent and received locks still collecting signatures
+    pub in_flight: u64
 }
 
 impl User {
     /// Struct size
-    pub const LEN: usize = 53;
+    pub const LEN: usize = 98;
     /// Create new validator entity
     pub fn new(blockchain_id: BlockchainId,
                address: Address) -> Self {
         Self {
+            account_type: AccountType::User as u8,
             version: PROGRAM_VERSION,
             blockchain_id,
             address,
             sent: 0,
             received: 0,
+            total_sent_amount: 0,
+            total_received_amount: 0,
+            in_flight: 0,
         }
     }
+}
 
-    /// is initialized account method
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
-        }
-        Ok(())
+impl Packed for User {
+    const LEN: usize = User::LEN;
+}
+
+impl Versioned for User {
+    const ACCOUNT_TYPE: AccountType = AccountType::User;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
     }
 }
 
@@ -326,6 +766,8 @@ impl User {
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
 pub struct LockTx {
+    /// Account type tag
+    pub account_type: u8,
     /// Data version
     pub version: u8,
     /// Lock transaction id
@@ -342,10 +784,11 @@ pub struct LockTx {
 
 impl LockTx {
     /// Struct size
-    pub const LEN: usize = 110;
+    pub const LEN: usize = 115;
     /// Create new validator entity
     pub fn new(tx_id: TxId, source: BlockchainId, lock_id: u64, lock_account: Pubkey, reverted: bool) -> Self {
         Self {
+            account_type: AccountType::LockTx as u8,
             version: PROGRAM_VERSION,
             tx_id,
             source,
@@ -354,16 +797,327 @@ impl LockTx {
             reverted
         }
     }
+}
 
-    /// is initialized account method
-    pub fn check_initialized(&self, expect_initialized: bool) -> ProgramResult {
-        if expect_initialized && self.version != PROGRAM_VERSION {
-            msg!("Account not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        } else if !expect_initialized && self.version == PROGRAM_VERSION {
-            msg!("Account already initialized");
-            return Err(ProgramError::AccountAlreadyInitialized);
+impl Packed for LockTx {
+    const LEN: usize = LockTx::LEN;
+}
+
+impl Versioned for LockTx {
+    const ACCOUNT_TYPE: AccountType = AccountType::LockTx;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Registered token info
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct TokenInfo {
+    /// Account type tag
+    pub account_type: u8,
+    /// Data version
+    pub version: u8,
+    /// Bridge reference
+    pub bridge: Pubkey,
+    /// Token source blockchain identifier
+    pub token_source: BlockchainId,
+    /// Token address from source blockchain
+    pub token_source_address: Address,
+    /// Wrapped token mint
+    pub mint: Pubkey
+}
+
+impl TokenInfo {
+    /// Struct size
+    pub const LEN: usize = 106;
+    /// Create new token info entity
+    pub fn new(bridge: Pubkey, token_source: BlockchainId, token_source_address: Address, mint: Pubkey) -> Self {
+        Self {
+            account_type: AccountType::TokenInfo as u8,
+            version: PROGRAM_VERSION,
+            bridge,
+            token_source,
+            token_source_address,
+            mint
         }
-        Ok(())
+    }
+}
+
+impl Packed for TokenInfo {
+    const LEN: usize = TokenInfo::LEN;
+}
+
+impl Versioned for TokenInfo {
+    const ACCOUNT_TYPE: AccountType = AccountType::TokenInfo;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Allowlist entry of the sender address
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct SenderAllowed {
+    /// Account type tag
+    pub account_type: u8,
+    /// Data version
+    pub version: u8,
+    /// Bridge reference
+    pub bridge: Pubkey,
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Sender address
+    pub sender: Address,
+    /// Sender may create locks
+    pub allowed: bool
+}
+
+impl SenderAllowed {
+    /// Struct size
+    pub const LEN: usize = 75;
+    /// Create new sender allowlist entity
+    pub fn new(bridge: Pubkey, source: BlockchainId, sender: Address, allowed: bool) -> Self {
+        Self {
+            account_type: AccountType::SenderAllowed as u8,
+            version: PROGRAM_VERSION,
+            bridge,
+            source,
+            sender,
+            allowed
+        }
+    }
+}
+
+impl Packed for SenderAllowed {
+    const LEN: usize = SenderAllowed::LEN;
+}
+
+impl Versioned for SenderAllowed {
+    const ACCOUNT_TYPE: AccountType = AccountType::SenderAllowed;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Settings of the source to destination blockchain route
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct Route {
+    /// Account type tag
+    pub account_type: u8,
+    /// Data version
+    pub version: u8,
+    /// Bridge reference
+    pub bridge: Pubkey,
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Destination blockchain identifier
+    pub destination: BlockchainId,
+    /// Route fee is used instead of the destination blockchain fee
+    pub fee_override: bool,
+    /// Route fee in basis points
+    pub fee_bps: u16
+}
+
+impl Route {
+    /// Struct size
+    pub const LEN: usize = 53;
+    /// Create new route entity
+    pub fn new(bridge: Pubkey, source: BlockchainId, destination: BlockchainId, fee_bps: Option<u16>) -> Self {
+        Self {
+            account_type: AccountType::Route as u8,
+            version: PROGRAM_VERSION,
+            bridge,
+            source,
+            destination,
+            fee_override: fee_bps.is_some(),
+            fee_bps: fee_bps.unwrap_or_default()
+        }
+    }
+}
+
+impl Packed for Route {
+    const LEN: usize = Route::LEN;
+}
+
+impl Versioned for Route {
+    const ACCOUNT_TYPE: AccountType = AccountType::Route;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Index of the lock created for a remote transaction
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct TxIndex {
+    /// Account type tag
+    pub account_type: u8,
+    /// Data version
+    pub version: u8,
+    /// Bridge reference
+    pub bridge: Pubkey,
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Remote transaction id
+    pub tx_id: TxId,
+    /// Lock created for the transaction
+    pub lock_account: Pubkey
+}
+
+impl TxIndex {
+    /// Struct size
+    pub const LEN: usize = 138;
+    /// Create new transaction index entity
+    pub fn new(bridge: Pubkey, source: BlockchainId, tx_id: TxId, lock_account: Pubkey) -> Self {
+        Self {
+            account_type: AccountType::TxIndex as u8,
+            version: PROGRAM_VERSION,
+            bridge,
+            source,
+            tx_id,
+            lock_account
+        }
+    }
+}
+
+impl Packed for TxIndex {
+    const LEN: usize = TxIndex::LEN;
+}
+
+impl Versioned for TxIndex {
+    const ACCOUNT_TYPE: AccountType = AccountType::TxIndex;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Lamports the transfer initiator set aside for relayers of the sender locks
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct TipVault {
+    /// Account type tag
+    pub account_type: u8,
+    /// Data version
+    pub version: u8,
+    /// Bridge reference
+    pub bridge: Pubkey,
+    /// Source blockchain identifier
+    pub source: BlockchainId,
+    /// Sender address
+    pub sender: Address,
+    /// Account that created the vault, the only one that can change the tip
+    pub funder: Pubkey,
+    /// Lamports paid to the relayer finalizing a lock of the sender
+    pub tip: u64
+}
+
+impl TipVault {
+    /// Struct size
+    pub const LEN: usize = 114;
+    /// Create new tip vault entity
+    pub fn new(bridge: Pubkey, source: BlockchainId, sender: Address, funder: Pubkey, tip: u64) -> Self {
+        Self {
+            account_type: AccountType::TipVault as u8,
+            version: PROGRAM_VERSION,
+            bridge,
+            source,
+            sender,
+            funder,
+            tip
+        }
+    }
+}
+
+impl Packed for TipVault {
+    const LEN: usize = TipVault::LEN;
+}
+
+impl Versioned for TipVault {
+    const ACCOUNT_TYPE: AccountType = AccountType::TipVault;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn account_type(&self) -> u8 {
+        self.account_type
+    }
+}
+
+/// Account of any kind, decoded by its tag
+#[derive(Clone, Debug, PartialEq)]
+pub enum Account {
+    /// Bridge account
+    Bridge(Bridge),
+    /// Blockchain account
+    Blockchain(Blockchain),
+    /// Validator account
+    Validator(Validator),
+    /// Lock account
+    Lock(Lock),
+    /// Signature account
+    Signature(Signature),
+    /// User account
+    User(User),
+    /// Sent or received lock record
+    LockTx(LockTx),
+    /// Registered token account
+    TokenInfo(TokenInfo),
+    /// Sender allowlist entry
+    SenderAllowed(SenderAllowed),
+    /// Route account
+    Route(Route),
+    /// Remote transaction index
+    TxIndex(TxIndex),
+    /// Relayer tip vault of the sender
+    TipVault(TipVault),
+}
+
+impl Account {
+    /// Deserialize account data of the kind given by its tag
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let account_type = data.first().ok_or(ProgramError::InvalidAccountData)?;
+        Ok(match AccountType::try_from(*account_type)? {
+            AccountType::Uninitialized => return Err(ProgramError::UninitializedAccount),
+            AccountType::Bridge => Account::Bridge(Bridge::unpack(data)?),
+            AccountType::Blockchain => Account::Blockchain(Blockchain::unpack(data)?),
+            AccountType::Validator => Account::Validator(Validator::unpack(data)?),
+            AccountType::Lock => Account::Lock(Lock::unpack(data)?),
+            AccountType::Signature => Account::Signature(Signature::unpack(data)?),
+            AccountType::User => Account::User(User::unpack(data)?),
+            AccountType::LockTx => Account::LockTx(LockTx::unpack(data)?),
+            AccountType::TokenInfo => Account::TokenInfo(TokenInfo::unpack(data)?),
+            AccountType::SenderAllowed => Account::SenderAllowed(SenderAllowed::unpack(data)?),
+            AccountType::Route => Account::Route(Route::unpack(data)?),
+            AccountType::TxIndex => Account::TxIndex(TxIndex::unpack(data)?),
+            AccountType::TipVault => Account::TipVault(TipVault::unpack(data)?),
+        })
     }
 }
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+use crate::error::SolBridgeProgramError;
 use crate::solana_program::{pubkey::Pubkey,
                             program_error::ProgramError,
                             account_info::AccountInfo,
@@ -5,14 +7,55 @@ use crate::solana_program::{pubkey::Pubkey,
                             entrypoint::ProgramResult,
                             program::invoke_signed,
                             system_instruction,
+                            system_program,
+                            keccak,
+                            msg,
+                            secp256k1_recover::secp256k1_recover,
+                            ed25519_program,
+                            secp256k1_program,
+                            sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
 };
+use crate::state::{Address, BlockchainId, TxId, BLOCKCHAIN_ID_LEN};
+
+/// Bridge authority address and bump seed
+pub fn get_bridge_authority(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[bridge_account.as_ref()], program_id)
+}
 
 pub fn validate_bridge_authority_and_get_bump_seed(
     program_id: &Pubkey,
     bridge_account: &Pubkey,
     authority_account: &Pubkey,
 ) -> Result<u8, ProgramError> {
-    validate_authority_and_get_bump_seed(program_id, bridge_account.as_ref(), authority_account)
+    let (expected_authority_account, bump_seed) = get_bridge_authority(program_id, bridge_account);
+    if expected_authority_account != *authority_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+/// Validate the bridge authority with the bump seed stored in the bridge, cheaper than deriving it
+pub fn validate_bridge_authority_with_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    bump_seed: u8,
+    authority_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let expected_authority_account =
+        Pubkey::create_program_address(&[bridge_account.as_ref(), &[bump_seed]], program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+    if expected_authority_account != *authority_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+/// User authority address and bump seed for a remote address
+pub fn user_authority(program_id: &Pubkey, address: &Address) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[address.as_ref()], program_id)
 }
 
 pub fn validate_user_address_authority_and_get_bump_seed(
@@ -23,109 +66,567 @@ pub fn validate_user_address_authority_and_get_bump_seed(
     validate_authority_and_get_bump_seed(program_id, user_address.as_ref(), authority_account)
 }
 
+/// Token info address for the token source and address
+pub fn get_token_info_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    token_source: &BlockchainId,
+    token_source_address: &Address,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[bridge_account.as_ref(), b"token", token_source.as_ref(), token_source_address.as_ref()],
+        program_id,
+    )
+}
+
+pub fn validate_token_info_and_get_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    token_source: &BlockchainId,
+    token_source_address: &Address,
+    token_info_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_token_info_account, bump_seed) =
+        get_token_info_address(program_id, bridge_account, token_source, token_source_address);
+    if expected_token_info_account != *token_info_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+pub fn get_sender_allowed_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    sender: &Address,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[bridge_account.as_ref(), b"sender", source.as_ref(), sender.as_ref()],
+        program_id,
+    )
+}
+
+pub fn validate_sender_allowed_and_get_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    sender: &Address,
+    sender_allowed_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_sender_allowed_account, bump_seed) =
+        get_sender_allowed_address(program_id, bridge_account, source, sender);
+    if expected_sender_allowed_account != *sender_allowed_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+pub fn get_route_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    destination: &BlockchainId,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[bridge_account.as_ref(), b"route", source.as_ref(), destination.as_ref()],
+        program_id,
+    )
+}
+
+pub fn validate_route_and_get_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    destination: &BlockchainId,
+    route_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_route_account, bump_seed) =
+        get_route_address(program_id, bridge_account, source, destination);
+    if expected_route_account != *route_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+pub fn get_escrow_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[bridge_account.as_ref(), b"escrow", mint.as_ref()],
+        program_id,
+    )
+}
+
+pub fn validate_escrow_and_get_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    mint: &Pubkey,
+    escrow_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_escrow_account, bump_seed) =
+        get_escrow_address(program_id, bridge_account, mint);
+    if expected_escrow_account != *escrow_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+pub fn get_tip_vault_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    sender: &Address,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[bridge_account.as_ref(), b"tip", source.as_ref(), sender.as_ref()],
+        program_id,
+    )
+}
+
+pub fn validate_tip_vault_and_get_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    sender: &Address,
+    tip_vault_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_tip_vault_account, bump_seed) =
+        get_tip_vault_address(program_id, bridge_account, source, sender);
+    if expected_tip_vault_account != *tip_vault_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+/// Transaction index address for the source and remote transaction id,
+/// the 64-byte id is split in two seeds as a seed is at most 32 bytes
+pub fn get_tx_index_address(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    tx_id: &TxId,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[bridge_account.as_ref(), b"tx", source.as_ref(), &tx_id[..32], &tx_id[32..]],
+        program_id,
+    )
+}
+
+pub fn validate_tx_index_and_get_bump_seed(
+    program_id: &Pubkey,
+    bridge_account: &Pubkey,
+    source: &BlockchainId,
+    tx_id: &TxId,
+    tx_index_account: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_tx_index_account, bump_seed) =
+        get_tx_index_address(program_id, bridge_account, source, tx_id);
+    if expected_tx_index_account != *tx_index_account {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+/// User authority is a bare PDA: it must never hold data or belong to another program
+pub fn check_user_authority_unused(user_authority_info: &AccountInfo) -> ProgramResult {
+    if !user_authority_info.data_is_empty() || *user_authority_info.owner != system_program::id() {
+        msg!("User authority {} is an existing account", user_authority_info.key);
+        return Err(SolBridgeProgramError::UserAuthorityInUse.into());
+    }
+    Ok(())
+}
+
+pub fn blockchain_account_seed(blockchain_id: BlockchainId) -> Result<String, ProgramError> {
+    Ok(format!("blockchain_{}", chain_id_to_str(&blockchain_id)?))
+}
+
+pub fn validator_account_seed(blockchain_id: BlockchainId, index: u64) -> Result<String, ProgramError> {
+    Ok(format!("validator_{}_{}", chain_id_to_str(&blockchain_id)?, index))
+}
+
+pub fn lock_account_seed(source: BlockchainId, lock_id: u64, revert: bool) -> Result<String, ProgramError> {
+    Ok(format!("{}_{}_{}", (if revert {"revert"} else {"lock"}), chain_id_to_str(&source)?, lock_id))
+}
+
+pub fn signature_account_seed(source: BlockchainId, lock_id: u64, validator_id: u64, revert: bool) -> Result<String, ProgramError> {
+    Ok(format!("signature_{}_{}_{}_{}", (if revert {"revert"} else {"lock"}), chain_id_to_str(&source)?, lock_id, validator_id))
+}
+
+pub fn user_account_seed(blockchain_id: BlockchainId) -> Result<String, ProgramError> {
+    Ok(format!("user_{}", chain_id_to_str(&blockchain_id)?))
+}
+
+/// Seed of the sent or received lock record, `tx_type` is "sent" or "received"
+pub fn lock_tx_account_seed(blockchain_id: BlockchainId, index: u64, tx_type: &str) -> Result<String, ProgramError> {
+    Ok(format!("{}_{}_{}", tx_type, chain_id_to_str(&blockchain_id)?, index))
+}
+
 pub fn check_and_get_blockchain_account_seed(
     program_id: &Pubkey,
-    blockchain_id: [u8; 4],
+    blockchain_id: BlockchainId,
     bridge_authority: &Pubkey,
     blockchain_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("blockchain_{}", chain_id_to_str(&blockchain_id)?);
+    let seed = blockchain_account_seed(blockchain_id)?;
     check_and_get_account_seed(program_id, seed, bridge_authority, blockchain_account)
 }
 
 pub fn check_and_get_validator_account_seed(
     program_id: &Pubkey,
-    blockchain_id: [u8; 4],
+    blockchain_id: BlockchainId,
     index: u64,
     bridge_authority: &Pubkey,
     validator_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("validator_{}_{}", chain_id_to_str(&blockchain_id)?, index);
+    let seed = validator_account_seed(blockchain_id, index)?;
     check_and_get_account_seed(program_id, seed, bridge_authority, validator_account)
 }
 
 pub fn check_and_get_lock_account_seed(
     program_id: &Pubkey,
-    source: [u8; 4],
+    source: BlockchainId,
     lock_id: u64,
     revert: bool,
     bridge_authority: &Pubkey,
     lock_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("{}_{}_{}", (if revert {"revert"} else {"lock"}), chain_id_to_str(&source)?, lock_id);
+    let seed = lock_account_seed(source, lock_id, revert)?;
     check_and_get_account_seed(program_id, seed, bridge_authority, lock_account)
 }
 
 pub fn check_and_get_signature_account_seed(
     program_id: &Pubkey,
-    source: [u8; 4],
+    source: BlockchainId,
     lock_id: u64,
     validator_id: u64,
     revert: bool,
     bridge_authority: &Pubkey,
     signature_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("signature_{}_{}_{}_{}", (if revert {"revert"} else { "lock" }), chain_id_to_str(&source)?, lock_id, validator_id);
+    let seed = signature_account_seed(source, lock_id, validator_id, revert)?;
     check_and_get_account_seed(program_id, seed, bridge_authority, signature_account)
 
 }
 
+/// Signature accounts of the lock for validators `0..validator_count`, in validator order
+pub fn signature_pdas_for_lock(
+    program_id: &Pubkey,
+    bridge: &Pubkey,
+    source: BlockchainId,
+    lock_id: u64,
+    validator_count: u64,
+    revert: bool,
+) -> Result<Vec<Pubkey>, ProgramError> {
+    let (bridge_authority, _) = get_bridge_authority(program_id, bridge);
+    (0..validator_count)
+        .map(|validator_id| {
+            let seed = signature_account_seed(source, lock_id, validator_id, revert)?;
+            Ok(Pubkey::create_with_seed(&bridge_authority, seed.as_str(), program_id)?)
+        })
+        .collect()
+}
+
 pub fn check_and_get_user_account_seed(
     program_id: &Pubkey,
-    blockchain_id: [u8; 4],
+    blockchain_id: BlockchainId,
     user_authority: &Pubkey,
     user_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("user_{}", chain_id_to_str(&blockchain_id)?);
+    let seed = user_account_seed(blockchain_id)?;
     check_and_get_account_seed(program_id, seed, user_authority, user_account)
 
 }
 
 pub fn check_and_get_sent_lock_account_seed(
     program_id: &Pubkey,
-    blockchain_id: [u8; 4],
+    blockchain_id: BlockchainId,
     user_authority: &Pubkey,
     index: u64,
     sent_lock_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("sent_{}_{}", chain_id_to_str(&blockchain_id)?, index);
+    let seed = lock_tx_account_seed(blockchain_id, index, "sent")?;
     check_and_get_account_seed(program_id, seed, user_authority, sent_lock_account)
 }
 
 pub fn check_and_get_received_lock_account_seed(
     program_id: &Pubkey,
-    blockchain_id: [u8; 4],
+    blockchain_id: BlockchainId,
     user_authority: &Pubkey,
     index: u64,
     received_lock_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("received_{}_{}", chain_id_to_str(&blockchain_id)?, index);
+    let seed = lock_tx_account_seed(blockchain_id, index, "received")?;
     check_and_get_account_seed(program_id, seed, user_authority, received_lock_account)
 }
 
 pub fn check_and_get_lock_tx_account_seed(
     program_id: &Pubkey,
-    blockchain_id: [u8; 4],
+    blockchain_id: BlockchainId,
     index: u64,
     tx_type: &str,
     user_authority: &Pubkey,
     sent_lock_account: &Pubkey,
 ) -> Result<String, ProgramError> {
-    let seed = format!("{}_{}_{}", tx_type, chain_id_to_str(&blockchain_id)?, index);
+    let seed = lock_tx_account_seed(blockchain_id, index, tx_type)?;
     check_and_get_account_seed(program_id, seed, user_authority, sent_lock_account)
 }
 
-pub fn str_to_chain_id(str: &str) -> [u8; 4] {
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Fee in basis points of the amount, rounded down. The quotient and the remainder of the amount
+/// by the denominator are scaled apart so amounts up to `u128::MAX` don't overflow
+pub fn calculate_fee(amount: u128, fee_bps: u16) -> Result<u128, ProgramError> {
+    if fee_bps as u64 > BPS_DENOMINATOR {
+        return Err(SolBridgeProgramError::InvalidFee.into());
+    }
+    let denominator = BPS_DENOMINATOR as u128;
+    let fee_bps = fee_bps as u128;
+    (amount / denominator)
+        .checked_mul(fee_bps)
+        .and_then(|fee| fee.checked_add(amount % denominator * fee_bps / denominator))
+        .ok_or_else(|| SolBridgeProgramError::Overflow.into())
+}
+
+/// Validator and protocol parts of the fee, the shares in basis points must not exceed 100% together
+pub fn split_fee(fee: u128, validator_fee_bp: u16, protocol_fee_bp: u16) -> Result<(u128, u128), ProgramError> {
+    if validator_fee_bp as u64 + protocol_fee_bp as u64 > BPS_DENOMINATOR {
+        return Err(SolBridgeProgramError::InvalidFee.into());
+    }
+    Ok((calculate_fee(fee, validator_fee_bp)?, calculate_fee(fee, protocol_fee_bp)?))
+}
+
+/// Increments the counter, failing with `Overflow` instead of wrapping
+pub fn checked_increment(value: u64) -> Result<u64, ProgramError> {
+    value.checked_add(1).ok_or_else(|| SolBridgeProgramError::Overflow.into())
+}
+
+/// Checks that the account data is owned by the program
+pub fn assert_owned_by(account_info: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    if account_info.owner != program_id {
+        msg!("Account is not owned by the program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Hash signed by validators to confirm a lock, `nonce` is the validator nonce the signature uses
+pub fn lock_message_hash(
+    token_source: &BlockchainId,
+    token_source_address: &Address,
+    source: &BlockchainId,
+    tx_id: &TxId,
+    lock_id: u64,
+    destination: &BlockchainId,
+    sender: &Address,
+    recipient: &Address,
+    amount: u128,
+    revert: bool,
+    nonce: u64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        token_source.as_ref(),
+        token_source_address.as_ref(),
+        source.as_ref(),
+        tx_id.as_ref(),
+        &lock_id.to_be_bytes(),
+        destination.as_ref(),
+        sender.as_ref(),
+        recipient.as_ref(),
+        &amount.to_be_bytes(),
+        &[revert as u8],
+        &nonce.to_be_bytes(),
+    ]).to_bytes()
+}
+
+/// Prefix `personal_sign` prepends to a 32-byte message before hashing (EIP-191)
+pub const ETH_SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+
+/// Hash validators actually sign with `personal_sign`: keccak256 of the EIP-191 prefix and the message
+pub fn eth_signed_message_hash(message: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[ETH_SIGNED_MESSAGE_PREFIX, message.as_ref()]).to_bytes()
+}
+
+/// Ethereum address, left-padded to 32 bytes, that produced the `r || s || v` signature of the hash
+pub fn recover_signer_address(message_hash: &[u8; 32], signature: &[u8; 65]) -> Result<Address, ProgramError> {
+    let recovery_id = if signature[64] >= 27 { signature[64] - 27 } else { signature[64] };
+    let public_key = secp256k1_recover(message_hash, recovery_id, &signature[..64])
+        .map_err(|_| SolBridgeProgramError::InvalidSignature)?;
+    let hash = keccak::hash(&public_key.to_bytes()).to_bytes();
+    let mut address = Address::default();
+    address[12..].copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+/// Offsets of the ed25519 precompile signatures start after the signature count and a padding byte
+const ED25519_OFFSETS_START: usize = 2;
+/// Size of one `Ed25519SignatureOffsets` entry
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// Whether ed25519 precompile instruction data checks the signature of the message by the public key,
+/// only entries with the signature, key and message inside the precompile instruction itself count
+pub fn ed25519_instruction_verifies(data: &[u8], public_key: &[u8; 32], message: &[u8], signature: &[u8]) -> bool {
+    let count = match data.first() {
+        Some(count) => *count as usize,
+        None => return false,
+    };
+    (0..count).any(|i| {
+        let start = ED25519_OFFSETS_START + i * ED25519_OFFSETS_LEN;
+        let offsets = match data.get(start..start + ED25519_OFFSETS_LEN) {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+        let offset = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+        let field = |at: usize, len: usize| data.get(offset(at)..offset(at) + len);
+        offset(2) == u16::MAX as usize
+            && offset(6) == u16::MAX as usize
+            && offset(12) == u16::MAX as usize
+            && offset(10) == message.len()
+            && field(0, 64) == Some(signature)
+            && field(4, 32) == Some(public_key.as_ref())
+            && field(8, message.len()) == Some(message)
+    })
+}
+
+/// Whether an ed25519 precompile instruction preceding the current one in the transaction
+/// checks the signature of the message by the public key
+pub fn has_ed25519_signature(
+    instructions_info: &AccountInfo,
+    public_key: &[u8; 32],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<bool, ProgramError> {
+    let current_index = load_current_index_checked(instructions_info)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_info)?;
+        if instruction.program_id == ed25519_program::id()
+            && ed25519_instruction_verifies(&instruction.data, public_key, message, signature) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Offsets of the secp256k1 precompile signatures start after the signature count
+const SECP256K1_OFFSETS_START: usize = 1;
+/// Size of one `SecpSignatureOffsets` entry
+const SECP256K1_OFFSETS_LEN: usize = 11;
+
+/// Whether secp256k1 precompile instruction data at `instruction_index` checks the `r || s || v`
+/// signature of the message by the Ethereum address, left-padded to 32 bytes; the precompile hashes
+/// the message with keccak256 itself. Only entries with all data inside the instruction count
+pub fn secp256k1_instruction_verifies(
+    data: &[u8],
+    instruction_index: u8,
+    eth_address: &Address,
+    message: &[u8],
+    signature: &[u8; 65],
+) -> bool {
+    let count = match data.first() {
+        Some(count) => *count as usize,
+        None => return false,
+    };
+    let recovery_id = if signature[64] >= 27 { signature[64] - 27 } else { signature[64] };
+    (0..count).any(|i| {
+        let start = SECP256K1_OFFSETS_START + i * SECP256K1_OFFSETS_LEN;
+        let offsets = match data.get(start..start + SECP256K1_OFFSETS_LEN) {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+        let offset = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+        let field = |at: usize, len: usize| data.get(offset(at)..offset(at) + len);
+        offsets[2] == instruction_index
+            && offsets[5] == instruction_index
+            && offsets[10] == instruction_index
+            && offset(8) == message.len()
+            && field(0, 64) == Some(&signature[..64])
+            && field(0, 65).map(|signature| signature[64]) == Some(recovery_id)
+            && field(3, 20) == Some(&eth_address[12..])
+            && field(6, message.len()) == Some(message)
+    })
+}
+
+/// Whether a secp256k1 precompile instruction preceding the current one in the transaction
+/// checks the signature of the message by the Ethereum address
+pub fn has_secp256k1_signature(
+    instructions_info: &AccountInfo,
+    eth_address: &Address,
+    message: &[u8],
+    signature: &[u8; 65],
+) -> Result<bool, ProgramError> {
+    let current_index = load_current_index_checked(instructions_info)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_info)?;
+        if instruction.program_id == secp256k1_program::id()
+            && u8::try_from(index).map_or(false, |index| {
+                secp256k1_instruction_verifies(&instruction.data, index, eth_address, message, signature)
+            }) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub fn str_to_chain_id(str: &str) -> Result<BlockchainId, ProgramError> {
     let str_len = str.len();
-    let mut result = [0; 4];
+    if str_len > BLOCKCHAIN_ID_LEN {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut result = BlockchainId::default();
     result[..str_len].copy_from_slice(str.as_bytes());
-    result
+    Ok(result)
 }
 
-pub fn chain_id_to_str(chain_id: &[u8; 4]) -> Result<&str, ProgramError> {
+pub fn chain_id_to_str(chain_id: &BlockchainId) -> Result<&str, ProgramError> {
     std::str::from_utf8(chain_id)
         .map_err(|_| ProgramError::InvalidArgument)
-        .map(|s| s.trim_matches(0 as char))
+        .map(|s| s.trim_end_matches(0 as char))
+}
+
+/// Owned counterpart of `chain_id_to_str`, for clients that keep the name beyond the id
+pub fn chain_id_to_string(chain_id: &BlockchainId) -> Result<String, ProgramError> {
+    chain_id_to_str(chain_id).map(String::from)
+}
+
+/// Parse a hex address with an optional `0x` prefix, 20-byte EVM addresses are left-padded to 32 bytes
+pub fn address_from_hex(hex: &str) -> Result<Address, ProgramError> {
+    let hex = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex);
+    if !hex.bytes().all(|c| c.is_ascii_hexdigit()) || (hex.len() != 40 && hex.len() != 64) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut address = Address::default();
+    let offset = address.len() - hex.len() / 2;
+    for (byte, digits) in address[offset..].iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let digits = std::str::from_utf8(digits).map_err(|_| ProgramError::InvalidArgument)?;
+        *byte = u8::from_str_radix(digits, 16).map_err(|_| ProgramError::InvalidArgument)?;
+    }
+    Ok(address)
+}
+
+/// Lowercase `0x`-prefixed hex of the full 32-byte address
+pub fn address_to_hex(address: &Address) -> String {
+    let mut hex = String::with_capacity(2 + address.len() * 2);
+    hex.push_str("0x");
+    for byte in address.iter() {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+/// Length of an EVM address
+pub const EVM_ADDRESS_LEN: usize = 20;
+
+/// Extract the 20-byte EVM address from a left-padded address, the 12 padding bytes must be zero
+pub fn as_evm_address(address: &Address) -> Result<[u8; EVM_ADDRESS_LEN], ProgramError> {
+    let (padding, evm_address) = address.split_at(address.len() - EVM_ADDRESS_LEN);
+    if padding.iter().any(|byte| *byte != 0) {
+        return Err(SolBridgeProgramError::InvalidAddress.into());
+    }
+    let mut result = [0; EVM_ADDRESS_LEN];
+    result.copy_from_slice(evm_address);
+    Ok(result)
 }
 
 
@@ -157,6 +658,15 @@ pub fn check_and_get_account_seed(
     Ok(seed)
 }
 
+/// Check that the payer can fund the rent-exempt balance of a new account
+pub fn check_payer_funds(payer_info: &AccountInfo, lamports: u64) -> ProgramResult {
+    if payer_info.lamports() < lamports {
+        msg!("Payer can not fund the rent-exempt balance");
+        return Err(ProgramError::InsufficientFunds);
+    }
+    Ok(())
+}
+
 pub fn create_account_with_seed<'a>(
     payer_info: &AccountInfo<'a>,
     new_account: &AccountInfo<'a>,
@@ -168,6 +678,7 @@ pub fn create_account_with_seed<'a>(
     signer_seed: &[u8],
     bump_seed: u8,
 ) -> ProgramResult {
+    check_payer_funds(payer_info, rent.minimum_balance(data_size))?;
     invoke_signed(
         &system_instruction::create_account_with_seed(
             &payer_info.key,
@@ -189,3 +700,45 @@ pub fn create_account_with_seed<'a>(
         ]],
     )
 }
+
+pub fn create_program_account<'a>(
+    payer_info: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    data_size: usize,
+    rent: &Rent,
+    program_id: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    check_payer_funds(payer_info, rent.minimum_balance(data_size))?;
+    invoke_signed(
+        &system_instruction::create_account(
+            &payer_info.key,
+            &new_account.key,
+            rent.minimum_balance(data_size),
+            data_size as u64,
+            &program_id,
+        ),
+        &[
+            payer_info.clone(),
+            new_account.clone(),
+        ],
+        &[signer_seeds],
+    )
+}
+
+/// Move all lamports of the program account to the destination and zero its data,
+/// the runtime removes the account at the end of the transaction
+pub fn close_account(
+    account_info: &AccountInfo,
+    destination_info: &AccountInfo,
+) -> ProgramResult {
+    let destination_lamports = destination_info.lamports()
+        .checked_add(account_info.lamports())
+        .ok_or(SolBridgeProgramError::Overflow)?;
+    **destination_info.lamports.borrow_mut() = destination_lamports;
+    **account_info.lamports.borrow_mut() = 0;
+    for byte in account_info.data.borrow_mut().iter_mut() {
+        *byte = 0;
+    }
+    Ok(())
+}
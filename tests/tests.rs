@@ -1,16 +1,23 @@
 #![cfg(feature = "test-bpf")]
 
-use borsh::{BorshDeserialize};
-use solana_program::{pubkey::Pubkey, system_instruction};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::Clock, instruction::{Instruction, InstructionError}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
+    message::Message,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 use solana_sdk::account::Account;
 
 use solbridge_master_contract::*;
-use solbridge_master_contract::state::{Blockchain, Bridge, Lock, Validator, Signature, User, LockTx};
+use solbridge_master_contract::error::SolBridgeProgramError;
+use solbridge_master_contract::pda;
+use solbridge_master_contract::events::{BridgeEvent, LockClosedEvent, LockCreatedEvent, SignatureAddedEvent, UserCreatedEvent};
+use solbridge_master_contract::utils::{address_from_hex, address_to_hex, as_evm_address, chain_id_to_str, chain_id_to_string, ed25519_instruction_verifies, eth_signed_message_hash, get_token_info_address, get_tx_index_address, secp256k1_instruction_verifies, ETH_SIGNED_MESSAGE_PREFIX, lock_message_hash, str_to_chain_id};
+use solbridge_master_contract::state::{AccountType, BlockchainId, ChainId, Blockchain, Bridge, BridgeV1, KeyType, Lock, LockStatus, Packed, Validator, Signature, User, LockTx, TokenInfo, SenderAllowed, Route, TxIndex, TipVault, Versioned};
+
+const MAX_VALIDATORS: u64 = 32;
 
 pub fn program_test() -> ProgramTest {
     ProgramTest::new(
@@ -29,6 +36,67 @@ pub async fn get_account(program_context: &mut ProgramTestContext, pubkey: &Pubk
         .expect("account empty")
 }
 
+pub async fn process_transaction(
+    program_context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&program_context.payer.pubkey()));
+
+    let mut all_signers = vec![&program_context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, program_context.last_blockhash);
+
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|e| e.unwrap())
+}
+
+/// Replace the instruction data, passes arguments rejected by the builders on to the program
+pub fn with_data(mut instruction: Instruction, data: instruction::BridgeProgramInstruction) -> Instruction {
+    instruction.data = data.try_to_vec().unwrap();
+    instruction
+}
+
+/// Ed25519 precompile instruction checking the signature of the message, all data inline
+pub fn ed25519_instruction(keypair: &Keypair, message: &[u8]) -> Instruction {
+    let signature = keypair.sign_message(message);
+    let public_key_offset = 2 + 14;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+    let mut data = vec![1, 0];
+    for offset in [signature_offset, u16::MAX as usize, public_key_offset, u16::MAX as usize, message_offset, message.len(), u16::MAX as usize] {
+        data.extend_from_slice(&(offset as u16).to_le_bytes());
+    }
+    data.extend_from_slice(keypair.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+    Instruction { program_id: solana_program::ed25519_program::id(), accounts: vec![], data }
+}
+
+/// Secp256k1 precompile instruction at `instruction_index` checking the validator's `personal_sign`
+/// signature of the message hash, all data inline
+pub fn secp256k1_instruction(validator: &TestValidator, message_hash: &[u8; 32], instruction_index: u8) -> Instruction {
+    let message = [ETH_SIGNED_MESSAGE_PREFIX, message_hash.as_ref()].concat();
+    let eth_address_offset = 1 + 11;
+    let signature_offset = eth_address_offset + 20;
+    let message_offset = signature_offset + 65;
+    let mut data = vec![1];
+    data.extend_from_slice(&(signature_offset as u16).to_le_bytes());
+    data.push(instruction_index);
+    data.extend_from_slice(&(eth_address_offset as u16).to_le_bytes());
+    data.push(instruction_index);
+    data.extend_from_slice(&(message_offset as u16).to_le_bytes());
+    data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+    data.push(instruction_index);
+    data.extend_from_slice(&validator.address()[12..]);
+    data.extend_from_slice(&validator.sign(message_hash));
+    data.extend_from_slice(&message);
+    Instruction { program_id: solana_program::secp256k1_program::id(), accounts: vec![], data }
+}
+
 pub async fn transfer_token(
     program_context: &mut ProgramTestContext,
     from: &Pubkey,
@@ -59,6 +127,126 @@ pub async fn transfer_token(
         .unwrap();
 }
 
+/// Test validator holding a secp256k1 key
+pub struct TestValidator {
+    secret_key: libsecp256k1::SecretKey,
+}
+
+impl TestValidator {
+    pub fn new(seed: u8) -> TestValidator {
+        TestValidator {
+            secret_key: libsecp256k1::SecretKey::parse(&[seed; 32]).unwrap()
+        }
+    }
+
+    /// Ethereum address left-padded to 32 bytes, registered as the validator public key
+    pub fn address(&self) -> [u8; 32] {
+        eth_address(&libsecp256k1::PublicKey::from_secret_key(&self.secret_key))
+    }
+
+    /// Recoverable `personal_sign` signature `r || s || v` over the lock message hash
+    pub fn sign(&self, message_hash: &[u8; 32]) -> [u8; 65] {
+        let message = libsecp256k1::Message::parse(&eth_signed_message_hash(message_hash));
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &self.secret_key);
+        let mut result = [0; 65];
+        result[..64].copy_from_slice(&signature.serialize());
+        result[64] = recovery_id.serialize();
+        result
+    }
+}
+
+pub fn eth_address(public_key: &libsecp256k1::PublicKey) -> [u8; 32] {
+    let hash = solana_program::keccak::hash(&public_key.serialize()[1..]).to_bytes();
+    let mut address = [0; 32];
+    address[12..].copy_from_slice(&hash[12..]);
+    address
+}
+
+pub fn recover_address(message_hash: &[u8; 32], signature: &[u8; 65]) -> [u8; 32] {
+    let message = libsecp256k1::Message::parse(message_hash);
+    let recovery_id = libsecp256k1::RecoveryId::parse(signature[64]).unwrap();
+    let signature = libsecp256k1::Signature::parse_standard_slice(&signature[..64]).unwrap();
+    eth_address(&libsecp256k1::recover(&message, &signature, &recovery_id).unwrap())
+}
+
+async fn create_mint(program_context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    process_transaction(
+        program_context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &payer, None, 0).unwrap(),
+        ],
+        &[&mint],
+    ).await.unwrap();
+    mint.pubkey()
+}
+
+async fn create_token_account(program_context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let account = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    process_transaction(
+        program_context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(&spl_token::id(), &account.pubkey(), mint, owner).unwrap(),
+        ],
+        &[&account],
+    ).await.unwrap();
+    account.pubkey()
+}
+
+async fn process_transaction_with_event_data(
+    program_context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Vec<Vec<u8>>, TransactionError> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&program_context.payer.pubkey()));
+
+    let mut all_signers = vec![&program_context.payer];
+    all_signers.extend_from_slice(signers);
+    transaction.sign(&all_signers, program_context.last_blockhash);
+
+    let result = program_context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result?;
+
+    Ok(result.metadata.unwrap().log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .map(|data| base64::decode(data).unwrap())
+        .collect())
+}
+
+async fn process_transaction_with_events(
+    program_context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Vec<BridgeEvent>, TransactionError> {
+    Ok(process_transaction_with_event_data(program_context, instructions, signers).await?
+        .iter()
+        .map(|data| BridgeEvent::from_log_data(data).unwrap())
+        .collect())
+}
+
 #[derive(Debug)]
 struct BridgeContext {
     bridge: Keypair,
@@ -111,6 +299,10 @@ impl BridgeContext {
     }
 
     pub async fn add_blockchain(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, contract_address: [u8; 32]) -> Pubkey {
+        self.add_blockchain_with_max_validators(program_context, blockchain_id_str, contract_address, MAX_VALIDATORS).await
+    }
+
+    pub async fn add_blockchain_with_max_validators(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, contract_address: [u8; 32], max_validators: u64) -> Pubkey {
         let blockchain_pubkey =
             Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
         let mut transaction = Transaction::new_with_payer(
@@ -122,7 +314,8 @@ impl BridgeContext {
                     &program_context.payer.pubkey(),
                     &self.bridge_authority,
                     blockchain_id_str,
-                    contract_address
+                    contract_address,
+                    max_validators
                 )
                     .unwrap(),
             ],
@@ -143,6 +336,10 @@ impl BridgeContext {
     }
 
     pub async fn add_validator(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, pubkey: [u8; 32]) -> Pubkey {
+        self.add_validator_with_key_type(program_context, blockchain_id_str, pubkey, KeyType::Secp256k1).await
+    }
+
+    pub async fn add_validator_with_key_type(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, pubkey: [u8; 32], key_type: KeyType) -> Pubkey {
         let blockchain_pubkey =
             Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
         let blockchain_account = get_account(program_context, &blockchain_pubkey).await;
@@ -158,9 +355,10 @@ impl BridgeContext {
                     &validator_account,
                     &program_context.payer.pubkey(),
                     &self.bridge_authority,
+                    &program_context.payer.pubkey(),
                     blockchain_id_str,
                     pubkey,
-
+                    key_type
                 )
                     .unwrap(),
             ],
@@ -190,35 +388,135 @@ impl BridgeContext {
                                destination: String,
                                sender: [u8; 32],
                                recipient: [u8; 32],
-                               amount: u64,
-                               validator_index: u64) -> (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) {
+                               amount: u128,
+                               validator_index: u64,
+                               revert: bool) -> Result<(Pubkey, Pubkey, Pubkey, Pubkey, Pubkey, Pubkey), TransactionError> {
+        let (instruction, accounts) = self.add_signature_instruction(
+            program_context,
+            signature,
+            token_source,
+            token_source_address,
+            source,
+            tx_id,
+            lock_id,
+            destination,
+            sender,
+            recipient,
+            amount,
+            validator_index,
+            revert,
+            None,
+            false
+        ).await;
+
+        process_transaction(program_context, &[instruction], &[]).await?;
+
+        Ok(accounts)
+    }
+
+    /// Creates the lock with the sender tokens deposited to the escrow
+    pub async fn add_signature_with_deposit(&self, program_context: &mut ProgramTestContext,
+                                            signature: [u8; 65],
+                                            token_source: String,
+                                            token_source_address: [u8; 32],
+                                            source: String,
+                                            tx_id: [u8; 64],
+                                            lock_id: u64,
+                                            destination: String,
+                                            sender: &Keypair,
+                                            recipient: [u8; 32],
+                                            amount: u128,
+                                            validator_index: u64,
+                                            mint: Pubkey,
+                                            escrow: Pubkey) -> Result<(Pubkey, Pubkey, Pubkey, Pubkey, Pubkey, Pubkey), TransactionError> {
+        let sender_token_pubkey = create_token_account(program_context, &mint, &sender.pubkey()).await;
+        let payer = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                spl_token::instruction::mint_to(&spl_token::id(), &mint, &sender_token_pubkey, &payer, &[], amount as u64).unwrap(),
+            ],
+            &[],
+        ).await.unwrap();
+
+        let (instruction, accounts) = self.add_signature_instruction(
+            program_context,
+            signature,
+            token_source,
+            token_source_address,
+            source,
+            tx_id,
+            lock_id,
+            destination,
+            sender.pubkey().to_bytes(),
+            recipient,
+            amount,
+            validator_index,
+            false,
+            Some((sender_token_pubkey, escrow, sender.pubkey())),
+            false
+        ).await;
+
+        process_transaction(program_context, &[instruction], &[sender]).await?;
+
+        Ok(accounts)
+    }
+
+    pub async fn add_signature_instruction(&self, program_context: &mut ProgramTestContext,
+                                           signature: [u8; 65],
+                                           token_source: String,
+                                           token_source_address: [u8; 32],
+                                           source: String,
+                                           tx_id: [u8; 64],
+                                           lock_id: u64,
+                                           destination: String,
+                                           sender: [u8; 32],
+                                           recipient: [u8; 32],
+                                           amount: u128,
+                                           validator_index: u64,
+                                           revert: bool,
+                                           token_accounts: Option<(Pubkey, Pubkey, Pubkey)>,
+                                           with_tip: bool) -> (Instruction, (Pubkey, Pubkey, Pubkey, Pubkey, Pubkey, Pubkey)) {
+        let lock_type = if revert { "revert" } else { "lock" };
 
         let lock_pubkey =
-            Pubkey::create_with_seed(&self.bridge_authority, format!("lock_{}_{}", source, lock_id).as_str(), &id()).unwrap();
-        let lock_account = program_context
-            .banks_client
-            .get_account(lock_pubkey)
-            .await
-            .expect("account not found");
-        let signature_index: u64 = match lock_account {
-            Some(l) => Lock::try_from_slice(&l.data).unwrap().signatures,
-            None => 0
-        };
+            Pubkey::create_with_seed(&self.bridge_authority, format!("{}_{}_{}", lock_type, source, lock_id).as_str(), &id()).unwrap();
 
         let blockchain_pubkey =
             Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", source).as_str(), &id()).unwrap();
 
+        let destination_blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", destination).as_str(), &id()).unwrap();
+
+        let (token_info_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"token", str_to_chain_id(token_source.as_str()).unwrap().as_ref(), token_source_address.as_ref()],
+            &id(),
+        );
+
+        let (sender_allowed_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"sender", str_to_chain_id(source.as_str()).unwrap().as_ref(), sender.as_ref()],
+            &id(),
+        );
+
+        let (route_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"route", str_to_chain_id(source.as_str()).unwrap().as_ref(), str_to_chain_id(destination.as_str()).unwrap().as_ref()],
+            &id(),
+        );
+
+        let (tip_vault_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"tip", str_to_chain_id(source.as_str()).unwrap().as_ref(), sender.as_ref()],
+            &id(),
+        );
+
         let signature_pubkey =
-            Pubkey::create_with_seed(&self.bridge_authority, format!("signature_lock_{}_{}_{}", source, lock_id, signature_index).as_str(), &id()).unwrap();
+            Pubkey::create_with_seed(&self.bridge_authority, format!("signature_{}_{}_{}_{}", lock_type, source, lock_id, validator_index).as_str(), &id()).unwrap();
 
         let validator_pubkey =
             Pubkey::create_with_seed(&self.bridge_authority, format!("validator_{}_{}", source, validator_index).as_str(), &id()).unwrap();
 
-        let (sender_authority, _) =
-            Pubkey::find_program_address(&[sender.as_ref()], &id());
+        let (sender_authority, _) = utils::user_authority(&id(), &sender);
 
-        let (recipient_authority, _) =
-            Pubkey::find_program_address(&[recipient.as_ref()], &id());
+        let (recipient_authority, _) = utils::user_authority(&id(), &recipient);
 
         let sender_user_pubkey =
             Pubkey::create_with_seed(&sender_authority, format!("user_{}", source).as_str(), &id()).unwrap();
@@ -249,28 +547,131 @@ impl BridgeContext {
         let sent_lock_pubkey =
             Pubkey::create_with_seed(&sender_authority, format!("sent_{}_{}", source, sent_index).as_str(), &id()).unwrap();
 
+        let nonce = self.next_validator_nonce(program_context, &validator_pubkey).await;
+
         let received_lock_pubkey =
             Pubkey::create_with_seed(&recipient_authority, format!("received_{}_{}", destination, received_index).as_str(), &id()).unwrap();
 
 
-        let mut transaction = Transaction::new_with_payer(
+        let instruction = instruction::add_signature(
+            &id(),
+            &self.bridge.pubkey(),
+            &blockchain_pubkey,
+            &destination_blockchain_pubkey,
+            &token_info_pubkey,
+            &sender_allowed_pubkey,
+            &route_pubkey,
+            &validator_pubkey,
+            &lock_pubkey,
+            &signature_pubkey,
+            &self.bridge_authority,
+            &sender_user_pubkey,
+            &sender_authority,
+            &recipient_user_pubkey,
+            &recipient_authority,
+            &sent_lock_pubkey,
+            &received_lock_pubkey,
+            &program_context.payer.pubkey(),
+            signature,
+            token_source,
+            token_source_address,
+            source,
+            tx_id,
+            lock_id,
+            destination,
+            sender,
+            recipient,
+            amount,
+            revert,
+            token_accounts.as_ref().map(|(sender_token_account, escrow_account, sender_token_owner)| (sender_token_account, escrow_account, sender_token_owner)),
+            if with_tip { Some(&tip_vault_pubkey) } else { None },
+            nonce
+        )
+            .unwrap();
+
+        (instruction, (lock_pubkey, signature_pubkey, sender_user_pubkey, recipient_user_pubkey, sent_lock_pubkey, received_lock_pubkey))
+    }
+
+    /// Nonce following the last one used by the validator, 1 for a validator that doesn't exist
+    pub async fn next_validator_nonce(&self, program_context: &mut ProgramTestContext, validator_pubkey: &Pubkey) -> u64 {
+        let validator_account = program_context
+            .banks_client
+            .get_account(*validator_pubkey)
+            .await
+            .expect("account not found");
+        match validator_account {
+            Some(account) => Validator::try_from_slice(&account.data).unwrap().nonce + 1,
+            None => 1
+        }
+    }
+
+    pub async fn add_signature_batch(&self, program_context: &mut ProgramTestContext,
+                                     signatures: Vec<([u8; 65], u64)>,
+                                     validator_indexes: &[u64],
+                                     token_source: String,
+                                     token_source_address: [u8; 32],
+                                     source: String,
+                                     tx_id: [u8; 64],
+                                     lock_id: u64,
+                                     destination: String,
+                                     sender: [u8; 32],
+                                     recipient: [u8; 32],
+                                     amount: u128,
+                                     token_accounts: Option<(Pubkey, Pubkey, &Keypair)>,
+                                     with_tip: bool) -> Result<Pubkey, TransactionError> {
+        let token_metas = token_accounts.map(|(sender_token, escrow, sender)| (sender_token, escrow, sender.pubkey()));
+        let signers: Vec<&Keypair> = token_accounts.map(|(_, _, sender)| sender).into_iter().collect();
+        let (add_signature_instruction, (lock_pubkey, _, _, _, _, _)) = self.add_signature_instruction(
+            program_context,
+            [0; 65],
+            token_source.clone(),
+            token_source_address,
+            source.clone(),
+            tx_id,
+            lock_id,
+            destination.clone(),
+            sender,
+            recipient,
+            amount,
+            0,
+            false,
+            token_metas,
+            with_tip
+        ).await;
+        let lock_accounts: Vec<Pubkey> = add_signature_instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+        let source_id = str_to_chain_id(source.as_str()).unwrap();
+        let validator_accounts: Vec<(Pubkey, Pubkey)> = validator_indexes.iter().map(|validator_index| (
+            pda::derive_validator(&id(), &self.bridge.pubkey(), source_id, *validator_index).unwrap(),
+            pda::derive_signature(&id(), &self.bridge.pubkey(), source_id, lock_id, *validator_index, false).unwrap()
+        )).collect();
+        let mut signatures_with_nonce = Vec::with_capacity(signatures.len());
+        for (signature, validator_index) in signatures {
+            let validator_pubkey = pda::derive_validator(&id(), &self.bridge.pubkey(), source_id, validator_index).unwrap();
+            signatures_with_nonce.push((signature, validator_index, self.next_validator_nonce(program_context, &validator_pubkey).await));
+        }
+        let payer = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
             &[
-                instruction::add_signature(
+                instruction::add_signature_batch(
                     &id(),
-                    &self.bridge.pubkey(),
-                    &blockchain_pubkey,
-                    &validator_pubkey,
+                    &lock_accounts[0],
+                    &lock_accounts[1],
+                    &lock_accounts[2],
+                    &lock_accounts[3],
+                    &lock_accounts[4],
+                    &lock_accounts[5],
                     &lock_pubkey,
-                    &signature_pubkey,
                     &self.bridge_authority,
-                    &sender_user_pubkey,
-                    &sender_authority,
-                    &recipient_user_pubkey,
-                    &recipient_authority,
-                    &sent_lock_pubkey,
-                    &received_lock_pubkey,
-                    &program_context.payer.pubkey(),
-                    signature,
+                    &lock_accounts[10],
+                    &lock_accounts[11],
+                    &lock_accounts[12],
+                    &lock_accounts[13],
+                    &lock_accounts[14],
+                    &lock_accounts[15],
+                    &payer,
+                    &validator_accounts,
+                    signatures_with_nonce,
                     token_source,
                     token_source_address,
                     source,
@@ -280,7 +681,299 @@ impl BridgeContext {
                     sender,
                     recipient,
                     amount,
-                    false
+                    false,
+                    token_metas.as_ref().map(|(sender_token, escrow, sender)| (sender_token, escrow, sender)),
+                    if with_tip { lock_accounts.last() } else { None }
+                ).unwrap()
+            ],
+            &signers
+        ).await?;
+
+        Ok(lock_pubkey)
+    }
+
+    pub fn add_signature_to_existing_instruction(&self, payer: &Pubkey,
+                                                 source: String,
+                                                 lock_id: u64,
+                                                 destination: String,
+                                                 sender: [u8; 32],
+                                                 recipient: [u8; 32],
+                                                 revert: bool,
+                                                 validator_index: u64,
+                                                 signature: [u8; 65],
+                                                 nonce: u64) -> (Instruction, Pubkey) {
+        let lock_pubkey = pda::derive_lock(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), lock_id, revert).unwrap();
+        let blockchain_pubkey = pda::derive_blockchain(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap()).unwrap();
+        let validator_pubkey = pda::derive_validator(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), validator_index).unwrap();
+        let signature_pubkey = pda::derive_signature(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), lock_id, validator_index, revert).unwrap();
+        let (sender_user_authority, _) = pda::user_authority(&id(), &sender);
+        let sender_user = pda::derive_user(&id(), &sender, str_to_chain_id(source.as_str()).unwrap()).unwrap();
+        let (recipient_user_authority, _) = pda::user_authority(&id(), &recipient);
+        let recipient_user = pda::derive_user(&id(), &recipient, str_to_chain_id(destination.as_str()).unwrap()).unwrap();
+        let instruction = instruction::add_signature_to_existing(
+            &id(),
+            &self.bridge.pubkey(),
+            &blockchain_pubkey,
+            &validator_pubkey,
+            &lock_pubkey,
+            &signature_pubkey,
+            &self.bridge_authority,
+            payer,
+            &sender_user,
+            &sender_user_authority,
+            &recipient_user,
+            &recipient_user_authority,
+            source,
+            lock_id,
+            revert,
+            validator_index,
+            signature,
+            nonce
+        ).unwrap();
+
+        (instruction, signature_pubkey)
+    }
+
+    pub async fn add_signature_to_existing(&self, program_context: &mut ProgramTestContext,
+                                           source: String,
+                                           lock_id: u64,
+                                           destination: String,
+                                           sender: [u8; 32],
+                                           recipient: [u8; 32],
+                                           revert: bool,
+                                           validator_index: u64,
+                                           signature: [u8; 65],
+                                           nonce: u64) -> Result<Pubkey, TransactionError> {
+        let payer = program_context.payer.pubkey();
+        let (instruction, signature_pubkey) = self.add_signature_to_existing_instruction(&payer, source, lock_id, destination, sender, recipient, revert, validator_index, signature, nonce);
+        process_transaction(program_context, &[instruction], &[]).await?;
+
+        Ok(signature_pubkey)
+    }
+
+    pub async fn add_revert_signature(&self, program_context: &mut ProgramTestContext,
+                                      source: String,
+                                      lock_id: u64,
+                                      sender: [u8; 32],
+                                      sent_index: u64,
+                                      validator_index: u64,
+                                      signature: [u8; 65],
+                                      nonce: u64) -> Result<Pubkey, TransactionError> {
+        let source_id = str_to_chain_id(source.as_str()).unwrap();
+        let bridge = self.bridge.pubkey();
+        let lock_pubkey = pda::derive_lock(&id(), &bridge, source_id, lock_id, false).unwrap();
+        let revert_lock_pubkey = pda::derive_lock(&id(), &bridge, source_id, lock_id, true).unwrap();
+        let signature_pubkey = pda::derive_signature(&id(), &bridge, source_id, lock_id, validator_index, true).unwrap();
+        let sent_lock_pubkey = pda::derive_lock_tx(&id(), &sender, source_id, sent_index, "sent").unwrap();
+        let (sender_authority, _) = pda::user_authority(&id(), &sender);
+        let payer = program_context.payer.pubkey();
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        process_transaction(
+            program_context,
+            &[
+                instruction::add_revert_signature(
+                    &id(),
+                    &bridge,
+                    &pda::derive_blockchain(&id(), &bridge, source_id).unwrap(),
+                    &pda::derive_validator(&id(), &bridge, source_id, validator_index).unwrap(),
+                    &lock_pubkey,
+                    &revert_lock_pubkey,
+                    &signature_pubkey,
+                    &sent_lock_pubkey,
+                    &sender_authority,
+                    &self.bridge_authority,
+                    &payer,
+                    source,
+                    lock_id,
+                    validator_index,
+                    sent_index,
+                    signature,
+                    nonce
+                ).unwrap()
+            ],
+            &[]
+        ).await?;
+
+        Ok(revert_lock_pubkey)
+    }
+
+    pub async fn update_contract_address(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, contract_address: [u8; 32]) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::update_contract_address(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    contract_address
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_destination_fee(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, destination_fee_bps: u16) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_destination_fee(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    destination_fee_bps
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_fee(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, fee_bp: u16) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_fee(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    fee_bp
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_threshold(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, threshold: u64) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_threshold(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    threshold
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_daily_limit(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, daily_limit: u128) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_daily_limit(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    daily_limit
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_fee_split(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, validator_fee_bp: u16, protocol_fee_bp: u16) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_fee_split(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    validator_fee_bp,
+                    protocol_fee_bp
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_min_amount(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, min_amount: u128) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_min_amount(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    min_amount
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn has_validator_signed(&self, program_context: &mut ProgramTestContext, source: String, lock_id: u64, validator_index: u64, revert: bool) -> bool {
+        let signature_pubkey = pda::derive_signature(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), lock_id, validator_index, revert).unwrap();
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        let mut transaction = Transaction::new_with_payer(
+            &[
+                instruction::has_validator_signed(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &signature_pubkey,
+                    &self.bridge_authority,
+                    source,
+                    lock_id,
+                    validator_index,
+                    revert
                 )
                     .unwrap(),
             ],
@@ -291,79 +984,5007 @@ impl BridgeContext {
             &[&program_context.payer],
             program_context.last_blockhash,
         );
-        program_context
+        let result = program_context
             .banks_client
-            .process_transaction(transaction)
+            .process_transaction_with_metadata(transaction)
             .await
             .unwrap();
+        result.result.unwrap();
 
-        (lock_pubkey, signature_pubkey, sender_user_pubkey, recipient_user_pubkey, sent_lock_pubkey, received_lock_pubkey)
+        let return_data = result.metadata.unwrap().return_data.unwrap();
+        assert_eq!(return_data.program_id, id());
+        bool::try_from_slice(&return_data.data).unwrap()
     }
-}
-
-#[tokio::test]
-async fn init_bridge_test() {
-    let mut program_context = program_test().start_with_context().await;
-    let bridge_context = BridgeContext::init(&mut program_context).await;
 
-    let bridge_account = get_account(&mut program_context, &bridge_context.bridge.pubkey()).await;
-    let bridge_data: Bridge = Bridge::try_from_slice(&bridge_account.data).unwrap();
-    println!("{:?}", bridge_data);
-    assert_eq!(bridge_data.owner, program_context.payer.pubkey());
-    assert_eq!(bridge_data.version, 1);
-}
+    pub async fn set_wrapped_mode(&self, program_context: &mut ProgramTestContext, enabled: bool) -> Result<(), TransactionError> {
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_wrapped_mode(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &owner,
+                    enabled
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_strict_mode(&self, program_context: &mut ProgramTestContext, enabled: bool) -> Result<(), TransactionError> {
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_strict_mode(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &owner,
+                    enabled
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn register_token(&self, program_context: &mut ProgramTestContext, token_source: String, token_source_address: [u8; 32], mint: Pubkey) -> Result<Pubkey, TransactionError> {
+        let (token_info_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"token", str_to_chain_id(token_source.as_str()).unwrap().as_ref(), token_source_address.as_ref()],
+            &id(),
+        );
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::register_token(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &token_info_pubkey,
+                    &owner,
+                    token_source,
+                    token_source_address,
+                    mint
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await?;
+        Ok(token_info_pubkey)
+    }
+
+    pub async fn set_validator_paused(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, index: u64, paused: bool) -> Result<(), TransactionError> {
+        let validator_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("validator_{}_{}", blockchain_id_str, index).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_validator_paused(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &validator_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    index,
+                    paused
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn initialize_escrow(&self, program_context: &mut ProgramTestContext, mint: Pubkey) -> Result<Pubkey, TransactionError> {
+        let (escrow_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"escrow", mint.as_ref()],
+            &id(),
+        );
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::initialize_escrow(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &escrow_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    mint
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await?;
+        Ok(escrow_pubkey)
+    }
+
+    pub async fn withdraw(&self, program_context: &mut ProgramTestContext, source: String, lock_id: u64, escrow: Pubkey, recipient_token_account: Pubkey) -> Result<(), TransactionError> {
+        let lock_pubkey = pda::derive_lock(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), lock_id, false).unwrap();
+        let lock_data = Lock::try_from_slice(&get_account(program_context, &lock_pubkey).await.data).unwrap();
+        let (token_info_pubkey, _) = utils::get_token_info_address(&id(), &self.bridge.pubkey(), &lock_data.token_source, &lock_data.token_source_address);
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        process_transaction(
+            program_context,
+            &[
+                instruction::withdraw(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &lock_pubkey,
+                    &token_info_pubkey,
+                    &escrow,
+                    &recipient_token_account,
+                    &self.bridge_authority,
+                    source,
+                    lock_id
+                ).unwrap()
+            ],
+            &[]
+        ).await
+    }
+
+    pub async fn set_dispute_window(&self, program_context: &mut ProgramTestContext, dispute_window: i64) -> Result<(), TransactionError> {
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_dispute_window(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &owner,
+                    dispute_window
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn revert_unlock(&self, program_context: &mut ProgramTestContext, source: String, lock_id: u64) -> Result<(), TransactionError> {
+        let lock_pubkey = pda::derive_lock(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), lock_id, false).unwrap();
+        let owner = program_context.payer.pubkey();
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        process_transaction(
+            program_context,
+            &[
+                instruction::revert_unlock(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &lock_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    source,
+                    lock_id
+                ).unwrap()
+            ],
+            &[]
+        ).await
+    }
+
+    pub async fn close_lock(&self, program_context: &mut ProgramTestContext, source: String, lock_id: u64, destination: Pubkey) -> Result<Vec<BridgeEvent>, TransactionError> {
+        let lock_pubkey = pda::derive_lock(&id(), &self.bridge.pubkey(), str_to_chain_id(source.as_str()).unwrap(), lock_id, false).unwrap();
+        let owner = program_context.payer.pubkey();
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        process_transaction_with_events(
+            program_context,
+            &[
+                instruction::close_lock(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &lock_pubkey,
+                    &destination,
+                    &self.bridge_authority,
+                    &owner,
+                    source,
+                    lock_id
+                ).unwrap()
+            ],
+            &[]
+        ).await
+    }
+
+    pub async fn close_signature(&self, program_context: &mut ProgramTestContext, source: String, lock_id: u64, validator_index: u64, destination: Pubkey) -> Result<(), TransactionError> {
+        let source_id = str_to_chain_id(source.as_str()).unwrap();
+        let lock_pubkey = pda::derive_lock(&id(), &self.bridge.pubkey(), source_id, lock_id, false).unwrap();
+        let signature_pubkey = pda::derive_signature(&id(), &self.bridge.pubkey(), source_id, lock_id, validator_index, false).unwrap();
+        let owner = program_context.payer.pubkey();
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        process_transaction(
+            program_context,
+            &[
+                instruction::close_signature(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &lock_pubkey,
+                    &signature_pubkey,
+                    &destination,
+                    &self.bridge_authority,
+                    &owner,
+                    source,
+                    lock_id,
+                    validator_index
+                ).unwrap()
+            ],
+            &[]
+        ).await
+    }
+
+    pub async fn check_escrow_solvency(&self, program_context: &mut ProgramTestContext,
+                                       token_source: String,
+                                       token_source_address: [u8; 32],
+                                       mint: Pubkey,
+                                       lock_accounts: &[Pubkey]) -> Result<(bool, u128), TransactionError> {
+        let (token_info_pubkey, _) = get_token_info_address(&id(), &self.bridge.pubkey(), &str_to_chain_id(&token_source).unwrap(), &token_source_address);
+        let (escrow_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"escrow", mint.as_ref()],
+            &id(),
+        );
+        program_context.last_blockhash = program_context
+            .banks_client
+            .get_new_latest_blockhash(&program_context.last_blockhash)
+            .await
+            .unwrap();
+        let mut transaction = Transaction::new_with_payer(
+            &[
+                instruction::check_escrow_solvency(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &token_info_pubkey,
+                    &escrow_pubkey,
+                    lock_accounts,
+                    mint
+                )
+                    .unwrap(),
+            ],
+            Some(&program_context.payer.pubkey()),
+        );
+
+        transaction.sign(
+            &[&program_context.payer],
+            program_context.last_blockhash,
+        );
+        let result = program_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result?;
+
+        let return_data = result.metadata.unwrap().return_data.unwrap();
+        assert_eq!(return_data.program_id, id());
+        Ok(<(bool, u128)>::try_from_slice(&return_data.data).unwrap())
+    }
+
+    pub async fn set_sender_allowed(&self, program_context: &mut ProgramTestContext, source: String, sender: [u8; 32], allowed: bool) -> Result<Pubkey, TransactionError> {
+        let (sender_allowed_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"sender", str_to_chain_id(source.as_str()).unwrap().as_ref(), sender.as_ref()],
+            &id(),
+        );
+        let owner = program_context.payer.pubkey();
+        let instruction = if allowed {
+            instruction::allow_sender(&id(), &self.bridge.pubkey(), &sender_allowed_pubkey, &owner, source, sender)
+        } else {
+            instruction::block_sender(&id(), &self.bridge.pubkey(), &sender_allowed_pubkey, &owner, source, sender)
+        };
+        process_transaction(program_context, &[instruction.unwrap()], &[]).await?;
+        Ok(sender_allowed_pubkey)
+    }
+
+    pub async fn set_route_fee(&self, program_context: &mut ProgramTestContext, source: String, destination: String, fee_bps: Option<u16>) -> Result<Pubkey, TransactionError> {
+        let (route_pubkey, _) = Pubkey::find_program_address(
+            &[self.bridge.pubkey().as_ref(), b"route", str_to_chain_id(source.as_str()).unwrap().as_ref(), str_to_chain_id(destination.as_str()).unwrap().as_ref()],
+            &id(),
+        );
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[instruction::set_route_fee(&id(), &self.bridge.pubkey(), &route_pubkey, &owner, source, destination, fee_bps).unwrap()],
+            &[]
+        ).await?;
+        Ok(route_pubkey)
+    }
+
+    pub async fn set_sender_allowlist_enforced(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, enforced: bool) -> Result<(), TransactionError> {
+        let blockchain_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("blockchain_{}", blockchain_id_str).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_sender_allowlist_enforced(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &blockchain_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    enforced
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn ping(&self, program_context: &mut ProgramTestContext) -> Result<(), TransactionError> {
+        process_transaction(
+            program_context,
+            &[
+                instruction::ping(
+                    &id(),
+                    &self.bridge.pubkey()
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+
+    pub async fn set_validator_active(&self, program_context: &mut ProgramTestContext, blockchain_id_str: String, index: u64, active: bool) -> Result<(), TransactionError> {
+        let validator_pubkey =
+            Pubkey::create_with_seed(&self.bridge_authority, format!("validator_{}_{}", blockchain_id_str, index).as_str(), &id()).unwrap();
+        let owner = program_context.payer.pubkey();
+        process_transaction(
+            program_context,
+            &[
+                instruction::set_validator_active(
+                    &id(),
+                    &self.bridge.pubkey(),
+                    &validator_pubkey,
+                    &self.bridge_authority,
+                    &owner,
+                    blockchain_id_str,
+                    index,
+                    active
+                )
+                    .unwrap(),
+            ],
+            &[],
+        ).await
+    }
+}
+
+#[tokio::test]
+async fn init_bridge_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+
+    let bridge_account = get_account(&mut program_context, &bridge_context.bridge.pubkey()).await;
+    let bridge_data: Bridge = Bridge::try_from_slice(&bridge_account.data).unwrap();
+    println!("{:?}", bridge_data);
+    assert_eq!(bridge_data.owner, program_context.payer.pubkey());
+    assert_eq!(bridge_data.version, PROGRAM_VERSION);
+}
+
+#[tokio::test]
+async fn init_bridge_owner_not_signer_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_key = Keypair::new();
+    let owner = Keypair::new();
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+
+    let mut init_instruction = instruction::init_bridge(
+        &id(),
+        &bridge_key.pubkey(),
+        &owner.pubkey()
+    )
+        .unwrap();
+    init_instruction.accounts[1].is_signer = false;
+
+    let payer = program_context.payer.pubkey();
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &bridge_key.pubkey(),
+                rent.minimum_balance(Bridge::LEN),
+                Bridge::LEN as u64,
+                &id(),
+            ),
+            init_instruction,
+        ],
+        &[&bridge_key],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::MissingRequiredSignature));
+}
+
+#[tokio::test]
+async fn add_blockchain_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    println!("{:?}", blockchain_data);
+    assert_eq!(blockchain_data.version, PROGRAM_VERSION);
+    assert_eq!(blockchain_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(blockchain_data.blockchain_id, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(blockchain_data.validators, 0);
+    assert_eq!(blockchain_data.contract_address, [1;32]);
+    assert_eq!(blockchain_data.threshold, 0);
+    assert_eq!(blockchain_data.max_validators, MAX_VALIDATORS);
+}
+
+#[tokio::test]
+async fn blockchain_count_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+
+    for (blockchain_id, blockchains) in [("ETH", 1), ("ARBITRUM", 2)] {
+        bridge_context.add_blockchain(&mut program_context, String::from(blockchain_id), [1; 32]).await;
+        let bridge_data = Bridge::try_from_slice(&get_account(&mut program_context, &bridge_context.bridge.pubkey()).await.data).unwrap();
+        assert_eq!(bridge_data.blockchains, blockchains);
+    }
+}
+
+#[tokio::test]
+async fn add_blockchain_account_derivation_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let bridge_pubkey = bridge_context.bridge.pubkey();
+
+    let (authority_pubkey, bump_seed) = Pubkey::find_program_address(&[bridge_pubkey.as_ref()], &id());
+    assert_eq!(authority_pubkey, bridge_context.bridge_authority);
+    assert_eq!(Pubkey::create_program_address(&[bridge_pubkey.as_ref(), &[bump_seed]], &id()).unwrap(), authority_pubkey);
+    assert!(!authority_pubkey.is_on_curve());
+
+    let blockchain_pubkey = Pubkey::create_with_seed(&authority_pubkey, "blockchain_ARBITRUM", &id()).unwrap();
+    let payer = program_context.payer.pubkey();
+
+    let wrong_base_pubkey = Pubkey::create_with_seed(&payer, "blockchain_ARBITRUM", &id()).unwrap();
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            instruction::add_blockchain(&id(), &bridge_pubkey, &wrong_base_pubkey, &payer, &authority_pubkey, String::from("ARBITRUM"), [1; 32], MAX_VALIDATORS).unwrap(),
+        ],
+        &[],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidSeeds));
+
+    let instruction = instruction::add_blockchain(&id(), &bridge_pubkey, &blockchain_pubkey, &payer, &authority_pubkey, String::from("ARBITRUM"), [1; 32], MAX_VALIDATORS).unwrap();
+    let authority_meta = instruction.accounts.iter().find(|meta| meta.pubkey == authority_pubkey).unwrap();
+    assert!(!authority_meta.is_signer);
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    assert_eq!(blockchain_account.owner, id());
+    assert_eq!(blockchain_account.data.len(), Blockchain::LEN);
+    assert_eq!(blockchain_account.lamports, rent.minimum_balance(Blockchain::LEN));
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.blockchain_id, str_to_chain_id("ARBITRUM").unwrap());
+    assert_eq!(blockchain_data.bridge, bridge_pubkey);
+}
+
+#[tokio::test]
+async fn add_signature_batch_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let error = bridge_context.add_signature_batch(
+        &mut program_context,
+        vec![([7; 65], 0), ([8; 65], 1)],
+        &[1, 0],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        None,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let lock_pubkey = bridge_context.add_signature_batch(
+        &mut program_context,
+        vec![([7; 65], 0), ([8; 65], 1)],
+        &[0, 1],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        None,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 2);
+    assert_eq!(lock_data.finalized, true);
+    assert_eq!(lock_data.amount, 10000);
+
+    for &validator_index in [0, 1].iter() {
+        let signature_pubkey = pda::derive_signature(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id("ETH").unwrap(), 1, validator_index, false).unwrap();
+        let signature_account = get_account(&mut program_context, &signature_pubkey).await;
+        let signature_data: Signature = Signature::try_from_slice(&signature_account.data).unwrap();
+        assert_eq!(signature_data.validator_index, validator_index);
+        // Submitted by the validator owners, the unverified bytes are not kept
+        assert_eq!(signature_data.signature, [0; 65]);
+    }
+
+    let blockchain_pubkey = pda::derive_blockchain(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id("ETH").unwrap()).unwrap();
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    assert_eq!(Blockchain::try_from_slice(&blockchain_account.data).unwrap().locks, 1);
+
+    // the batch deposits the sender tokens and pays the tip like single signatures do
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+    bridge_context.register_token(&mut program_context, String::from("ETH"), [3; 32], mint).await.unwrap();
+    let sender = Keypair::new();
+    let sender_token_pubkey = create_token_account(&mut program_context, &mint, &sender.pubkey()).await;
+    let payer = program_context.payer.pubkey();
+    process_transaction(
+        &mut program_context,
+        &[
+            spl_token::instruction::mint_to(&spl_token::id(), &mint, &sender_token_pubkey, &payer, &[], 10000).unwrap(),
+            instruction::fund_tip_vault(&id(), &bridge_context.bridge.pubkey(), &payer, String::from("ETH"), sender.pubkey().to_bytes(), 60_000, 100_000).unwrap(),
+        ],
+        &[],
+    ).await.unwrap();
+    let (tip_vault_pubkey, _) = Pubkey::find_program_address(
+        &[bridge_context.bridge.pubkey().as_ref(), b"tip", str_to_chain_id("ETH").unwrap().as_ref(), sender.pubkey().as_ref()],
+        &id(),
+    );
+    let vault_balance = program_context.banks_client.get_balance(tip_vault_pubkey).await.unwrap();
+
+    let lock_pubkey = bridge_context.add_signature_batch(
+        &mut program_context,
+        vec![([7; 65], 0), ([8; 65], 1)],
+        &[0, 1],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        sender.pubkey().to_bytes(),
+        [4; 32],
+        10000,
+        Some((sender_token_pubkey, escrow_pubkey, &sender)),
+        true
+    ).await.unwrap();
+
+    let lock_data: Lock = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.finalized, true);
+    assert_eq!(lock_data.deposited, true);
+    let escrow_account = get_account(&mut program_context, &escrow_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&escrow_account.data).unwrap().amount, 10000);
+    assert_eq!(program_context.banks_client.get_balance(tip_vault_pubkey).await.unwrap(), vault_balance - 60_000);
+}
+
+#[tokio::test]
+async fn close_signature_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let (_, signature_pubkey, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let destination = Pubkey::new_unique();
+    let error = bridge_context.close_signature(&mut program_context, String::from("ETH"), 1, 0, destination).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotFinalized as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false
+    ).await.unwrap();
+
+    let signature_lamports = get_account(&mut program_context, &signature_pubkey).await.lamports;
+    bridge_context.close_signature(&mut program_context, String::from("ETH"), 1, 0, destination).await.unwrap();
+
+    let signature_account = program_context.banks_client.get_account(signature_pubkey).await.unwrap();
+    assert!(signature_account.is_none());
+    assert_eq!(get_account(&mut program_context, &destination).await.lamports, signature_lamports);
+
+    let error = bridge_context.close_signature(&mut program_context, String::from("ETH"), 1, 0, destination).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::IncorrectProgramId));
+}
+
+#[tokio::test]
+async fn add_signature_with_pdas_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (expected_instruction, (lock, signature, sender_user, recipient_user, sent_lock, received_lock)) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+
+    let (instruction, pdas) = instruction::add_signature_with_pdas(
+        &id(),
+        &bridge_context.bridge.pubkey(),
+        &program_context.payer.pubkey(),
+        0,
+        0,
+        0,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        false,
+        None,
+        false,
+        1
+    ).unwrap();
+    assert_eq!(instruction, expected_instruction);
+    assert_eq!(pdas, instruction::AddSignaturePdas {
+        lock,
+        signature,
+        sender_user,
+        recipient_user,
+        sent_lock,
+        received_lock,
+        tx_index: get_tx_index_address(&id(), &bridge_context.bridge.pubkey(), &str_to_chain_id("ETH").unwrap(), &[9; 64]).0,
+    });
+
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+
+    assert_eq!(Lock::try_from_slice(&get_account(&mut program_context, &pdas.lock).await.data).unwrap().lock_id, 1);
+    assert_eq!(Signature::try_from_slice(&get_account(&mut program_context, &pdas.signature).await.data).unwrap().account_type, AccountType::Signature as u8);
+    assert_eq!(User::try_from_slice(&get_account(&mut program_context, &pdas.sender_user).await.data).unwrap().address, [2; 32]);
+    assert_eq!(User::try_from_slice(&get_account(&mut program_context, &pdas.recipient_user).await.data).unwrap().address, [4; 32]);
+    assert_eq!(LockTx::try_from_slice(&get_account(&mut program_context, &pdas.sent_lock).await.data).unwrap().lock_account, pdas.lock);
+    assert_eq!(LockTx::try_from_slice(&get_account(&mut program_context, &pdas.received_lock).await.data).unwrap().lock_account, pdas.lock);
+    assert_eq!(TxIndex::try_from_slice(&get_account(&mut program_context, &pdas.tx_index).await.data).unwrap().lock_account, pdas.lock);
+}
+
+#[tokio::test]
+async fn double_signature_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized));
+}
+
+#[tokio::test]
+async fn close_user_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let user = Keypair::new();
+    let address = user.pubkey().to_bytes();
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        address,
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let arbitrum = str_to_chain_id("ARBITRUM").unwrap();
+    let (user_authority, _) = pda::user_authority(&id(), &address);
+    let user_pubkey = pda::derive_user(&id(), &address, arbitrum).unwrap();
+    let received_lock_pubkey = pda::derive_lock_tx(&id(), &address, arbitrum, 0, "received").unwrap();
+    let destination = Pubkey::new_unique();
+    let owner = program_context.payer.pubkey();
+    let close_user = |signer: &Pubkey| instruction::close_user(
+        &id(), &bridge_context.bridge.pubkey(), &user_pubkey, &user_authority, &destination, signer, String::from("ARBITRUM"), address
+    ).unwrap();
+
+    // only the user or the bridge owner signs
+    let stranger = Keypair::new();
+    let error = process_transaction(&mut program_context, &[close_user(&stranger.pubkey())], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+    let mut instruction = close_user(&stranger.pubkey());
+    instruction.accounts[2].pubkey = pda::user_authority(&id(), &stranger.pubkey().to_bytes()).0;
+    instruction.data = instruction::BridgeProgramInstruction::CloseUser {blockchain_id: arbitrum, address: stranger.pubkey().to_bytes()}.try_to_vec().unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidSeeds));
+
+    // the lock of the user is still collecting signatures
+    let user_data = User::try_from_slice(&get_account(&mut program_context, &user_pubkey).await.data).unwrap();
+    assert_eq!(user_data.in_flight, 1);
+    let error = process_transaction(&mut program_context, &[close_user(&user.pubkey())], &[&user]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotFinalized as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        address,
+        10000,
+        1,
+        false
+    ).await.unwrap();
+    let user_account = get_account(&mut program_context, &user_pubkey).await;
+    assert_eq!(User::try_from_slice(&user_account.data).unwrap().in_flight, 0);
+
+    // the user of a remote chain is closed by the bridge owner, its records are left behind
+    process_transaction(&mut program_context, &[close_user(&owner)], &[]).await.unwrap();
+
+    assert!(program_context.banks_client.get_account(user_pubkey).await.unwrap().is_none());
+    assert_eq!(get_account(&mut program_context, &destination).await.lamports, user_account.lamports);
+    let lock_tx_data = LockTx::try_from_slice(&get_account(&mut program_context, &received_lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_tx_data.lock_id, 1);
+
+    // the user created again starts from the first record and takes it over
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [2; 32],
+        address,
+        10000,
+        0,
+        false
+    ).await.unwrap();
+    let user_data = User::try_from_slice(&get_account(&mut program_context, &user_pubkey).await.data).unwrap();
+    assert_eq!(user_data.received, 1);
+    assert_eq!(user_data.in_flight, 1);
+    let lock_tx_data = LockTx::try_from_slice(&get_account(&mut program_context, &received_lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_tx_data.lock_id, 2);
+
+    // the bridge owner doesn't close users of the Solana chain
+    process_transaction(
+        &mut program_context,
+        &[instruction::set_solana_chain_id(&id(), &bridge_context.bridge.pubkey(), &owner, Some(String::from("ARBITRUM"))).unwrap()],
+        &[]
+    ).await.unwrap();
+    program_context.last_blockhash = program_context
+        .banks_client
+        .get_new_latest_blockhash(&program_context.last_blockhash)
+        .await
+        .unwrap();
+    let error = process_transaction(&mut program_context, &[close_user(&owner)], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+}
+
+#[tokio::test]
+async fn relayer_tip_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let (tip_vault_pubkey, _) = Pubkey::find_program_address(
+        &[bridge_context.bridge.pubkey().as_ref(), b"tip", str_to_chain_id("ETH").unwrap().as_ref(), [2; 32].as_ref()],
+        &id(),
+    );
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    let vault_balance = rent.minimum_balance(TipVault::LEN) + 100_000;
+    let payer = program_context.payer.pubkey();
+    let fund_tip_vault = |funder: &Pubkey, tip: u64, deposit: u64| instruction::fund_tip_vault(
+        &id(), &bridge_context.bridge.pubkey(), funder, String::from("ETH"), [2; 32], tip, deposit
+    ).unwrap();
+    process_transaction(&mut program_context, &[fund_tip_vault(&payer, 60_000, 100_000)], &[]).await.unwrap();
+
+    let tip_vault_account = get_account(&mut program_context, &tip_vault_pubkey).await;
+    assert_eq!(tip_vault_account.lamports, vault_balance);
+    let tip_vault_data = TipVault::try_from_slice(&tip_vault_account.data).unwrap();
+    assert_eq!(tip_vault_data.funder, payer);
+    assert_eq!(tip_vault_data.tip, 60_000);
+
+    // only the funder sets the tip
+    let stranger = Keypair::new();
+    let error = process_transaction(&mut program_context, &[fund_tip_vault(&stranger.pubkey(), 100_000, 0)], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    // the tip is not paid before the lock is finalized
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        true
+    ).await;
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+    assert_eq!(program_context.banks_client.get_balance(tip_vault_pubkey).await.unwrap(), vault_balance);
+
+    let (instruction, (lock_pubkey, signature_pubkey, _, _, _, _)) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false,
+        None,
+        true
+    ).await;
+
+    process_transaction(&mut program_context, &[fund_tip_vault(&payer, 100_001, 0)], &[]).await.unwrap();
+    let error = process_transaction(&mut program_context, &[instruction.clone()], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+    process_transaction(&mut program_context, &[fund_tip_vault(&payer, 60_000, 0)], &[]).await.unwrap();
+
+    program_context.last_blockhash = program_context
+        .banks_client
+        .get_new_latest_blockhash(&program_context.last_blockhash)
+        .await
+        .unwrap();
+    let message = Message::new_with_blockhash(&[instruction.clone()], Some(&payer), &program_context.last_blockhash);
+    let fee = program_context.banks_client.get_fee_for_message(message).await.unwrap().unwrap();
+    let payer_balance = program_context.banks_client.get_balance(payer).await.unwrap();
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert!(lock_data.finalized);
+    let signature_rent = get_account(&mut program_context, &signature_pubkey).await.lamports;
+    assert_eq!(
+        program_context.banks_client.get_balance(payer).await.unwrap(),
+        payer_balance + 60_000 - fee - signature_rent
+    );
+    assert_eq!(program_context.banks_client.get_balance(tip_vault_pubkey).await.unwrap(), vault_balance - 60_000);
+}
+
+#[tokio::test]
+async fn lock_tx_account_derivation_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let sender = [2; 32];
+    let recipient = [4; 32];
+    let (lock_pubkey, _, _, _, sent_lock_pubkey, received_lock_pubkey) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        sender,
+        recipient,
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    for (address, seed, lock_tx_pubkey) in [(sender, "sent_ETH_0", sent_lock_pubkey), (recipient, "received_ARBITRUM_0", received_lock_pubkey)] {
+        let (authority_pubkey, bump_seed) = Pubkey::find_program_address(&[address.as_ref()], &id());
+        assert_eq!(Pubkey::create_program_address(&[address.as_ref(), &[bump_seed]], &id()).unwrap(), authority_pubkey);
+        assert!(!authority_pubkey.is_on_curve());
+
+        let expected_pubkey = Pubkey::create_with_seed(&authority_pubkey, seed, &id()).unwrap();
+        assert_eq!(expected_pubkey, lock_tx_pubkey);
+
+        let lock_tx_account = get_account(&mut program_context, &expected_pubkey).await;
+        assert_eq!(lock_tx_account.owner, id());
+        let lock_tx_data = LockTx::try_from_slice(&lock_tx_account.data).unwrap();
+        assert_eq!(lock_tx_data.lock_account, lock_pubkey);
+        assert_eq!(lock_tx_data.source, str_to_chain_id("ETH").unwrap());
+        assert_eq!(lock_tx_data.lock_id, 1);
+    }
+}
+
+#[tokio::test]
+async fn add_validator_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let validator_account = get_account(&mut program_context, &validator_pubkey).await;
+    let validator_data: Validator = Validator::try_from_slice(&validator_account.data).unwrap();
+    println!("{:?}", validator_data);
+    assert_eq!(validator_data.version, PROGRAM_VERSION);
+    assert_eq!(validator_data.blockchain_id, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(validator_data.index, 0);
+    assert_eq!(validator_data.pub_key, [2;32]);
+
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.validators, 1);
+}
+
+#[tokio::test]
+async fn add_validator_owner_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("SOL"), [1; 32]).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("SOL"), 2).await.unwrap();
+
+    let stranger = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &stranger.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+    let bridge = bridge_context.bridge.pubkey();
+    let arbitrum = str_to_chain_id("ARBITRUM").unwrap();
+    let instruction = instruction::add_blockchain(
+        &id(), &bridge, &pda::derive_blockchain(&id(), &bridge, str_to_chain_id("ETH").unwrap()).unwrap(), &stranger.pubkey(), &bridge_context.bridge_authority,
+        String::from("ETH"), [1; 32], MAX_VALIDATORS
+    ).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let validator_pubkey = pda::derive_validator(&id(), &bridge, arbitrum, 0).unwrap();
+    let add_validator = |owner: &Pubkey, validator_owner: &Pubkey| instruction::add_validator(
+        &id(), &bridge, &blockchain_pubkey, &validator_pubkey, owner, &bridge_context.bridge_authority, validator_owner,
+        String::from("ARBITRUM"), [7; 32], KeyType::Secp256k1
+    ).unwrap();
+    let error = process_transaction(&mut program_context, &[add_validator(&stranger.pubkey(), &stranger.pubkey())], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    // the bridge owner adds a validator owned by another account
+    let validator_owner = Pubkey::new_unique();
+    process_transaction(&mut program_context, &[add_validator(&payer, &validator_owner)], &[]).await.unwrap();
+    let validator_data = Validator::try_from_slice(&get_account(&mut program_context, &validator_pubkey).await.data).unwrap();
+    assert_eq!(validator_data.owner, validator_owner);
+    assert_eq!(validator_data.pub_key, [7; 32]);
+
+    // a lock short of the threshold can't be withdrawn
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+    bridge_context.register_token(&mut program_context, String::from("SOL"), [3; 32], mint).await.unwrap();
+    let stranger_token_pubkey = create_token_account(&mut program_context, &mint, &stranger.pubkey()).await;
+    process_transaction(
+        &mut program_context,
+        &[spl_token::instruction::mint_to(&spl_token::id(), &mint, &escrow_pubkey, &payer, &[], 10000).unwrap()],
+        &[],
+    ).await.unwrap();
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        stranger.pubkey().to_bytes(),
+        6000,
+        0,
+        false
+    ).await.unwrap();
+    let error = bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, escrow_pubkey, stranger_token_pubkey).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotFinalized as u32)));
+    let escrow_account = get_account(&mut program_context, &escrow_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&escrow_account.data).unwrap().amount, 10000);
+}
+
+#[tokio::test]
+async fn add_signature_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    let second_validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+
+    let (
+        lock_pubkey,
+        signature_pubkey,
+        sender_pubkey,
+        recipient_pubkey,
+        sent_lock_pubkey,
+        received_lock_pubkey
+    ) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    println!("{:?}", lock_data);
+    assert_eq!(lock_data.version, PROGRAM_VERSION);
+    assert_eq!(lock_data.index, 0);
+    assert_eq!(lock_data.lock_id, 1);
+    assert_eq!(lock_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(lock_data.token_source_address, [3; 32]);
+    assert_eq!(lock_data.token_source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(lock_data.source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(lock_data.recipient, [4; 32]);
+    assert_eq!(lock_data.destination, [0x41, 0x52, 0x42, 0x49, 0x54, 0x52, 0x55, 0x4d]);
+    assert_eq!(lock_data.amount, 10000);
+    assert_eq!(lock_data.signatures, 1);
+
+    let signature_account = get_account(&mut program_context, &signature_pubkey).await;
+    let signature_data: Signature = Signature::try_from_slice(&signature_account.data).unwrap();
+    println!("{:?}", signature_data);
+    assert_eq!(signature_data.version, PROGRAM_VERSION);
+    assert_eq!(signature_data.source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(signature_data.lock_id, 1);
+    assert_eq!(signature_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(signature_data.signature, [0; 65]);
+    assert_eq!(signature_data.validator, validator_pubkey);
+    assert_eq!(signature_data.validator_index, 0);
+
+    let sender_account = get_account(&mut program_context, &sender_pubkey).await;
+    let sender_data: User = User::try_from_slice(&sender_account.data).unwrap();
+    println!("{:?}", sender_data);
+    assert_eq!(sender_data.version, PROGRAM_VERSION);
+    assert_eq!(sender_data.blockchain_id, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(sender_data.address, [2; 32]);
+    assert_eq!(sender_data.sent, 1);
+    assert_eq!(sender_data.received, 0);
+
+    let recipient_account = get_account(&mut program_context, &recipient_pubkey).await;
+    let recipient_data: User = User::try_from_slice(&recipient_account.data).unwrap();
+    println!("{:?}", recipient_data);
+    assert_eq!(recipient_data.version, PROGRAM_VERSION);
+    assert_eq!(recipient_data.blockchain_id, [0x41, 0x52, 0x42, 0x49, 0x54, 0x52, 0x55, 0x4d]);
+    assert_eq!(recipient_data.address, [4; 32]);
+    assert_eq!(recipient_data.sent, 0);
+    assert_eq!(recipient_data.received, 1);
+
+    let sent_lock_account = get_account(&mut program_context, &sent_lock_pubkey).await;
+    let sent_lock_data: LockTx = LockTx::try_from_slice(&sent_lock_account.data).unwrap();
+    println!("{:?}", sent_lock_data);
+    assert_eq!(sent_lock_data.version, PROGRAM_VERSION);
+    assert_eq!(sent_lock_data.tx_id, [9; 64]);
+
+    let received_lock_account = get_account(&mut program_context, &received_lock_pubkey).await;
+    let received_lock_data: LockTx = LockTx::try_from_slice(&received_lock_account.data).unwrap();
+    println!("{:?}", received_lock_data);
+    assert_eq!(received_lock_data.version, PROGRAM_VERSION);
+    assert_eq!(received_lock_data.tx_id, [9; 64]);
+
+
+    let (instruction, (
+        lock_pubkey,
+        signature_pubkey,
+        sender_pubkey,
+        recipient_pubkey,
+        sent_lock_pubkey,
+        received_lock_pubkey
+    )) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [17; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false,
+        None,
+        false
+    ).await;
+
+    let events = process_transaction_with_events(&mut program_context, &[instruction], &[]).await.unwrap();
+    assert_eq!(events, vec![BridgeEvent::SignatureAdded(SignatureAddedEvent {
+        source: [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0],
+        lock_id: 1,
+        validator_index: 1,
+        signatures_total: 2,
+    })]);
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    println!("{:?}", lock_data);
+    assert_eq!(lock_data.version, PROGRAM_VERSION);
+    assert_eq!(lock_data.index, 0);
+    assert_eq!(lock_data.lock_id, 1);
+    assert_eq!(lock_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(lock_data.token_source_address, [3; 32]);
+    assert_eq!(lock_data.token_source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(lock_data.source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(lock_data.recipient, [4; 32]);
+    assert_eq!(lock_data.destination, [0x41, 0x52, 0x42, 0x49, 0x54, 0x52, 0x55, 0x4d]);
+    assert_eq!(lock_data.amount, 10000);
+    assert_eq!(lock_data.signatures, 2);
+
+    let signature_account = get_account(&mut program_context, &signature_pubkey).await;
+    let signature_data: Signature = Signature::try_from_slice(&signature_account.data).unwrap();
+    println!("{:?}", signature_data);
+    assert_eq!(signature_data.version, PROGRAM_VERSION);
+    assert_eq!(signature_data.source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(signature_data.lock_id, 1);
+    assert_eq!(signature_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(signature_data.signature, [0; 65]);
+    assert_eq!(signature_data.validator, second_validator_pubkey);
+    assert_eq!(signature_data.validator_index, 1);
+
+    let sender_account = get_account(&mut program_context, &sender_pubkey).await;
+    let sender_data: User = User::try_from_slice(&sender_account.data).unwrap();
+    println!("{:?}", sender_data);
+    assert_eq!(sender_data.version, PROGRAM_VERSION);
+    assert_eq!(sender_data.blockchain_id, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(sender_data.address, [2; 32]);
+    assert_eq!(sender_data.sent, 1);
+    assert_eq!(sender_data.received, 0);
+
+    let recipient_account = get_account(&mut program_context, &recipient_pubkey).await;
+    let recipient_data: User = User::try_from_slice(&recipient_account.data).unwrap();
+    println!("{:?}", recipient_data);
+    assert_eq!(recipient_data.version, PROGRAM_VERSION);
+    assert_eq!(recipient_data.blockchain_id, [0x41, 0x52, 0x42, 0x49, 0x54, 0x52, 0x55, 0x4d]);
+    assert_eq!(recipient_data.address, [4; 32]);
+    assert_eq!(recipient_data.sent, 0);
+    assert_eq!(recipient_data.received, 1);
+
+    assert_eq!(program_context
+                   .banks_client
+                   .get_account(sent_lock_pubkey)
+                   .await
+                   .expect("account not found"), None);
+
+    assert_eq!(program_context
+                   .banks_client
+                   .get_account(received_lock_pubkey)
+                   .await
+                   .expect("account not found"), None);
+
+}
+
+#[tokio::test]
+async fn add_signature_created_accounts_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+
+    let bridge = bridge_context.bridge.pubkey();
+    let eth = str_to_chain_id("ETH").unwrap();
+    let arbitrum = str_to_chain_id("ARBITRUM").unwrap();
+    let lock_accounts = [
+        pda::derive_lock(&id(), &bridge, eth, 1, false).unwrap(),
+        pda::derive_signature(&id(), &bridge, eth, 1, 0, false).unwrap(),
+        pda::derive_user(&id(), &[2; 32], eth).unwrap(),
+        pda::derive_user(&id(), &[4; 32], arbitrum).unwrap(),
+        pda::derive_lock_tx(&id(), &[2; 32], eth, 0, "sent").unwrap(),
+        pda::derive_lock_tx(&id(), &[4; 32], arbitrum, 0, "received").unwrap(),
+    ];
+    let second_signature = pda::derive_signature(&id(), &bridge, eth, 1, 1, false).unwrap();
+    let user_authorities = [
+        pda::user_authority(&id(), &[2; 32]).0,
+        pda::user_authority(&id(), &[4; 32]).0,
+    ];
+
+    for pubkey in lock_accounts.iter().chain(&[second_signature]).chain(&user_authorities) {
+        assert!(program_context.banks_client.get_account(*pubkey).await.unwrap().is_none());
+    }
+
+    let (lock_pubkey, signature_pubkey, sender_user_pubkey, recipient_user_pubkey, sent_lock_pubkey, received_lock_pubkey) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+    assert_eq!([lock_pubkey, signature_pubkey, sender_user_pubkey, recipient_user_pubkey, sent_lock_pubkey, received_lock_pubkey], lock_accounts);
+
+    // The first signature creates the lock, its signature, both users and both lock records
+    for pubkey in lock_accounts.iter() {
+        assert!(program_context.banks_client.get_account(*pubkey).await.unwrap().is_some());
+    }
+    for pubkey in [second_signature].iter().chain(&user_authorities) {
+        assert!(program_context.banks_client.get_account(*pubkey).await.unwrap().is_none());
+    }
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false
+    ).await.unwrap();
+
+    // A signature of another validator only creates its signature account
+    assert!(program_context.banks_client.get_account(second_signature).await.unwrap().is_some());
+    for pubkey in user_authorities.iter() {
+        assert!(program_context.banks_client.get_account(*pubkey).await.unwrap().is_none());
+    }
+}
+
+#[tokio::test]
+async fn lock_tx_reverted_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (_, _, _, _, sent_lock_pubkey, received_lock_pubkey) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let sent_lock_account = get_account(&mut program_context, &sent_lock_pubkey).await;
+    let sent_lock_data: LockTx = LockTx::try_from_slice(&sent_lock_account.data).unwrap();
+    assert_eq!(sent_lock_data.reverted, false);
+
+    let received_lock_account = get_account(&mut program_context, &received_lock_pubkey).await;
+    let received_lock_data: LockTx = LockTx::try_from_slice(&received_lock_account.data).unwrap();
+    assert_eq!(received_lock_data.reverted, false);
+
+    let (revert_lock_pubkey, _, _, _, sent_lock_pubkey, received_lock_pubkey) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        true
+    ).await.unwrap();
+
+    let sent_lock_account = get_account(&mut program_context, &sent_lock_pubkey).await;
+    let sent_lock_data: LockTx = LockTx::try_from_slice(&sent_lock_account.data).unwrap();
+    assert_eq!(sent_lock_data.lock_account, revert_lock_pubkey);
+    assert_eq!(sent_lock_data.reverted, true);
+
+    let received_lock_account = get_account(&mut program_context, &received_lock_pubkey).await;
+    let received_lock_data: LockTx = LockTx::try_from_slice(&received_lock_account.data).unwrap();
+    assert_eq!(received_lock_data.lock_account, revert_lock_pubkey);
+    assert_eq!(received_lock_data.reverted, true);
+}
+
+#[tokio::test]
+async fn revert_signature_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+
+    let validators = [TestValidator::new(11), TestValidator::new(12)];
+    for validator in validators.iter() {
+        bridge_context.add_validator(&mut program_context, String::from("ETH"), validator.address()).await;
+    }
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let revert_message_hash = |nonce| lock_message_hash(
+        &str_to_chain_id("ETH").unwrap(),
+        &[3; 32],
+        &str_to_chain_id("ETH").unwrap(),
+        &[9; 64],
+        1,
+        &str_to_chain_id("ARBITRUM").unwrap(),
+        &[2; 32],
+        &[4; 32],
+        10000,
+        true,
+        nonce,
+    );
+
+    let error = bridge_context.add_revert_signature(&mut program_context, String::from("ETH"), 1, [2; 32], 0, 0, validators[0].sign(&revert_message_hash(1)), 1).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::UninitializedAccount));
+
+    let (lock_pubkey, _, _, _, sent_lock_pubkey, received_lock_pubkey) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    // the first validator used nonce 1 for the lock signature
+    let error = bridge_context.add_revert_signature(&mut program_context, String::from("ETH"), 1, [2; 32], 0, 0, validators[0].sign(&revert_message_hash(1)), 1).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::StaleNonce as u32)));
+
+    let revert_lock_pubkey = bridge_context.add_revert_signature(&mut program_context, String::from("ETH"), 1, [2; 32], 0, 0, validators[0].sign(&revert_message_hash(2)), 2).await.unwrap();
+
+    let sent_lock_account = get_account(&mut program_context, &sent_lock_pubkey).await;
+    assert_eq!(LockTx::try_from_slice(&sent_lock_account.data).unwrap().reverted, false);
+
+    bridge_context.add_revert_signature(&mut program_context, String::from("ETH"), 1, [2; 32], 0, 1, validators[1].sign(&revert_message_hash(1)), 1).await.unwrap();
+    let revert_signature_pubkey = pda::derive_signature(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id("ETH").unwrap(), 1, 1, true).unwrap();
+    // the validator owner attests the revert the same way as the lock
+    let revert_signature_data = Signature::try_from_slice(&get_account(&mut program_context, &revert_signature_pubkey).await.data).unwrap();
+    assert_eq!(revert_signature_data.signature, [0; 65]);
+
+    let revert_lock_account = get_account(&mut program_context, &revert_lock_pubkey).await;
+    let revert_lock_data: Lock = Lock::try_from_slice(&revert_lock_account.data).unwrap();
+    assert_eq!(revert_lock_data.revert_signatures, 2);
+    assert_eq!(revert_lock_data.signatures, 0);
+    assert_eq!(revert_lock_data.finalized, true);
+    assert_eq!(revert_lock_data.amount, 10000);
+
+    let sent_lock_account = get_account(&mut program_context, &sent_lock_pubkey).await;
+    let sent_lock_data: LockTx = LockTx::try_from_slice(&sent_lock_account.data).unwrap();
+    assert_eq!(sent_lock_data.lock_account, lock_pubkey);
+    assert_eq!(sent_lock_data.reverted, true);
+
+    let received_lock_account = get_account(&mut program_context, &received_lock_pubkey).await;
+    assert_eq!(LockTx::try_from_slice(&received_lock_account.data).unwrap().reverted, false);
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 1);
+    assert_eq!(lock_data.revert_signatures, 0);
+    assert_eq!(lock_data.finalized, false);
+}
+
+#[tokio::test]
+async fn set_validator_active_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let validator_account = get_account(&mut program_context, &validator_pubkey).await;
+    let validator_data: Validator = Validator::try_from_slice(&validator_account.data).unwrap();
+    assert_eq!(validator_data.active, true);
+
+    bridge_context.set_validator_active(&mut program_context, String::from("ETH"), 0, false).await.unwrap();
+
+    let validator_account = get_account(&mut program_context, &validator_pubkey).await;
+    let validator_data: Validator = Validator::try_from_slice(&validator_account.data).unwrap();
+    assert_eq!(validator_data.active, false);
+    assert_eq!(validator_data.index, 0);
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::ValidatorInactive as u32)));
+
+    bridge_context.set_validator_active(&mut program_context, String::from("ETH"), 0, true).await.unwrap();
+    bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn user_authority_in_use_test() {
+    let (sender_authority, _) = Pubkey::find_program_address(&[[2u8; 32].as_ref()], &id());
+
+    let mut program_test = program_test();
+    program_test.add_account(sender_authority, Account {
+        lamports: 1_000_000,
+        data: vec![1; 8],
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::UserAuthorityInUse as u32)));
+}
+
+#[tokio::test]
+async fn user_mismatch_test() {
+    let (recipient_authority, _) = Pubkey::find_program_address(&[[4u8; 32].as_ref()], &id());
+    let recipient_user_pubkey = Pubkey::create_with_seed(&recipient_authority, "user_ARBITRUM", &id()).unwrap();
+
+    let mut program_test = program_test();
+    program_test.add_account(recipient_user_pubkey, Account {
+        lamports: 1_000_000_000,
+        data: User::new(str_to_chain_id("ARBITRUM").unwrap(), [5; 32]).try_to_vec().unwrap(),
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+}
+
+#[tokio::test]
+async fn migrate_account_test() {
+    let bridge = Keypair::new();
+    let owner = Keypair::new();
+    let previous_bridge = BridgeV1 {
+        account_type: AccountType::Bridge as u8,
+        version: PREVIOUS_VERSION,
+        owner: owner.pubkey(),
+        wrapped_mode: true,
+        strict_mode: true,
+        dispute_window: 3600,
+    };
+    let previous_data = previous_bridge.try_to_vec().unwrap();
+    assert_eq!(previous_data.len(), BridgeV1::LEN);
+
+    let mut program_test = program_test();
+    program_test.add_account(bridge.pubkey(), Account {
+        lamports: Rent::default().minimum_balance(BridgeV1::LEN),
+        data: previous_data,
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &owner.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+    // the previous layout can't be read as the current one before the migration
+    let instruction = instruction::set_strict_mode(&id(), &bridge.pubkey(), &owner.pubkey(), false).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&owner]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+
+    let stranger = Keypair::new();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &stranger.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+    let instruction = instruction::migrate_account(&id(), &bridge.pubkey(), &stranger.pubkey()).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let instruction = instruction::migrate_account(&id(), &bridge.pubkey(), &owner.pubkey()).unwrap();
+    process_transaction(&mut program_context, &[instruction], &[&owner]).await.unwrap();
+
+    let bridge_account = get_account(&mut program_context, &bridge.pubkey()).await;
+    assert_eq!(bridge_account.data.len(), Bridge::LEN);
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    assert!(bridge_account.lamports >= rent.minimum_balance(Bridge::LEN));
+    let bridge_data = Bridge::try_from_slice(&bridge_account.data).unwrap();
+    assert_eq!(bridge_data, Bridge {
+        account_type: AccountType::Bridge as u8,
+        version: PROGRAM_VERSION,
+        owner: owner.pubkey(),
+        wrapped_mode: true,
+        strict_mode: true,
+        dispute_window: 3600,
+        guardian: Pubkey::default(),
+        authority_bump: pda::get_bridge_authority(&id(), &bridge.pubkey()).1,
+        solana_chain_id: BlockchainId::default(),
+        blockchains: 0,
+    });
+
+    // the migrated bridge is usable
+    program_context.last_blockhash = program_context
+        .banks_client
+        .get_new_latest_blockhash(&program_context.last_blockhash)
+        .await
+        .unwrap();
+    let instruction = instruction::set_strict_mode(&id(), &bridge.pubkey(), &owner.pubkey(), false).unwrap();
+    process_transaction(&mut program_context, &[instruction], &[&owner]).await.unwrap();
+    let bridge_data = Bridge::try_from_slice(&get_account(&mut program_context, &bridge.pubkey()).await.data).unwrap();
+    assert!(!bridge_data.strict_mode);
+
+    let instruction = instruction::migrate_account(&id(), &bridge.pubkey(), &owner.pubkey()).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&owner]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+}
+
+#[tokio::test]
+async fn add_blockchain_batch_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let owner = program_context.payer.pubkey();
+
+    let entries = vec![
+        (String::from("ETH"), [1; 32], 4),
+        (String::from("ARBITRUM"), [6; 32], 8),
+        (String::from("SOL"), [7; 32], MAX_VALIDATORS),
+    ];
+
+    let stranger = Keypair::new();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&owner, &stranger.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+    let instruction = instruction::add_blockchain_batch(&id(), &bridge_context.bridge.pubkey(), &stranger.pubkey(), &bridge_context.bridge_authority, &entries).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let instruction = instruction::add_blockchain_batch(&id(), &bridge_context.bridge.pubkey(), &owner, &bridge_context.bridge_authority, &entries).unwrap();
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+
+    let bridge_data = Bridge::try_from_slice(&get_account(&mut program_context, &bridge_context.bridge.pubkey()).await.data).unwrap();
+    assert_eq!(bridge_data.blockchains, entries.len() as u64);
+
+    for (blockchain_id, contract_address, max_validators) in entries {
+        let blockchain_pubkey = pda::derive_blockchain(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id(&blockchain_id).unwrap()).unwrap();
+        let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &blockchain_pubkey).await.data).unwrap();
+        assert_eq!(blockchain_data.blockchain_id, str_to_chain_id(&blockchain_id).unwrap());
+        assert_eq!(blockchain_data.bridge, bridge_context.bridge.pubkey());
+        assert_eq!(blockchain_data.contract_address, contract_address);
+        assert_eq!(blockchain_data.max_validators, max_validators);
+    }
+
+    let key = Pubkey::new_unique();
+    assert_eq!(instruction::add_blockchain_batch(&id(), &key, &key, &key, &[]).unwrap_err(), ProgramError::InvalidArgument);
+    let too_many: Vec<_> = (0..=instruction::ADD_BLOCKCHAIN_BATCH_MAX_ENTRIES).map(|index| (format!("C{}", index), [1; 32], 1)).collect();
+    assert_eq!(instruction::add_blockchain_batch(&id(), &key, &key, &key, &too_many).unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[tokio::test]
+async fn update_contract_address_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+
+    bridge_context.update_contract_address(&mut program_context, String::from("ETH"), [8; 32]).await.unwrap();
+
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.contract_address, [8; 32]);
+    assert_eq!(blockchain_data.blockchain_id, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+}
+
+#[tokio::test]
+async fn destination_fee_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    let destination_blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let owner = program_context.payer.pubkey();
+    let instruction = with_data(
+        instruction::set_destination_fee(&id(), &bridge_context.bridge.pubkey(), &destination_blockchain_pubkey, &bridge_context.bridge_authority, &owner, String::from("ARBITRUM"), 0).unwrap(),
+        instruction::BridgeProgramInstruction::SetDestinationFee {blockchain_id: str_to_chain_id("ARBITRUM").unwrap(), destination_fee_bps: 10001}
+    );
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidFee as u32)));
+
+    bridge_context.set_destination_fee(&mut program_context, String::from("ARBITRUM"), 50).await.unwrap();
+
+    let blockchain_account = get_account(&mut program_context, &destination_blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.destination_fee_bps, 50);
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.amount, 10000);
+    assert_eq!(lock_data.destination_fee, 50);
+    assert_eq!(lock_data.net_amount().unwrap(), 9950);
+}
+
+#[tokio::test]
+async fn protocol_fee_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let source_blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let owner = program_context.payer.pubkey();
+    let instruction = with_data(
+        instruction::set_fee(&id(), &bridge_context.bridge.pubkey(), &source_blockchain_pubkey, &bridge_context.bridge_authority, &owner, String::from("ETH"), 0).unwrap(),
+        instruction::BridgeProgramInstruction::SetFee {blockchain_id: str_to_chain_id("ETH").unwrap(), fee_bp: 10001}
+    );
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidFee as u32)));
+
+    bridge_context.set_fee(&mut program_context, String::from("ETH"), 50).await.unwrap();
+    bridge_context.set_destination_fee(&mut program_context, String::from("ARBITRUM"), 20).await.unwrap();
+
+    let blockchain_account = get_account(&mut program_context, &source_blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.fee_bp, 50);
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.amount, 10000);
+    assert_eq!(lock_data.fee, 50);
+    assert_eq!(lock_data.destination_fee, 20);
+    assert_eq!(lock_data.net_amount().unwrap(), 9930);
+    assert_eq!(lock_data.validator_fee, 0);
+    assert_eq!(lock_data.protocol_fee, 50);
+}
+
+#[tokio::test]
+async fn fee_split_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let source_blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let key = Pubkey::new_unique();
+    assert_eq!(
+        instruction::set_fee_split(&id(), &key, &key, &key, &key, String::from("ETH"), 6000, 4001).unwrap_err(),
+        ProgramError::Custom(SolBridgeProgramError::InvalidFee as u32)
+    );
+    let owner = program_context.payer.pubkey();
+    let instruction = with_data(
+        instruction::set_fee_split(&id(), &bridge_context.bridge.pubkey(), &source_blockchain_pubkey, &bridge_context.bridge_authority, &owner, String::from("ETH"), 0, 0).unwrap(),
+        instruction::BridgeProgramInstruction::SetFeeSplit {blockchain_id: str_to_chain_id("ETH").unwrap(), validator_fee_bp: 6000, protocol_fee_bp: 4001}
+    );
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidFee as u32)));
+
+    bridge_context.set_fee(&mut program_context, String::from("ETH"), 100).await.unwrap();
+    bridge_context.set_fee_split(&mut program_context, String::from("ETH"), 3000, 7000).await.unwrap();
+
+    let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &source_blockchain_pubkey).await.data).unwrap();
+    assert_eq!(blockchain_data.validator_fee_bp, 3000);
+    assert_eq!(blockchain_data.protocol_fee_bp, 7000);
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.fee, 100);
+    assert_eq!(lock_data.validator_fee, 30);
+    assert_eq!(lock_data.protocol_fee, 70);
+}
+
+#[test]
+fn split_fee_test() {
+    assert_eq!(utils::split_fee(100, 3000, 7000).unwrap(), (30, 70));
+    assert_eq!(utils::split_fee(u128::MAX / 10000, 10000, 0).unwrap(), (u128::MAX / 10000, 0));
+    assert_eq!(utils::split_fee(u128::MAX, 2, 0).unwrap(), (u128::MAX / 5000, 0));
+    assert_eq!(utils::split_fee(100, 5000, 5001).unwrap_err(), ProgramError::Custom(SolBridgeProgramError::InvalidFee as u32));
+    assert_eq!(utils::split_fee(100, u16::MAX, u16::MAX).unwrap_err(), ProgramError::Custom(SolBridgeProgramError::InvalidFee as u32));
+}
+
+#[test]
+fn checked_increment_test() {
+    assert_eq!(utils::checked_increment(0).unwrap(), 1);
+    assert_eq!(utils::checked_increment(u64::MAX - 1).unwrap(), u64::MAX);
+    assert_eq!(utils::checked_increment(u64::MAX).unwrap_err(), ProgramError::Custom(SolBridgeProgramError::Overflow as u32));
+}
+
+#[test]
+fn calculate_fee_test() {
+    assert_eq!(utils::calculate_fee(10000, 50).unwrap(), 50);
+    assert_eq!(utils::calculate_fee(u64::MAX as u128, 10000).unwrap(), u64::MAX as u128);
+    assert_eq!(utils::calculate_fee(u128::MAX / 10000, 10000).unwrap(), u128::MAX / 10000);
+    assert_eq!(utils::calculate_fee(u128::MAX, 10000).unwrap(), u128::MAX);
+    assert_eq!(utils::calculate_fee(u128::MAX, 2).unwrap(), u128::MAX / 5000);
+    assert_eq!(utils::calculate_fee(u128::MAX, 9999).unwrap(), u128::MAX / 10000 * 9999 + u128::MAX % 10000 * 9999 / 10000);
+    assert_eq!(utils::calculate_fee(9999, 1).unwrap(), 0);
+    assert_eq!(utils::calculate_fee(10000, 10001).unwrap_err(), ProgramError::Custom(SolBridgeProgramError::InvalidFee as u32));
+}
+
+#[test]
+fn net_amount_test() {
+    let eth = str_to_chain_id("ETH").unwrap();
+    let arbitrum = str_to_chain_id("ARBITRUM").unwrap();
+    let lock = Lock::new(0, 1, [9; 64], Pubkey::new_unique(), [3; 32], eth, eth, [2; 32], [4; 32], arbitrum, 10000, 20, 50, 0);
+    assert_eq!(lock.net_amount().unwrap(), 9930);
+    let lock = Lock::new(0, 1, [9; 64], Pubkey::new_unique(), [3; 32], eth, eth, [2; 32], [4; 32], arbitrum, 10000, 10000, 0, 0);
+    assert_eq!(lock.net_amount().unwrap(), 0);
+    for (destination_fee, fee) in [(10001, 0), (0, 10001), (9999, 2)] {
+        let lock = Lock::new(0, 1, [9; 64], Pubkey::new_unique(), [3; 32], eth, eth, [2; 32], [4; 32], arbitrum, 10000, destination_fee, fee, 0);
+        assert_eq!(lock.net_amount().unwrap_err(), ProgramError::Custom(SolBridgeProgramError::InvalidFee as u32));
+    }
+}
+
+#[tokio::test]
+async fn set_threshold_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.threshold, 2);
+
+    let error = bridge_context.set_threshold(&mut program_context, String::from("ETH"), 3).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidThreshold as u32)));
+
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 1).await.unwrap();
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.threshold, 1);
+}
+
+#[tokio::test]
+async fn daily_limit_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.set_daily_limit(&mut program_context, String::from("ETH"), 15000).await.unwrap();
+
+    let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &blockchain_pubkey).await.data).unwrap();
+    assert_eq!(blockchain_data.daily_limit, 15000);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &blockchain_pubkey).await.data).unwrap();
+    assert_eq!(blockchain_data.volume_window_start, clock.unix_timestamp);
+    assert_eq!(blockchain_data.volume_in_window, 10000);
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::DailyLimitExceeded as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        5000,
+        0,
+        false
+    ).await.unwrap();
+
+    let mut clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += 86_400;
+    program_context.set_sysvar(&clock);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [11; 64],
+        3,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &blockchain_pubkey).await.data).unwrap();
+    assert_eq!(blockchain_data.volume_window_start, clock.unix_timestamp);
+    assert_eq!(blockchain_data.volume_in_window, 10000);
+}
+
+#[tokio::test]
+async fn min_amount_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.set_min_amount(&mut program_context, String::from("ETH"), 10000).await.unwrap();
+
+    let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &blockchain_pubkey).await.data).unwrap();
+    assert_eq!(blockchain_data.min_amount, 10000);
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        9999,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::AmountBelowMinimum as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn has_validator_signed_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 1).await.unwrap();
+
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 1, false).await, false);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false
+    ).await.unwrap();
+
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 1, false).await, true);
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 0, false).await, false);
+
+    // revert signatures are queried with their own seed
+    bridge_context.add_revert_signature(&mut program_context, String::from("ETH"), 1, [2; 32], 0, 0, [8; 65], 1).await.unwrap();
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 0, true).await, true);
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 0, false).await, false);
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 1, true).await, false);
+
+    // the answer is read from the signature account, a closed signature isn't seen anymore
+    bridge_context.close_signature(&mut program_context, String::from("ETH"), 1, 1, Pubkey::new_unique()).await.unwrap();
+    assert_eq!(bridge_context.has_validator_signed(&mut program_context, String::from("ETH"), 1, 1, false).await, false);
+}
+
+#[tokio::test]
+async fn lock_finalized_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 1);
+    assert_eq!(lock_data.finalized, false);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [17; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 2);
+    assert_eq!(lock_data.finalized, true);
+}
+
+#[tokio::test]
+async fn token_not_registered_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.set_wrapped_mode(&mut program_context, true).await.unwrap();
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::TokenNotRegistered as u32)));
+
+    let mint = Pubkey::new_unique();
+    let token_info_pubkey = bridge_context.register_token(&mut program_context, String::from("ETH"), [3; 32], mint).await.unwrap();
+
+    let token_info_account = get_account(&mut program_context, &token_info_pubkey).await;
+    let token_info_data: TokenInfo = TokenInfo::try_from_slice(&token_info_account.data).unwrap();
+    assert_eq!(token_info_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(token_info_data.token_source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(token_info_data.token_source_address, [3; 32]);
+    assert_eq!(token_info_data.mint, mint);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn strict_mode_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    bridge_context.set_strict_mode(&mut program_context, true).await.unwrap();
+    let bridge_account = get_account(&mut program_context, &bridge_context.bridge.pubkey()).await;
+    assert_eq!(Bridge::try_from_slice(&bridge_account.data).unwrap().strict_mode, true);
+
+    // Each missing piece of configuration is reported in turn until all are in place
+    for (step, expected_error) in [
+        Some(SolBridgeProgramError::RouteNotConfigured),
+        Some(SolBridgeProgramError::TokenNotRegistered),
+        Some(SolBridgeProgramError::SenderNotAllowed),
+        None
+    ].into_iter().enumerate() {
+        let result = bridge_context.add_signature(
+            &mut program_context,
+            [10 + step as u8; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            [10; 64],
+            2,
+            String::from("ARBITRUM"),
+            [2; 32],
+            [4; 32],
+            10000,
+            0,
+            false
+        ).await;
+        match expected_error {
+            Some(ref error) => assert_eq!(result.unwrap_err(), TransactionError::InstructionError(0, InstructionError::Custom(error.clone() as u32))),
+            None => { result.unwrap(); }
+        }
+        match expected_error {
+            Some(SolBridgeProgramError::RouteNotConfigured) => {
+                bridge_context.set_route_fee(&mut program_context, String::from("ETH"), String::from("ARBITRUM"), None).await.unwrap();
+            }
+            Some(SolBridgeProgramError::TokenNotRegistered) => {
+                bridge_context.register_token(&mut program_context, String::from("ETH"), [3; 32], Pubkey::new_unique()).await.unwrap();
+            }
+            Some(SolBridgeProgramError::SenderNotAllowed) => {
+                bridge_context.set_sender_allowed(&mut program_context, String::from("ETH"), [2; 32], true).await.unwrap();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test]
+async fn lock_already_finalized_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [6; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let mut lock_pubkey = Pubkey::default();
+    for validator_index in 0..2 {
+        let (pubkey, _, _, _, _, _) = bridge_context.add_signature(
+            &mut program_context,
+            [7; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            [9; 64],
+            1,
+            String::from("ARBITRUM"),
+            [2; 32],
+            [4; 32],
+            10000,
+            validator_index,
+            false
+        ).await.unwrap();
+        lock_pubkey = pubkey;
+    }
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        2,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockAlreadyFinalized as u32)));
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 2);
+    assert_eq!(lock_data.finalized, true);
+}
+
+#[tokio::test]
+async fn validator_quorum_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+
+    let validators = [TestValidator::new(11), TestValidator::new(12)];
+    for validator in validators.iter() {
+        bridge_context.add_validator(&mut program_context, String::from("ETH"), validator.address()).await;
+    }
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let relayer = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &relayer.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+    let lock_hash = |amount| lock_message_hash(
+        &str_to_chain_id("ETH").unwrap(),
+        &[3; 32],
+        &str_to_chain_id("ETH").unwrap(),
+        &[9; 64],
+        1,
+        &str_to_chain_id("ARBITRUM").unwrap(),
+        &[2; 32],
+        &[4; 32],
+        amount,
+        false,
+        1,
+    );
+    let message_hash = lock_hash(10000);
+
+    // the first signature is relayed and checked by the secp256k1 precompile
+    let (mut add_signature_instruction, (lock_pubkey, first_signature_pubkey, _, _, _, _)) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        validators[0].sign(&message_hash),
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    add_signature_instruction.accounts[16].pubkey = relayer.pubkey();
+    let error = process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&TestValidator::new(13), &message_hash, 0), add_signature_instruction.clone()],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+    process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[0], &message_hash, 0), add_signature_instruction],
+        &[&relayer]
+    ).await.unwrap();
+
+    // the second one is relayed to the existing lock under the same rule, foreign, tampered and
+    // unchecked signatures are refused
+    let relayed_to_existing = |signature: [u8; 65]| bridge_context.add_signature_to_existing_instruction(
+        &relayer.pubkey(), String::from("ETH"), 1, String::from("ARBITRUM"), [2; 32], [4; 32], false, 1, signature, 1
+    );
+    let invalid_signatures = [
+        (TestValidator::new(13).sign(&message_hash), Some(secp256k1_instruction(&TestValidator::new(13), &message_hash, 0))),
+        (validators[1].sign(&lock_hash(10001)), Some(secp256k1_instruction(&validators[1], &lock_hash(10001), 0))),
+        (validators[1].sign(&message_hash), None),
+    ];
+    for (invalid_signature, precompile_instruction) in invalid_signatures.iter() {
+        let (instruction, _) = relayed_to_existing(*invalid_signature);
+        let instructions: Vec<Instruction> = precompile_instruction.iter().cloned().chain(std::iter::once(instruction)).collect();
+        let error = process_transaction(&mut program_context, &instructions, &[&relayer]).await.unwrap_err();
+        assert_eq!(error, TransactionError::InstructionError(instructions.len() as u8 - 1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+    }
+    let lock_data: Lock = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.finalized, false);
+
+    let (instruction, second_signature_pubkey) = relayed_to_existing(validators[1].sign(&message_hash));
+    process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[1], &message_hash, 0), instruction],
+        &[&relayer]
+    ).await.unwrap();
+
+    for (signature_pubkey, validator) in [first_signature_pubkey, second_signature_pubkey].iter().zip(validators.iter()) {
+        let signature_data: Signature = Signature::try_from_slice(&get_account(&mut program_context, signature_pubkey).await.data).unwrap();
+        assert_eq!(recover_address(&eth_signed_message_hash(&message_hash), &signature_data.signature), validator.address());
+    }
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 2);
+    assert_eq!(lock_data.finalized, true);
+}
+
+#[tokio::test]
+async fn lock_created_at_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    program_context.warp_to_slot(100).unwrap();
+    let clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert!(lock_data.created_at > 0);
+    assert!(lock_data.created_at >= clock.unix_timestamp);
+}
+
+#[test]
+fn instruction_chain_id_too_long_test() {
+    let key = Pubkey::new_unique();
+
+    assert_eq!(
+        instruction::add_blockchain(&id(), &key, &key, &key, &key, String::from("ETHEREUM2"), [1; 32], MAX_VALIDATORS).unwrap_err(),
+        ProgramError::InvalidArgument
+    );
+
+    assert_eq!(
+        instruction::add_validator(&id(), &key, &key, &key, &key, &key, &key, String::from("ETHEREUM2"), [2; 32], KeyType::Secp256k1).unwrap_err(),
+        ProgramError::InvalidArgument
+    );
+
+    for (token_source, source, destination) in [("ETHEREUM2", "ETH", "ARBITRUM"), ("ETH", "ETHEREUM2", "ARBITRUM"), ("ETH", "ETH", "ARBITRUM1")] {
+        assert_eq!(
+            instruction::add_signature(
+                &id(), &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key,
+                [7; 65],
+                String::from(token_source),
+                [3; 32],
+                String::from(source),
+                [9; 64],
+                1,
+                String::from(destination),
+                [2; 32],
+                [4; 32],
+                10000,
+                false,
+                None,
+                None,
+                1
+            ).unwrap_err(),
+            ProgramError::InvalidArgument
+        );
+    }
+}
+
+#[test]
+fn instruction_validation_test() {
+    let key = Pubkey::new_unique();
+    let invalid_address = ProgramError::from(SolBridgeProgramError::InvalidAddress);
+    let invalid_fee = ProgramError::from(SolBridgeProgramError::InvalidFee);
+
+    assert_eq!(instruction::add_blockchain(&id(), &key, &key, &key, &key, String::new(), [1; 32], MAX_VALIDATORS).unwrap_err(), ProgramError::InvalidArgument);
+    assert_eq!(instruction::add_blockchain(&id(), &key, &key, &key, &key, String::from("ETH"), [0; 32], MAX_VALIDATORS).unwrap_err(), invalid_address);
+    assert_eq!(instruction::add_validator(&id(), &key, &key, &key, &key, &key, &key, String::from("ETH"), [0; 32], KeyType::Secp256k1).unwrap_err(), invalid_address);
+    assert_eq!(instruction::update_contract_address(&id(), &key, &key, &key, &key, String::from("ETH"), [0; 32]).unwrap_err(), invalid_address);
+    assert_eq!(instruction::register_token(&id(), &key, &key, &key, String::from("ETH"), [0; 32], key).unwrap_err(), invalid_address);
+    assert_eq!(instruction::allow_sender(&id(), &key, &key, &key, String::from("ETH"), [0; 32]).unwrap_err(), invalid_address);
+    assert_eq!(instruction::fund_tip_vault(&id(), &key, &key, String::from("ETH"), [0; 32], 1, 1).unwrap_err(), invalid_address);
+    assert_eq!(instruction::block_sender(&id(), &key, &key, &key, String::from("ETH"), [0; 32]).unwrap_err(), invalid_address);
+
+    assert_eq!(instruction::set_destination_fee(&id(), &key, &key, &key, &key, String::from("ETH"), 10001).unwrap_err(), invalid_fee);
+    assert_eq!(instruction::set_fee(&id(), &key, &key, &key, &key, String::from("ETH"), 10001).unwrap_err(), invalid_fee);
+    assert_eq!(instruction::set_route_fee(&id(), &key, &key, &key, String::from("ETH"), String::from("ARBITRUM"), Some(10001)).unwrap_err(), invalid_fee);
+    assert!(instruction::set_route_fee(&id(), &key, &key, &key, String::from("ETH"), String::from("ARBITRUM"), None).is_ok());
+
+    assert_eq!(instruction::set_dispute_window(&id(), &key, &key, -1).unwrap_err(), ProgramError::InvalidArgument);
+    assert_eq!(instruction::withdraw(&id(), &key, &key, &key, &key, &key, &key, String::new(), 1).unwrap_err(), ProgramError::InvalidArgument);
+
+    let add_signature = |destination: &str, sender: [u8; 32], recipient: [u8; 32], amount: u128, tip_vault: Option<&Pubkey>| instruction::add_signature(
+        &id(), &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from(destination),
+        sender,
+        recipient,
+        amount,
+        false,
+        None,
+        tip_vault,
+        1
+    );
+    assert!(add_signature("ARBITRUM", [2; 32], [4; 32], 10000, Some(&key)).is_ok());
+    assert_eq!(add_signature("ARBITRUM", [2; 32], [4; 32], 0, None).unwrap_err(), SolBridgeProgramError::InvalidAmount.into());
+    assert_eq!(add_signature("ARBITRUM", [0; 32], [0; 32], 10000, None).unwrap_err(), invalid_address);
+    assert_eq!(add_signature("ETH", [2; 32], [4; 32], 10000, None).unwrap_err(), SolBridgeProgramError::SameSourceAndDestination.into());
+
+    let add_signature_batch = |validator_accounts: &[(Pubkey, Pubkey)], signatures: Vec<([u8; 65], u64, u64)>, amount: u128| instruction::add_signature_batch(
+        &id(), &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key, &key,
+        validator_accounts,
+        signatures,
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        amount,
+        false,
+        None,
+        None
+    );
+    assert!(add_signature_batch(&[(key, key)], vec![([7; 65], 0, 1)], 10000).is_ok());
+    assert_eq!(add_signature_batch(&[], vec![], 10000).unwrap_err(), ProgramError::InvalidArgument);
+    assert_eq!(add_signature_batch(&[(key, key)], vec![([7; 65], 0, 1), ([8; 65], 1, 1)], 10000).unwrap_err(), ProgramError::InvalidArgument);
+    assert_eq!(add_signature_batch(&[(key, key)], vec![([7; 65], 0, 1)], 0).unwrap_err(), SolBridgeProgramError::InvalidAmount.into());
+}
+
+#[test]
+fn add_signature_accounts_test() {
+    let keys: Vec<Pubkey> = (0..20).map(|_| Pubkey::new_unique()).collect();
+    let accounts = instruction::AddSignatureAccounts {
+        bridge: keys[0],
+        blockchain: keys[1],
+        destination_blockchain: keys[2],
+        token_info: keys[3],
+        sender_allowed: keys[4],
+        route: keys[5],
+        validator: keys[6],
+        lock: keys[7],
+        signature: keys[8],
+        bridge_authority: keys[9],
+        sender_user: keys[10],
+        sender_user_authority: keys[11],
+        recipient_user: keys[12],
+        recipient_user_authority: keys[13],
+        sent_lock: keys[14],
+        received_lock: keys[15],
+        payer: keys[16],
+        tx_index: get_tx_index_address(&id(), &keys[0], &str_to_chain_id("ETH").unwrap(), &[3; 64]).0,
+        token_accounts: Some((keys[17], keys[18], keys[19])),
+        tip_vault: None,
+    };
+
+    let positional = instruction::add_signature(
+        &id(), &keys[0], &keys[1], &keys[2], &keys[3], &keys[4], &keys[5], &keys[6], &keys[7], &keys[8],
+        &keys[9], &keys[10], &keys[11], &keys[12], &keys[13], &keys[14], &keys[15], &keys[16],
+        [1; 65], "SOL".to_string(), [2; 32], "ETH".to_string(), [3; 64], 4, "SOL".to_string(), [5; 32], [6; 32], 7, false,
+        Some((&keys[17], &keys[18], &keys[19])), None, 8,
+    ).unwrap();
+    let named = instruction::add_signature_with_accounts(
+        &id(), &accounts,
+        [1; 65], "SOL".to_string(), [2; 32], "ETH".to_string(), [3; 64], 4, "SOL".to_string(), [5; 32], [6; 32], 7, false, 8,
+    ).unwrap();
+    assert_eq!(positional, named);
+    assert_eq!(named.accounts.len(), 26);
+    assert!(named.accounts[16].is_signer);
+    assert_eq!(named.accounts[25].pubkey, keys[19]);
+    assert!(named.accounts[25].is_signer);
+
+    let tip_vault = Pubkey::new_unique();
+    let with_tip = instruction::add_signature_with_accounts(
+        &id(), &instruction::AddSignatureAccounts { tip_vault: Some(tip_vault), ..accounts.clone() },
+        [1; 65], "SOL".to_string(), [2; 32], "ETH".to_string(), [3; 64], 4, "SOL".to_string(), [5; 32], [6; 32], 7, false, 8,
+    ).unwrap();
+    assert_eq!(with_tip.accounts.last().unwrap().pubkey, tip_vault);
+}
+
+#[test]
+fn str_to_chain_id_too_long_test() {
+    assert_eq!(str_to_chain_id("ETH").unwrap(), *b"ETH\0\0\0\0\0");
+    assert_eq!(str_to_chain_id("ARBITRUM").unwrap(), *b"ARBITRUM");
+    assert_eq!(str_to_chain_id("ETHEREUM2").unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[tokio::test]
+async fn signature_timestamp_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+
+    let (lock_pubkey, first_signature_pubkey, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    let first_signature_account = get_account(&mut program_context, &first_signature_pubkey).await;
+    let first_signature_data: Signature = Signature::try_from_slice(&first_signature_account.data).unwrap();
+    assert_eq!(first_signature_data.signed_at, lock_data.created_at);
+
+    let mut clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += 60;
+    program_context.set_sysvar(&clock);
+
+    let (_, second_signature_pubkey, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [17; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false
+    ).await.unwrap();
+
+    let second_signature_account = get_account(&mut program_context, &second_signature_pubkey).await;
+    let second_signature_data: Signature = Signature::try_from_slice(&second_signature_account.data).unwrap();
+    assert!(second_signature_data.signed_at > lock_data.created_at);
+}
+
+#[test]
+fn chain_id_round_trip_test() {
+    for chain in ["ETH", "SOLA", "ARBITRUM", "OPTIMISM", "\0AB"] {
+        let chain_id = str_to_chain_id(chain).unwrap();
+        assert_eq!(chain_id_to_str(&chain_id).unwrap(), chain);
+        assert_eq!(chain_id_to_string(&chain_id).unwrap(), chain_id_to_str(&chain_id).unwrap());
+    }
+    assert_eq!(chain_id_to_string(&[0xff; 8]).unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[test]
+fn eth_signed_message_hash_test() {
+    // keccak256("hello world") signed with `personal_sign`
+    let message = address_from_hex("0x47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad").unwrap();
+    let expected = address_from_hex("0x93100cc9477ba6522a2d7d5e83d0e075b167224ed8aa0c5860cfd47fa9f22797").unwrap();
+    assert_eq!(eth_signed_message_hash(&message), expected);
+}
+
+#[test]
+fn address_hex_test() {
+    let mut expected = [0; 32];
+    expected[12..].copy_from_slice(&[
+        0x52, 0x90, 0x84, 0x00, 0x09, 0x85, 0x27, 0x88, 0x6e, 0x0f,
+        0x70, 0x30, 0x06, 0x98, 0x57, 0xd2, 0xe4, 0x16, 0x9e, 0xe7,
+    ]);
+    let padded = "0x00000000000000000000000052908400098527886e0f7030069857d2e4169ee7";
+
+    assert_eq!(address_from_hex("0x52908400098527886E0F7030069857D2E4169EE7").unwrap(), expected);
+    assert_eq!(address_from_hex("52908400098527886e0f7030069857d2e4169ee7").unwrap(), expected);
+    assert_eq!(address_from_hex(padded).unwrap(), expected);
+    assert_eq!(address_to_hex(&expected), padded);
+
+    let solana_address = Pubkey::new_unique().to_bytes();
+    assert_eq!(address_from_hex(&address_to_hex(&solana_address)).unwrap(), solana_address);
+
+    for invalid in ["", "0x", "0x52908400098527886e0f7030069857d2e4169e", "0x52908400098527886e0f7030069857d2e4169eg7", "0x+2908400098527886e0f7030069857d2e4169ee7"] {
+        assert_eq!(address_from_hex(invalid).unwrap_err(), ProgramError::InvalidArgument);
+    }
+}
+
+#[test]
+fn user_authority_test() {
+    let address = [4; 32];
+    let (authority, bump_seed) = utils::user_authority(&id(), &address);
+    assert_eq!((authority, bump_seed), Pubkey::find_program_address(&[address.as_ref()], &id()));
+    assert_eq!(utils::validate_user_address_authority_and_get_bump_seed(&id(), address, &authority).unwrap(), bump_seed);
+    assert_ne!(utils::user_authority(&id(), &[5; 32]).0, authority);
+}
+
+#[test]
+fn evm_address_test() {
+    let evm_address = [0x52, 0x90, 0x84, 0x00, 0x09, 0x85, 0x27, 0x88, 0x6e, 0x0f,
+        0x70, 0x30, 0x06, 0x98, 0x57, 0xd2, 0xe4, 0x16, 0x9e, 0xe7];
+    let padded = address_from_hex("0x52908400098527886e0f7030069857d2e4169ee7").unwrap();
+    assert_eq!(as_evm_address(&padded).unwrap(), evm_address);
+
+    let mut invalid = padded;
+    invalid[11] = 1;
+    assert_eq!(as_evm_address(&invalid).unwrap_err(), SolBridgeProgramError::InvalidAddress.into());
+    assert_eq!(as_evm_address(&[1; 32]).unwrap_err(), SolBridgeProgramError::InvalidAddress.into());
+}
+
+#[test]
+fn chain_id_newtype_test() {
+    for chain in ["ETH", "BSC", "SOLA", "ARBITRUM"] {
+        let chain_id: ChainId = chain.parse().unwrap();
+        assert_eq!(chain_id.to_string(), chain);
+        assert_eq!(BlockchainId::from(chain_id), str_to_chain_id(chain).unwrap());
+        assert_eq!(chain_id.try_to_vec().unwrap(), str_to_chain_id(chain).unwrap().to_vec());
+        assert_eq!(ChainId::try_from_slice(&str_to_chain_id(chain).unwrap()).unwrap(), chain_id);
+    }
+    assert_eq!(BlockchainId::from("SOLA".parse::<ChainId>().unwrap()), *b"SOLA\0\0\0\0");
+    assert_eq!("ETHEREUM2".parse::<ChainId>().unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[tokio::test]
+async fn ping_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+
+    bridge_context.ping(&mut program_context).await.unwrap();
+}
+
+/// `AddSignature` data of the ETH lock 1 with the given destination, addresses and amount
+fn add_signature_data(destination: String, sender: [u8; 32], recipient: [u8; 32], amount: u128) -> instruction::BridgeProgramInstruction {
+    instruction::BridgeProgramInstruction::AddSignature {
+        signature: [7; 65],
+        token_source: str_to_chain_id("ETH").unwrap(),
+        token_source_address: [3; 32],
+        source: str_to_chain_id("ETH").unwrap(),
+        lock_id: 1,
+        tx_id: [9; 64],
+        destination: str_to_chain_id(destination.as_str()).unwrap(),
+        sender,
+        recipient,
+        amount,
+        revert: false,
+        with_token: false,
+        with_tip: false,
+        nonce: 1
+    }
+}
+
+#[tokio::test]
+async fn zero_amount_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    let instruction = with_data(instruction, add_signature_data(String::from("ARBITRUM"), [2; 32], [4; 32], 0));
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidAmount as u32)));
+
+    let lock_pubkey = Pubkey::create_with_seed(&bridge_context.bridge_authority, "lock_ETH_1", &id()).unwrap();
+    assert!(program_context.banks_client.get_account(lock_pubkey).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn large_amount_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.set_destination_fee(&mut program_context, String::from("ARBITRUM"), 50).await.unwrap();
+
+    let amount = u64::MAX as u128 * 1000;
+    let (lock_pubkey, _, sender_user_pubkey, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        amount,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.amount, amount);
+    assert_eq!(lock_data.destination_fee, amount / 200);
+    assert_eq!(lock_data.net_amount().unwrap(), amount - amount / 200);
+    let sender_user_data = User::try_from_slice(&get_account(&mut program_context, &sender_user_pubkey).await.data).unwrap();
+    assert_eq!(sender_user_data.total_sent_amount, amount);
+}
+
+#[tokio::test]
+async fn zero_addresses_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    let instruction = with_data(instruction, add_signature_data(String::from("ARBITRUM"), [0; 32], [0; 32], 10000));
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidAddress as u32)));
+}
+
+#[tokio::test]
+async fn same_source_and_destination_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    let instruction = with_data(instruction, add_signature_data(String::from("ETH"), [2; 32], [4; 32], 10000));
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::SameSourceAndDestination as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn guardian_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let guardian = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &guardian.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+    let pause = |paused: bool| instruction::set_validator_paused(
+        &id(),
+        &bridge_context.bridge.pubkey(),
+        &validator_pubkey,
+        &bridge_context.bridge_authority,
+        &guardian.pubkey(),
+        String::from("ETH"),
+        0,
+        paused
+    ).unwrap();
+
+    let error = process_transaction(&mut program_context, &[pause(true)], &[&guardian]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let instruction = instruction::set_guardian(&id(), &bridge_context.bridge.pubkey(), &guardian.pubkey(), guardian.pubkey()).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&guardian]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    let instruction = instruction::set_guardian(&id(), &bridge_context.bridge.pubkey(), &payer, guardian.pubkey()).unwrap();
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+    let bridge_data = Bridge::try_from_slice(&get_account(&mut program_context, &bridge_context.bridge.pubkey()).await.data).unwrap();
+    assert_eq!(bridge_data.guardian, guardian.pubkey());
+
+    program_context.last_blockhash = program_context
+        .banks_client
+        .get_new_latest_blockhash(&program_context.last_blockhash)
+        .await
+        .unwrap();
+    process_transaction(&mut program_context, &[pause(true)], &[&guardian]).await.unwrap();
+    let validator_data = Validator::try_from_slice(&get_account(&mut program_context, &validator_pubkey).await.data).unwrap();
+    assert_eq!(validator_data.paused, true);
+
+    let error = process_transaction(&mut program_context, &[pause(false)], &[&guardian]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    bridge_context.set_validator_paused(&mut program_context, String::from("ETH"), 0, false).await.unwrap();
+    let validator_data = Validator::try_from_slice(&get_account(&mut program_context, &validator_pubkey).await.data).unwrap();
+    assert_eq!(validator_data.paused, false);
+}
+
+#[tokio::test]
+async fn set_validator_paused_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let validator_account = get_account(&mut program_context, &validator_pubkey).await;
+    let validator_data: Validator = Validator::try_from_slice(&validator_account.data).unwrap();
+    assert_eq!(validator_data.paused, false);
+
+    bridge_context.set_validator_paused(&mut program_context, String::from("ETH"), 0, true).await.unwrap();
+
+    let validator_account = get_account(&mut program_context, &validator_pubkey).await;
+    let validator_data: Validator = Validator::try_from_slice(&validator_account.data).unwrap();
+    assert_eq!(validator_data.paused, true);
+    assert_eq!(validator_data.active, true);
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::ValidatorPaused as u32)));
+
+    bridge_context.set_validator_paused(&mut program_context, String::from("ETH"), 0, false).await.unwrap();
+    bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn wrong_blockchain_account_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (mut instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    instruction.accounts[1].pubkey =
+        Pubkey::create_with_seed(&bridge_context.bridge_authority, "blockchain_ARBITRUM", &id()).unwrap();
+
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidSeeds));
+}
+
+#[tokio::test]
+async fn wrong_validator_account_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let forged_validator_pubkey = Pubkey::new_unique();
+    let forged_validator = Validator::new(str_to_chain_id("ETH").unwrap(), 0, [5; 32], KeyType::Secp256k1, program_context.payer.pubkey());
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    program_context.set_account(&forged_validator_pubkey, &Account {
+        lamports: rent.minimum_balance(Validator::LEN),
+        data: forged_validator.try_to_vec().unwrap(),
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    }.into());
+
+    let (mut instruction, (lock_pubkey, _, _, _, _, _)) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    // Validator account follows the route account
+    instruction.accounts[6].pubkey = forged_validator_pubkey;
+
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidSeeds));
+    assert!(program_context.banks_client.get_account(lock_pubkey).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn user_total_amounts_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ARBITRUM"), [5; 32]).await;
+
+    let (_, _, eth_user_pubkey, bsc_user_pubkey, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        100,
+        0,
+        false
+    ).await.unwrap();
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        250,
+        0,
+        false
+    ).await.unwrap();
+
+    let eth_user_account = get_account(&mut program_context, &eth_user_pubkey).await;
+    let eth_user_data: User = User::try_from_slice(&eth_user_account.data).unwrap();
+    assert_eq!(eth_user_data.total_sent_amount, 350);
+    assert_eq!(eth_user_data.total_received_amount, 0);
+
+    let bsc_user_account = get_account(&mut program_context, &bsc_user_pubkey).await;
+    let bsc_user_data: User = User::try_from_slice(&bsc_user_account.data).unwrap();
+    assert_eq!(bsc_user_data.total_sent_amount, 0);
+    assert_eq!(bsc_user_data.total_received_amount, 350);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ARBITRUM"),
+        [11; 64],
+        1,
+        String::from("ETH"),
+        [4; 32],
+        [2; 32],
+        50,
+        0,
+        false
+    ).await.unwrap();
+
+    let eth_user_account = get_account(&mut program_context, &eth_user_pubkey).await;
+    let eth_user_data: User = User::try_from_slice(&eth_user_account.data).unwrap();
+    assert_eq!(eth_user_data.total_sent_amount, 350);
+    assert_eq!(eth_user_data.total_received_amount, 50);
+
+    let bsc_user_account = get_account(&mut program_context, &bsc_user_pubkey).await;
+    let bsc_user_data: User = User::try_from_slice(&bsc_user_account.data).unwrap();
+    assert_eq!(bsc_user_data.total_sent_amount, 50);
+    assert_eq!(bsc_user_data.total_received_amount, 350);
+}
+
+#[tokio::test]
+async fn same_lock_and_signature_account_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (mut instruction, (lock_pubkey, _, _, _, _, _)) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    // Signature account follows the lock account
+    let signature_position = instruction.accounts.iter().position(|account| account.pubkey == lock_pubkey).unwrap() + 1;
+    instruction.accounts[signature_position].pubkey = lock_pubkey;
+
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::DuplicateLockAndSignatureAccount as u32)));
+}
+
+#[tokio::test]
+async fn add_validator_foreign_blockchain_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let other_bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    let other_blockchain_pubkey = other_bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+
+    let validator_pubkey =
+        Pubkey::create_with_seed(&bridge_context.bridge_authority, "validator_ETH_0", &id()).unwrap();
+    let owner = program_context.payer.pubkey();
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            instruction::add_validator(
+                &id(),
+                &bridge_context.bridge.pubkey(),
+                &other_blockchain_pubkey,
+                &validator_pubkey,
+                &owner,
+                &bridge_context.bridge_authority,
+                &owner,
+                String::from("ETH"),
+                [2; 32],
+                KeyType::Secp256k1
+            )
+                .unwrap(),
+        ],
+        &[],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+}
+
+#[tokio::test]
+async fn unsigned_payer_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+
+    let payer = Keypair::new();
+    let owner = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&owner, &payer.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+    let mut add_blockchain_instruction = instruction::add_blockchain(
+        &id(),
+        &bridge_context.bridge.pubkey(),
+        &pda::derive_blockchain(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id("ARBITRUM").unwrap()).unwrap(),
+        &payer.pubkey(),
+        &bridge_context.bridge_authority,
+        String::from("ARBITRUM"),
+        [6; 32],
+        MAX_VALIDATORS
+    ).unwrap();
+    add_blockchain_instruction.accounts[2].is_signer = false;
+    let error = process_transaction(&mut program_context, &[add_blockchain_instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    let mut add_validator_instruction = instruction::add_validator(
+        &id(),
+        &bridge_context.bridge.pubkey(),
+        &blockchain_pubkey,
+        &pda::derive_validator(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id("ETH").unwrap(), 0).unwrap(),
+        &payer.pubkey(),
+        &bridge_context.bridge_authority,
+        &payer.pubkey(),
+        String::from("ETH"),
+        [2; 32],
+        KeyType::Secp256k1
+    ).unwrap();
+    add_validator_instruction.accounts[3].is_signer = false;
+    let error = process_transaction(&mut program_context, &[add_validator_instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+}
+
+#[tokio::test]
+async fn foreign_owned_account_test() {
+    let fake_bridge = Pubkey::new_unique();
+    let mut program_test = program_test();
+    program_test.add_account(fake_bridge, Account {
+        lamports: 1_000_000,
+        data: Bridge::new(Pubkey::new_unique(), 255).try_to_vec().unwrap(),
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            instruction::ping(
+                &id(),
+                &fake_bridge
+            )
+                .unwrap(),
+        ],
+        &[],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::IncorrectProgramId));
+}
+
+#[tokio::test]
+async fn instruction_data_length_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let owner = program_context.payer.pubkey();
+    let instruction = instruction::set_dispute_window(&id(), &bridge_context.bridge.pubkey(), &owner, 3600).unwrap();
+
+    let mut trailing = instruction.clone();
+    trailing.data.push(0);
+    let mut truncated = instruction.clone();
+    truncated.data.pop();
+    for malformed in [trailing, truncated] {
+        let error = process_transaction(&mut program_context, &[malformed], &[]).await.unwrap_err();
+        assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidInstructionData));
+    }
+
+    process_transaction(&mut program_context, &[instruction], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn initialize_escrow_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let mint = create_mint(&mut program_context).await;
+    let other_mint = create_mint(&mut program_context).await;
+
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+    let other_escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, other_mint).await.unwrap();
+    assert_ne!(escrow_pubkey, other_escrow_pubkey);
+
+    let escrow_account = get_account(&mut program_context, &escrow_pubkey).await;
+    let escrow_data = spl_token::state::Account::unpack(&escrow_account.data).unwrap();
+    assert_eq!(escrow_data.mint, mint);
+    assert_eq!(escrow_data.owner, bridge_context.bridge_authority);
+
+    let payer = program_context.payer.pubkey();
+    let user_token_account = create_token_account(&mut program_context, &mint, &payer).await;
+    process_transaction(
+        &mut program_context,
+        &[
+            spl_token::instruction::mint_to(&spl_token::id(), &mint, &user_token_account, &payer, &[], 1000).unwrap(),
+            spl_token::instruction::transfer(&spl_token::id(), &user_token_account, &escrow_pubkey, &payer, &[], 400).unwrap(),
+        ],
+        &[],
+    ).await.unwrap();
+
+    let escrow_account = get_account(&mut program_context, &escrow_pubkey).await;
+    let escrow_data = spl_token::state::Account::unpack(&escrow_account.data).unwrap();
+    assert_eq!(escrow_data.amount, 400);
+}
+
+#[tokio::test]
+async fn lock_created_event_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+
+    let events = process_transaction_with_events(&mut program_context, &[instruction], &[]).await.unwrap();
+    assert_eq!(events[0], BridgeEvent::LockCreated(LockCreatedEvent {
+        lock_id: 1,
+        source: str_to_chain_id("ETH").unwrap(),
+        destination: str_to_chain_id("ARBITRUM").unwrap(),
+        sender: [2; 32],
+        recipient: [4; 32],
+        amount: 10000,
+    }));
+}
+
+#[tokio::test]
+async fn user_created_event_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let mut user_events = vec![];
+    for (lock_id, tx_id) in [(1, [9; 64]), (2, [8; 64])] {
+        let (instruction, _) = bridge_context.add_signature_instruction(
+            &mut program_context,
+            [7; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            tx_id,
+            lock_id,
+            String::from("ARBITRUM"),
+            [2; 32],
+            [4; 32],
+            10000,
+            0,
+            false,
+            None,
+            false
+        ).await;
+        let events = process_transaction_with_events(&mut program_context, &[instruction], &[]).await.unwrap();
+        user_events.push(events.into_iter().filter(|event| matches!(event, BridgeEvent::UserCreated(_))).collect::<Vec<_>>());
+    }
+
+    assert_eq!(user_events[0], vec![
+        BridgeEvent::UserCreated(UserCreatedEvent {blockchain_id: str_to_chain_id("ETH").unwrap(), address: [2; 32]}),
+        BridgeEvent::UserCreated(UserCreatedEvent {blockchain_id: str_to_chain_id("ARBITRUM").unwrap(), address: [4; 32]}),
+    ]);
+    assert!(user_events[1].is_empty());
+}
+
+#[tokio::test]
+async fn anchor_event_log_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+
+    let event_data = process_transaction_with_event_data(&mut program_context, &[instruction], &[]).await.unwrap();
+    // Lock created, sender and recipient users created, signature added
+    assert_eq!(event_data.len(), 4);
+
+    let lock_created_discriminator = &solana_program::hash::hash(b"event:LockCreated").to_bytes()[..8];
+    assert_eq!(&event_data[0][..8], lock_created_discriminator);
+    let lock_created = LockCreatedEvent::try_from_slice(&event_data[0][8..]).unwrap();
+    assert_eq!(lock_created.lock_id, 1);
+    assert_eq!(lock_created.amount, 10000);
+
+    let signature_added_discriminator = &solana_program::hash::hash(b"event:SignatureAdded").to_bytes()[..8];
+    assert_eq!(&event_data[3][..8], signature_added_discriminator);
+    let signature_added = SignatureAddedEvent::try_from_slice(&event_data[3][8..]).unwrap();
+    assert_eq!(signature_added.signatures_total, 1);
+
+    assert_eq!(BridgeEvent::from_log_data(&event_data[0]).unwrap(), BridgeEvent::LockCreated(lock_created));
+    assert_eq!(BridgeEvent::from_log_data(&[0; 8]).unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[tokio::test]
+async fn check_escrow_solvency_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+
+    // the token info registering the mint is required
+    let error = bridge_context.check_escrow_solvency(&mut program_context, String::from("ETH"), [3; 32], mint, &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::TokenNotRegistered as u32)));
+    bridge_context.register_token(&mut program_context, String::from("ETH"), [3; 32], mint).await.unwrap();
+    let other_mint = create_mint(&mut program_context).await;
+    bridge_context.initialize_escrow(&mut program_context, other_mint).await.unwrap();
+    let error = bridge_context.check_escrow_solvency(&mut program_context, String::from("ETH"), [3; 32], other_mint, &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::MintMismatch as u32)));
+
+    let sender = Keypair::new();
+    let (deposited_lock_pubkey, _, _, _, _, _) = bridge_context.add_signature_with_deposit(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        &sender,
+        [4; 32],
+        10000,
+        0,
+        mint,
+        escrow_pubkey
+    ).await.unwrap();
+    assert_eq!(bridge_context.check_escrow_solvency(&mut program_context, String::from("ETH"), [3; 32], mint, &[deposited_lock_pubkey]).await.unwrap(), (true, 0));
+
+    // locks created without a deposit are not backed by the escrow
+    let (undeposited_lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        5000,
+        0,
+        false
+    ).await.unwrap();
+    assert_eq!(
+        bridge_context.check_escrow_solvency(&mut program_context, String::from("ETH"), [3; 32], mint, &[deposited_lock_pubkey, undeposited_lock_pubkey]).await.unwrap(),
+        (true, 0)
+    );
+
+    // a lock passed twice is refused instead of being counted twice
+    let error = bridge_context.check_escrow_solvency(&mut program_context, String::from("ETH"), [3; 32], mint, &[deposited_lock_pubkey, deposited_lock_pubkey]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    // deposited locks the escrow can't cover are reported, locks of other tokens are skipped
+    let deposited_lock_account = get_account(&mut program_context, &deposited_lock_pubkey).await;
+    let deposited_lock_data = Lock::try_from_slice(&deposited_lock_account.data).unwrap();
+    let forged_lock_pubkey = Pubkey::new_unique();
+    program_context.set_account(&forged_lock_pubkey, &Account {
+        data: Lock { lock_id: 3, ..deposited_lock_data.clone() }.try_to_vec().unwrap(),
+        ..deposited_lock_account.clone()
+    }.into());
+    let other_token_lock_pubkey = Pubkey::new_unique();
+    program_context.set_account(&other_token_lock_pubkey, &Account {
+        data: Lock { lock_id: 4, token_source_address: [5; 32], ..deposited_lock_data }.try_to_vec().unwrap(),
+        ..deposited_lock_account
+    }.into());
+    assert_eq!(
+        bridge_context.check_escrow_solvency(&mut program_context, String::from("ETH"), [3; 32], mint, &[deposited_lock_pubkey, forged_lock_pubkey, other_token_lock_pubkey]).await.unwrap(),
+        (false, 10000)
+    );
+}
+
+#[tokio::test]
+async fn add_signature_with_token_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("SOL"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [2; 32]).await;
+
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+
+    let sender = Keypair::new();
+    let sender_token_pubkey = create_token_account(&mut program_context, &mint, &sender.pubkey()).await;
+    let payer = program_context.payer.pubkey();
+    process_transaction(
+        &mut program_context,
+        &[
+            spl_token::instruction::mint_to(&spl_token::id(), &mint, &sender_token_pubkey, &payer, &[], 10000).unwrap(),
+        ],
+        &[],
+    ).await.unwrap();
+
+    // Tokens are only moved for a registered token
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        sender.pubkey().to_bytes(),
+        [4; 32],
+        6000,
+        0,
+        false,
+        Some((sender_token_pubkey, escrow_pubkey, sender.pubkey())),
+        false
+    ).await;
+    let error = process_transaction(&mut program_context, &[instruction], &[&sender]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::TokenNotRegistered as u32)));
+    bridge_context.register_token(&mut program_context, String::from("SOL"), [3; 32], mint).await.unwrap();
+
+    // The sender has to sign as the owner of the token account
+    let (mut instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        sender.pubkey().to_bytes(),
+        [4; 32],
+        6000,
+        0,
+        false,
+        Some((sender_token_pubkey, escrow_pubkey, sender.pubkey())),
+        false
+    ).await;
+    instruction.accounts.last_mut().unwrap().is_signer = false;
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature));
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        sender.pubkey().to_bytes(),
+        [4; 32],
+        6000,
+        0,
+        false,
+        Some((sender_token_pubkey, escrow_pubkey, sender.pubkey())),
+        false
+    ).await;
+    program_context.last_blockhash = program_context
+        .banks_client
+        .get_new_latest_blockhash(&program_context.last_blockhash)
+        .await
+        .unwrap();
+    process_transaction(&mut program_context, &[instruction], &[&sender]).await.unwrap();
+
+    let sender_token_account = get_account(&mut program_context, &sender_token_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&sender_token_account.data).unwrap().amount, 4000);
+    let escrow_account = get_account(&mut program_context, &escrow_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&escrow_account.data).unwrap().amount, 6000);
+
+    // Token account of someone other than the sender is rejected
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [5; 32],
+        [4; 32],
+        1000,
+        0,
+        false,
+        Some((sender_token_pubkey, escrow_pubkey, sender.pubkey())),
+        false
+    ).await;
+    let error = process_transaction(&mut program_context, &[instruction], &[&sender]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+
+    // Token account of a mint other than the registered one is rejected
+    let other_mint = create_mint(&mut program_context).await;
+    let other_escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, other_mint).await.unwrap();
+    let other_token_pubkey = create_token_account(&mut program_context, &other_mint, &sender.pubkey()).await;
+    process_transaction(
+        &mut program_context,
+        &[
+            spl_token::instruction::mint_to(&spl_token::id(), &other_mint, &other_token_pubkey, &payer, &[], 10000).unwrap(),
+        ],
+        &[],
+    ).await.unwrap();
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [11; 64],
+        3,
+        String::from("ARBITRUM"),
+        sender.pubkey().to_bytes(),
+        [4; 32],
+        1000,
+        0,
+        false,
+        Some((other_token_pubkey, other_escrow_pubkey, sender.pubkey())),
+        false
+    ).await;
+    let error = process_transaction(&mut program_context, &[instruction], &[&sender]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::MintMismatch as u32)));
+}
+
+#[tokio::test]
+async fn withdraw_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("SOL"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("SOL"), 2).await.unwrap();
+
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+    bridge_context.register_token(&mut program_context, String::from("SOL"), [3; 32], mint).await.unwrap();
+    let recipient = Keypair::new();
+    let recipient_token_pubkey = create_token_account(&mut program_context, &mint, &recipient.pubkey()).await;
+    let payer = program_context.payer.pubkey();
+    let sender = Keypair::new();
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature_with_deposit(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        &sender,
+        recipient.pubkey().to_bytes(),
+        6000,
+        0,
+        mint,
+        escrow_pubkey
+    ).await.unwrap();
+    let lock_data: Lock = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.deposited, true);
+
+    let error = bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, escrow_pubkey, recipient_token_pubkey).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotFinalized as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        sender.pubkey().to_bytes(),
+        recipient.pubkey().to_bytes(),
+        6000,
+        1,
+        false
+    ).await.unwrap();
+
+    // A finalized lock whose tokens never reached the escrow is not paid out
+    for validator_index in 0..2 {
+        bridge_context.add_signature(
+            &mut program_context,
+            [7 + validator_index as u8; 65],
+            String::from("SOL"),
+            [3; 32],
+            String::from("SOL"),
+            [10; 64],
+            2,
+            String::from("ARBITRUM"),
+            [2; 32],
+            recipient.pubkey().to_bytes(),
+            6000,
+            validator_index,
+            false
+        ).await.unwrap();
+    }
+    let error = bridge_context.withdraw(&mut program_context, String::from("SOL"), 2, escrow_pubkey, recipient_token_pubkey).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotDeposited as u32)));
+
+    // Escrow of a mint other than the one registered for the lock token is not paid out
+    let other_mint = create_mint(&mut program_context).await;
+    let other_escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, other_mint).await.unwrap();
+    let other_recipient_token_pubkey = create_token_account(&mut program_context, &other_mint, &recipient.pubkey()).await;
+    process_transaction(
+        &mut program_context,
+        &[
+            spl_token::instruction::mint_to(&spl_token::id(), &other_mint, &other_escrow_pubkey, &payer, &[], 10000).unwrap(),
+        ],
+        &[],
+    ).await.unwrap();
+    let error = bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, other_escrow_pubkey, other_recipient_token_pubkey).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::MintMismatch as u32)));
+    let other_escrow_account = get_account(&mut program_context, &other_escrow_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&other_escrow_account.data).unwrap().amount, 10000);
+
+    bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, escrow_pubkey, recipient_token_pubkey).await.unwrap();
+
+    let recipient_token_account = get_account(&mut program_context, &recipient_token_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&recipient_token_account.data).unwrap().amount, 6000);
+    let escrow_account = get_account(&mut program_context, &escrow_pubkey).await;
+    assert_eq!(spl_token::state::Account::unpack(&escrow_account.data).unwrap().amount, 0);
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.withdrawn, true);
+    assert_eq!(bridge_context.check_escrow_solvency(&mut program_context, String::from("SOL"), [3; 32], mint, &[lock_pubkey]).await.unwrap(), (true, 0));
+
+    let error = bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, escrow_pubkey, recipient_token_pubkey).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockAlreadyWithdrawn as u32)));
+}
+
+#[tokio::test]
+async fn close_lock_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("SOL"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [2; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("SOL"), 1).await.unwrap();
+    bridge_context.set_dispute_window(&mut program_context, 3600).await.unwrap();
+
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+    bridge_context.register_token(&mut program_context, String::from("SOL"), [3; 32], mint).await.unwrap();
+    let recipient = Keypair::new();
+    let recipient_token_pubkey = create_token_account(&mut program_context, &mint, &recipient.pubkey()).await;
+    let sender = Keypair::new();
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature_with_deposit(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        &sender,
+        recipient.pubkey().to_bytes(),
+        6000,
+        0,
+        mint,
+        escrow_pubkey
+    ).await.unwrap();
+
+    let destination = Pubkey::new_unique();
+    let error = bridge_context.close_lock(&mut program_context, String::from("SOL"), 1, destination).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotWithdrawn as u32)));
+
+    bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, escrow_pubkey, recipient_token_pubkey).await.unwrap();
+
+    let error = bridge_context.close_lock(&mut program_context, String::from("SOL"), 1, destination).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::DisputeWindowOpen as u32)));
+
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    let mut clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = lock_data.unlock_time + 3601;
+    program_context.set_sysvar(&clock);
+
+    let lock_lamports = get_account(&mut program_context, &lock_pubkey).await.lamports;
+    let events = bridge_context.close_lock(&mut program_context, String::from("SOL"), 1, destination).await.unwrap();
+    assert_eq!(events, vec![BridgeEvent::LockClosed(LockClosedEvent {
+        source: str_to_chain_id("SOL").unwrap(),
+        lock_id: 1,
+        recipient: recipient.pubkey().to_bytes(),
+        amount: 6000,
+        unlock_time: lock_data.unlock_time,
+    })]);
+
+    assert!(program_context.banks_client.get_account(lock_pubkey).await.unwrap().is_none());
+    assert_eq!(get_account(&mut program_context, &destination).await.lamports, lock_lamports);
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("SOL"),
+        [3; 32],
+        String::from("SOL"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        recipient.pubkey().to_bytes(),
+        6000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::TxAlreadyLocked as u32)));
+}
+
+#[tokio::test]
+async fn solana_recipient_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("SOL"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let owner = program_context.payer.pubkey();
+    process_transaction(
+        &mut program_context,
+        &[instruction::set_solana_chain_id(&id(), &bridge_context.bridge.pubkey(), &owner, Some(String::from("SOL"))).unwrap()],
+        &[],
+    ).await.unwrap();
+    let bridge_data = Bridge::try_from_slice(&get_account(&mut program_context, &bridge_context.bridge.pubkey()).await.data).unwrap();
+    assert_eq!(bridge_data.solana_chain_id, str_to_chain_id("SOL").unwrap());
+
+    for recipient in [bridge_context.bridge_authority, bridge_context.bridge.pubkey()] {
+        let error = bridge_context.add_signature(
+            &mut program_context,
+            [7; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            [9; 64],
+            1,
+            String::from("SOL"),
+            [2; 32],
+            recipient.to_bytes(),
+            10000,
+            0,
+            false
+        ).await.unwrap_err();
+        assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidAddress as u32)));
+    }
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("SOL"),
+        [2; 32],
+        Keypair::new().pubkey().to_bytes(),
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    process_transaction(
+        &mut program_context,
+        &[instruction::set_solana_chain_id(&id(), &bridge_context.bridge.pubkey(), &owner, None).unwrap()],
+        &[],
+    ).await.unwrap();
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [8; 64],
+        2,
+        String::from("SOL"),
+        [2; 32],
+        bridge_context.bridge_authority.to_bytes(),
+        10000,
+        0,
+        false
+    ).await.unwrap();
+}
+
+#[tokio::test]
+async fn dispute_window_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("SOL"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("SOL"), [2; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("SOL"), 1).await.unwrap();
+
+    let owner = program_context.payer.pubkey();
+    let instruction = with_data(
+        instruction::set_dispute_window(&id(), &bridge_context.bridge.pubkey(), &owner, 0).unwrap(),
+        instruction::BridgeProgramInstruction::SetDisputeWindow {dispute_window: -1}
+    );
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+    bridge_context.set_dispute_window(&mut program_context, 3600).await.unwrap();
+    let bridge_account = get_account(&mut program_context, &bridge_context.bridge.pubkey()).await;
+    assert_eq!(Bridge::try_from_slice(&bridge_account.data).unwrap().dispute_window, 3600);
+
+    let mint = create_mint(&mut program_context).await;
+    let escrow_pubkey = bridge_context.initialize_escrow(&mut program_context, mint).await.unwrap();
+    bridge_context.register_token(&mut program_context, String::from("SOL"), [3; 32], mint).await.unwrap();
+    let recipient = Keypair::new();
+    let recipient_token_pubkey = create_token_account(&mut program_context, &mint, &recipient.pubkey()).await;
+    let sender = Keypair::new();
+
+    let mut lock_pubkeys = vec![];
+    for lock_id in 1..=2 {
+        let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature_with_deposit(
+            &mut program_context,
+            [7; 65],
+            String::from("SOL"),
+            [3; 32],
+            String::from("SOL"),
+            [8 + lock_id as u8; 64],
+            lock_id,
+            String::from("ARBITRUM"),
+            &sender,
+            recipient.pubkey().to_bytes(),
+            6000,
+            0,
+            mint,
+            escrow_pubkey
+        ).await.unwrap();
+        lock_pubkeys.push(lock_pubkey);
+    }
+
+    let error = bridge_context.revert_unlock(&mut program_context, String::from("SOL"), 1).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::LockNotWithdrawn as u32)));
+
+    bridge_context.withdraw(&mut program_context, String::from("SOL"), 1, escrow_pubkey, recipient_token_pubkey).await.unwrap();
+    bridge_context.withdraw(&mut program_context, String::from("SOL"), 2, escrow_pubkey, recipient_token_pubkey).await.unwrap();
+    let clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    let lock_account = get_account(&mut program_context, &lock_pubkeys[0]).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.unlock_time, clock.unix_timestamp);
+    assert_eq!(lock_data.unlock_reverted, false);
+
+    bridge_context.revert_unlock(&mut program_context, String::from("SOL"), 1).await.unwrap();
+    let lock_account = get_account(&mut program_context, &lock_pubkeys[0]).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.unlock_reverted, true);
+    assert_eq!(lock_data.withdrawn, true);
+
+    let error = bridge_context.revert_unlock(&mut program_context, String::from("SOL"), 1).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::UnlockAlreadyReverted as u32)));
+
+    let mut clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += 3601;
+    program_context.set_sysvar(&clock);
+
+    let error = bridge_context.revert_unlock(&mut program_context, String::from("SOL"), 2).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::DisputeWindowClosed as u32)));
+    let lock_account = get_account(&mut program_context, &lock_pubkeys[1]).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.unlock_reverted, false);
+
+    bridge_context.set_dispute_window(&mut program_context, 0).await.unwrap();
+    let mut clock: Clock = program_context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = lock_data.unlock_time;
+    program_context.set_sysvar(&clock);
+    let error = bridge_context.revert_unlock(&mut program_context, String::from("SOL"), 2).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::DisputeWindowClosed as u32)));
+}
+
+#[tokio::test]
+async fn oversized_account_test() {
+    let oversized_bridge = Pubkey::new_unique();
+    let mut data = Bridge::new(Pubkey::new_unique(), 255).try_to_vec().unwrap();
+    data.resize(Bridge::LEN + 8, 0);
+
+    let mut program_test = program_test();
+    program_test.add_account(oversized_bridge, Account {
+        lamports: 1_000_000,
+        data,
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            instruction::ping(
+                &id(),
+                &oversized_bridge
+            )
+                .unwrap(),
+        ],
+        &[],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+}
+
+#[tokio::test]
+async fn wrong_account_type_test() {
+    let confused_bridge = Pubkey::new_unique();
+    let mut data = Bridge::new(Pubkey::new_unique(), 255).try_to_vec().unwrap();
+    data[0] = AccountType::User as u8;
+
+    let mut program_test = program_test();
+    program_test.add_account(confused_bridge, Account {
+        lamports: 1_000_000,
+        data,
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            instruction::ping(
+                &id(),
+                &confused_bridge
+            )
+                .unwrap(),
+        ],
+        &[],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidAccountType as u32)));
+}
+
+#[tokio::test]
+async fn unmigrated_account_test() {
+    let previous_bridge = Pubkey::new_unique();
+    let mut bridge_data = Bridge::new(Pubkey::new_unique(), 255);
+    bridge_data.version = PREVIOUS_VERSION;
+
+    let mut program_test = program_test();
+    program_test.add_account(previous_bridge, Account {
+        lamports: 1_000_000,
+        data: bridge_data.try_to_vec().unwrap(),
+        owner: id(),
+        executable: false,
+        rent_epoch: 0,
+    });
+    let mut program_context = program_test.start_with_context().await;
+
+    let error = process_transaction(
+        &mut program_context,
+        &[
+            instruction::ping(
+                &id(),
+                &previous_bridge
+            )
+                .unwrap(),
+        ],
+        &[],
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::AccountNeedsMigration as u32)));
+}
+
+#[test]
+fn signature_pdas_for_lock_test() {
+    let bridge = Pubkey::new_unique();
+    let (bridge_authority, _) = pda::get_bridge_authority(&id(), &bridge);
+    let eth = str_to_chain_id("ETH").unwrap();
+
+    let signatures = utils::signature_pdas_for_lock(&id(), &bridge, eth, 7, 3, false).unwrap();
+    assert_eq!(signatures, vec![
+        Pubkey::create_with_seed(&bridge_authority, "signature_lock_ETH_7_0", &id()).unwrap(),
+        Pubkey::create_with_seed(&bridge_authority, "signature_lock_ETH_7_1", &id()).unwrap(),
+        Pubkey::create_with_seed(&bridge_authority, "signature_lock_ETH_7_2", &id()).unwrap(),
+    ]);
+
+    let revert_signatures = utils::signature_pdas_for_lock(&id(), &bridge, eth, 7, 2, true).unwrap();
+    assert_eq!(revert_signatures, vec![
+        pda::derive_signature(&id(), &bridge, eth, 7, 0, true).unwrap(),
+        pda::derive_signature(&id(), &bridge, eth, 7, 1, true).unwrap(),
+    ]);
+
+    assert!(utils::signature_pdas_for_lock(&id(), &bridge, eth, 7, 0, false).unwrap().is_empty());
+}
+
+#[test]
+fn account_unpack_test() {
+    use solbridge_master_contract::state::Account as BridgeAccount;
+
+    let eth = str_to_chain_id("ETH").unwrap();
+    let arbitrum = str_to_chain_id("ARBITRUM").unwrap();
+    let key = Pubkey::new_unique();
+    let accounts = vec![
+        BridgeAccount::Bridge(Bridge::new(key, 255)),
+        BridgeAccount::Blockchain(Blockchain::new(key, eth, [1; 32], MAX_VALIDATORS)),
+        BridgeAccount::Validator(Validator::new(eth, 0, [2; 32], KeyType::Secp256k1, key)),
+        BridgeAccount::Lock(Lock::new(0, 1, [9; 64], key, [3; 32], eth, eth, [2; 32], [4; 32], arbitrum, 10000, 0, 0, 0)),
+        BridgeAccount::Signature(Signature::new(eth, 1, key, [7; 65], key, 0, 0)),
+        BridgeAccount::User(User::new(eth, [2; 32])),
+        BridgeAccount::LockTx(LockTx::new([9; 64], eth, 1, key, false)),
+        BridgeAccount::TokenInfo(TokenInfo::new(key, eth, [3; 32], key)),
+        BridgeAccount::SenderAllowed(SenderAllowed::new(key, eth, [2; 32], true)),
+        BridgeAccount::Route(Route::new(key, eth, arbitrum, Some(10))),
+        BridgeAccount::TxIndex(TxIndex::new(key, eth, [9; 64], key)),
+        BridgeAccount::TipVault(TipVault::new(key, eth, [2; 32], key, 5000)),
+    ];
+    for account in accounts {
+        let data = match &account {
+            BridgeAccount::Bridge(data) => data.try_to_vec(),
+            BridgeAccount::Blockchain(data) => data.try_to_vec(),
+            BridgeAccount::Validator(data) => data.try_to_vec(),
+            BridgeAccount::Lock(data) => data.try_to_vec(),
+            BridgeAccount::Signature(data) => data.try_to_vec(),
+            BridgeAccount::User(data) => data.try_to_vec(),
+            BridgeAccount::LockTx(data) => data.try_to_vec(),
+            BridgeAccount::TokenInfo(data) => data.try_to_vec(),
+            BridgeAccount::SenderAllowed(data) => data.try_to_vec(),
+            BridgeAccount::Route(data) => data.try_to_vec(),
+            BridgeAccount::TxIndex(data) => data.try_to_vec(),
+            BridgeAccount::TipVault(data) => data.try_to_vec(),
+        }.unwrap();
+        assert_eq!(BridgeAccount::unpack(&data).unwrap(), account);
+    }
+
+    assert_eq!(BridgeAccount::unpack(&[]).unwrap_err(), ProgramError::InvalidAccountData);
+    assert_eq!(BridgeAccount::unpack(&[0; Bridge::LEN]).unwrap_err(), ProgramError::UninitializedAccount);
+    assert_eq!(BridgeAccount::unpack(&[13; Bridge::LEN]).unwrap_err(), ProgramError::Custom(SolBridgeProgramError::InvalidAccountType as u32));
+    assert_eq!(BridgeAccount::unpack(&Bridge::new(key, 255).try_to_vec().unwrap()[..Bridge::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+}
+
+#[test]
+fn account_version_test() {
+    let mut bridge = Bridge::new(Pubkey::new_unique(), 255);
+
+    bridge.version = 0;
+    assert_eq!(bridge.check_initialized(true), Err(ProgramError::UninitializedAccount));
+    bridge.check_initialized(false).unwrap();
+
+    bridge.version = PREVIOUS_VERSION;
+    assert_eq!(bridge.check_initialized(true), Err(SolBridgeProgramError::AccountNeedsMigration.into()));
+    assert_eq!(bridge.check_initialized(false), Err(ProgramError::AccountAlreadyInitialized));
+
+    bridge.version = PROGRAM_VERSION;
+    bridge.check_initialized(true).unwrap();
+    assert_eq!(bridge.check_initialized(false), Err(ProgramError::AccountAlreadyInitialized));
+
+    bridge.version = PROGRAM_VERSION + 1;
+    assert_eq!(bridge.check_initialized(true), Err(ProgramError::InvalidAccountData));
+    assert_eq!(bridge.check_initialized(false), Err(ProgramError::AccountAlreadyInitialized));
+}
+
+#[test]
+fn account_type_test() {
+    let invalid_account_type = Err(ProgramError::Custom(SolBridgeProgramError::InvalidAccountType as u32));
+
+    let mut bridge = Bridge::new(Pubkey::new_unique(), 255);
+    assert_eq!(bridge.account_type, AccountType::Bridge as u8);
+    bridge.check_initialized(true).unwrap();
+    bridge.account_type = AccountType::Blockchain as u8;
+    assert_eq!(bridge.check_initialized(true), invalid_account_type);
+
+    let mut validator = Validator::new(str_to_chain_id("ETH").unwrap(), 0, [2; 32], KeyType::Secp256k1, Pubkey::new_unique());
+    assert_eq!(validator.account_type, AccountType::Validator as u8);
+    validator.check_initialized(true).unwrap();
+    validator.account_type = AccountType::Signature as u8;
+    assert_eq!(validator.check_initialized(true), invalid_account_type);
+
+    let mut lock_tx = LockTx::new([9; 64], str_to_chain_id("ETH").unwrap(), 1, Pubkey::new_unique(), false);
+    assert_eq!(lock_tx.account_type, AccountType::LockTx as u8);
+    lock_tx.check_initialized(true).unwrap();
+    lock_tx.account_type = AccountType::User as u8;
+    assert_eq!(lock_tx.check_initialized(true), invalid_account_type);
+}
+
+#[test]
+fn pda_test() {
+    let bridge = Pubkey::new_unique();
+    let eth = str_to_chain_id("ETH").unwrap();
+    let (bridge_authority, _) = pda::get_bridge_authority(&id(), &bridge);
+    let (user_authority, _) = pda::user_authority(&id(), &[2; 32]);
+
+    assert_eq!(utils::validate_bridge_authority_and_get_bump_seed(&id(), &bridge, &bridge_authority), Ok(pda::get_bridge_authority(&id(), &bridge).1));
+    assert_eq!(utils::validate_user_address_authority_and_get_bump_seed(&id(), [2; 32], &user_authority), Ok(pda::user_authority(&id(), &[2; 32]).1));
+
+    let blockchain = pda::derive_blockchain(&id(), &bridge, eth).unwrap();
+    assert!(utils::check_and_get_blockchain_account_seed(&id(), eth, &bridge_authority, &blockchain).is_ok());
+
+    let validator = pda::derive_validator(&id(), &bridge, eth, 1).unwrap();
+    assert!(utils::check_and_get_validator_account_seed(&id(), eth, 1, &bridge_authority, &validator).is_ok());
+
+    for revert in [false, true] {
+        let lock = pda::derive_lock(&id(), &bridge, eth, 7, revert).unwrap();
+        assert!(utils::check_and_get_lock_account_seed(&id(), eth, 7, revert, &bridge_authority, &lock).is_ok());
+
+        let signature = pda::derive_signature(&id(), &bridge, eth, 7, 1, revert).unwrap();
+        assert!(utils::check_and_get_signature_account_seed(&id(), eth, 7, 1, revert, &bridge_authority, &signature).is_ok());
+    }
+
+    let user = pda::derive_user(&id(), &[2; 32], eth).unwrap();
+    assert!(utils::check_and_get_user_account_seed(&id(), eth, &user_authority, &user).is_ok());
+
+    let sent = pda::derive_lock_tx(&id(), &[2; 32], eth, 3, "sent").unwrap();
+    assert!(utils::check_and_get_sent_lock_account_seed(&id(), eth, &user_authority, 3, &sent).is_ok());
+
+    let received = pda::derive_lock_tx(&id(), &[2; 32], eth, 3, "received").unwrap();
+    assert!(utils::check_and_get_received_lock_account_seed(&id(), eth, &user_authority, 3, &received).is_ok());
+}
+
+#[tokio::test]
+async fn sender_allowlist_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.set_sender_allowlist_enforced(&mut program_context, String::from("ETH"), true).await.unwrap();
+
+    let sender_allowed_pubkey = bridge_context.set_sender_allowed(&mut program_context, String::from("ETH"), [2; 32], true).await.unwrap();
+    let sender_allowed_account = get_account(&mut program_context, &sender_allowed_pubkey).await;
+    let sender_allowed_data: SenderAllowed = SenderAllowed::try_from_slice(&sender_allowed_account.data).unwrap();
+    assert_eq!(sender_allowed_data.bridge, bridge_context.bridge.pubkey());
+    assert_eq!(sender_allowed_data.source, [0x45, 0x54, 0x48, 0x0, 0x0, 0x0, 0x0, 0x0]);
+    assert_eq!(sender_allowed_data.sender, [2; 32]);
+    assert_eq!(sender_allowed_data.allowed, true);
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
+        [5; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::SenderNotAllowed as u32)));
+
+    bridge_context.set_sender_allowed(&mut program_context, String::from("ETH"), [2; 32], false).await.unwrap();
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [11; 64],
+        3,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::SenderNotAllowed as u32)));
+}
+
+#[tokio::test]
+async fn get_bridge_authority_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+
+    let (bridge_authority, bump_seed) = utils::get_bridge_authority(&id(), &bridge_context.bridge.pubkey());
+    assert_eq!(bridge_authority, bridge_context.bridge_authority);
+    assert_eq!(
+        utils::validate_bridge_authority_and_get_bump_seed(&id(), &bridge_context.bridge.pubkey(), &bridge_authority),
+        Ok(bump_seed)
+    );
+
+    let bridge_data = Bridge::try_from_slice(&get_account(&mut program_context, &bridge_context.bridge.pubkey()).await.data).unwrap();
+    assert_eq!(bridge_data.authority_bump, bump_seed);
+    assert_eq!(
+        utils::validate_bridge_authority_with_bump_seed(&id(), &bridge_context.bridge.pubkey(), bridge_data.authority_bump, &bridge_authority),
+        Ok(bump_seed)
+    );
+    assert_eq!(
+        utils::validate_bridge_authority_with_bump_seed(&id(), &bridge_context.bridge.pubkey(), bridge_data.authority_bump, &Pubkey::new_unique()),
+        Err(ProgramError::InvalidSeeds)
+    );
+}
+
+#[tokio::test]
+async fn lock_params_mismatch_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    // Lock id and source are part of the lock account seed, so a mismatch fails the seed check instead
+    let cases = [
+        ("ETH", [3; 32], [8; 64], "ARBITRUM", [2; 32], [4; 32], 10000, SolBridgeProgramError::TxIdMismatch),
+        ("ETH", [8; 32], [9; 64], "ARBITRUM", [2; 32], [4; 32], 10000, SolBridgeProgramError::TokenSourceAddressMismatch),
+        ("ARBITRUM", [3; 32], [9; 64], "ARBITRUM", [2; 32], [4; 32], 10000, SolBridgeProgramError::TokenSourceMismatch),
+        ("ETH", [3; 32], [9; 64], "ARBITRUM", [8; 32], [4; 32], 10000, SolBridgeProgramError::SenderMismatch),
+        ("ETH", [3; 32], [9; 64], "ARBITRUM", [2; 32], [8; 32], 10000, SolBridgeProgramError::RecipientMismatch),
+        ("ETH", [3; 32], [9; 64], "SOL", [2; 32], [4; 32], 10000, SolBridgeProgramError::DestinationMismatch),
+        ("ETH", [3; 32], [9; 64], "ARBITRUM", [2; 32], [4; 32], 8000, SolBridgeProgramError::AmountMismatch),
+    ];
+
+    for (token_source, token_source_address, tx_id, destination, sender, recipient, amount, expected_error) in cases {
+        let error = bridge_context.add_signature(
+            &mut program_context,
+            [17; 65],
+            String::from(token_source),
+            token_source_address,
+            String::from("ETH"),
+            tx_id,
+            1,
+            String::from(destination),
+            sender,
+            recipient,
+            amount,
+            1,
+            false
+        ).await.unwrap_err();
+        assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(expected_error as u32)));
+    }
+}
+
+#[tokio::test]
+async fn lock_tx_matches_lock_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+
+    let (lock_pubkey, _, sender_user_pubkey, _, sent_lock_pubkey, received_lock_pubkey) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    let lock_txs = [
+        get_account(&mut program_context, &sent_lock_pubkey).await.data,
+        get_account(&mut program_context, &received_lock_pubkey).await.data,
+    ];
+    for data in &lock_txs {
+        let lock_tx_data = LockTx::try_from_slice(data).unwrap();
+        assert_eq!(lock_tx_data.tx_id, lock_data.tx_id);
+        assert_eq!(lock_tx_data.source, lock_data.source);
+        assert_eq!(lock_tx_data.lock_id, lock_data.lock_id);
+        assert_eq!(lock_tx_data.lock_account, lock_pubkey);
+    }
+
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [8; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [8; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        1,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::TxIdMismatch as u32)));
+
+    assert_eq!(get_account(&mut program_context, &sent_lock_pubkey).await.data, lock_txs[0]);
+    assert_eq!(get_account(&mut program_context, &received_lock_pubkey).await.data, lock_txs[1]);
+    let sender_user_data = User::try_from_slice(&get_account(&mut program_context, &sender_user_pubkey).await.data).unwrap();
+    assert_eq!(sender_user_data.sent, 1);
+}
+
+#[tokio::test]
+async fn add_signature_to_existing_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+
+    let validators = [TestValidator::new(11), TestValidator::new(12)];
+    for validator in validators.iter() {
+        bridge_context.add_validator(&mut program_context, String::from("ETH"), validator.address()).await;
+    }
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let message_hash = lock_message_hash(
+        &str_to_chain_id("ETH").unwrap(),
+        &[3; 32],
+        &str_to_chain_id("ETH").unwrap(),
+        &[9; 64],
+        1,
+        &str_to_chain_id("ARBITRUM").unwrap(),
+        &[2; 32],
+        &[4; 32],
+        10000,
+        false,
+        1,
+    );
+
+    let error = bridge_context.add_signature_to_existing(
+        &mut program_context,
+        String::from("ETH"),
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        false,
+        0,
+        validators[0].sign(&message_hash),
+        1
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::UninitializedAccount));
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        validators[0].sign(&message_hash),
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    // a relayed signature of another validator is refused
+    let relayer = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &relayer.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+    let (instruction, _) = bridge_context.add_signature_to_existing_instruction(
+        &relayer.pubkey(), String::from("ETH"), 1, String::from("ARBITRUM"), [2; 32], [4; 32], false, 1, validators[0].sign(&message_hash), 1
+    );
+    let error = process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[0], &message_hash, 0), instruction],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+
+    // the nonce is part of the signed message and has to be above the last one of the validator
+    let stale_nonce_hash = lock_message_hash(
+        &str_to_chain_id("ETH").unwrap(),
+        &[3; 32],
+        &str_to_chain_id("ETH").unwrap(),
+        &[9; 64],
+        1,
+        &str_to_chain_id("ARBITRUM").unwrap(),
+        &[2; 32],
+        &[4; 32],
+        10000,
+        false,
+        0,
+    );
+    let (instruction, _) = bridge_context.add_signature_to_existing_instruction(
+        &relayer.pubkey(), String::from("ETH"), 1, String::from("ARBITRUM"), [2; 32], [4; 32], false, 1, validators[1].sign(&message_hash), 2
+    );
+    let error = process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[1], &message_hash, 0), instruction],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+    let (instruction, _) = bridge_context.add_signature_to_existing_instruction(
+        &relayer.pubkey(), String::from("ETH"), 1, String::from("ARBITRUM"), [2; 32], [4; 32], false, 1, validators[1].sign(&stale_nonce_hash), 0
+    );
+    let error = process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[1], &stale_nonce_hash, 0), instruction],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::StaleNonce as u32)));
+
+    // the validator owner attests with the transaction signature as for new locks
+    let signature_pubkey = bridge_context.add_signature_to_existing(
+        &mut program_context,
+        String::from("ETH"),
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        false,
+        1,
+        validators[1].sign(&message_hash),
+        1
+    ).await.unwrap();
+
+    let signature_account = get_account(&mut program_context, &signature_pubkey).await;
+    let signature_data: Signature = Signature::try_from_slice(&signature_account.data).unwrap();
+    assert_eq!(signature_data.validator_index, 1);
+    assert_eq!(signature_data.lock_id, 1);
+    assert_eq!(signature_data.signature, [0; 65]);
+
+    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+    assert_eq!(lock_data.signatures, 2);
+    assert_eq!(lock_data.finalized, true);
+}
+
+#[test]
+fn ed25519_instruction_verifies_test() {
+    let keypair = Keypair::new();
+    let message = [7; 32];
+    let instruction = ed25519_instruction(&keypair, &message);
+    let signature = keypair.sign_message(&message);
+    let public_key = keypair.pubkey().to_bytes();
+
+    assert!(ed25519_instruction_verifies(&instruction.data, &public_key, &message, signature.as_ref()));
+    assert!(!ed25519_instruction_verifies(&instruction.data, &Pubkey::new_unique().to_bytes(), &message, signature.as_ref()));
+    assert!(!ed25519_instruction_verifies(&instruction.data, &public_key, &[8; 32], signature.as_ref()));
+    assert!(!ed25519_instruction_verifies(&instruction.data, &public_key, &message, &[0; 64]));
+    assert!(!ed25519_instruction_verifies(&instruction.data[..20], &public_key, &message, signature.as_ref()));
+    assert!(!ed25519_instruction_verifies(&[], &public_key, &message, signature.as_ref()));
+
+    // data read from another instruction is not trusted
+    let mut data = instruction.data.clone();
+    data[4..6].copy_from_slice(&0u16.to_le_bytes());
+    assert!(!ed25519_instruction_verifies(&data, &public_key, &message, signature.as_ref()));
+}
+
+#[tokio::test]
+async fn ed25519_validator_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+
+    let validator = TestValidator::new(11);
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), validator.address()).await;
+    let ed25519_validator = Keypair::new();
+    let ed25519_validator_pubkey = bridge_context.add_validator_with_key_type(
+        &mut program_context, String::from("ETH"), ed25519_validator.pubkey().to_bytes(), KeyType::Ed25519
+    ).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let validator_data = Validator::try_from_slice(&get_account(&mut program_context, &ed25519_validator_pubkey).await.data).unwrap();
+    assert_eq!(validator_data.key_type, KeyType::Ed25519 as u8);
+
+    let message_hash = lock_message_hash(
+        &str_to_chain_id("ETH").unwrap(),
+        &[3; 32],
+        &str_to_chain_id("ETH").unwrap(),
+        &[9; 64],
+        1,
+        &str_to_chain_id("ARBITRUM").unwrap(),
+        &[2; 32],
+        &[4; 32],
+        10000,
+        false,
+        1,
+    );
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        validator.sign(&message_hash),
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    let mut signature = [0; 65];
+    signature[..64].copy_from_slice(ed25519_validator.sign_message(&message_hash).as_ref());
+    let relayer = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &relayer.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+    let (add_signature_instruction, signature_pubkey) = bridge_context.add_signature_to_existing_instruction(
+        &relayer.pubkey(), String::from("ETH"), 1, String::from("ARBITRUM"), [2; 32], [4; 32], false, 1, signature, 1
+    );
+
+    // the relayed ed25519 signature is checked by the precompile
+    let error = process_transaction(&mut program_context, &[add_signature_instruction.clone()], &[&relayer]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+
+    let error = process_transaction(
+        &mut program_context,
+        &[ed25519_instruction(&ed25519_validator, &[5; 32]), add_signature_instruction.clone()],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+
+    process_transaction(
+        &mut program_context,
+        &[ed25519_instruction(&ed25519_validator, &message_hash), add_signature_instruction],
+        &[&relayer]
+    ).await.unwrap();
+
+    let signature_data = Signature::try_from_slice(&get_account(&mut program_context, &signature_pubkey).await.data).unwrap();
+    assert_eq!(signature_data.validator_index, 1);
+    assert_eq!(signature_data.signature, signature);
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.finalized, true);
+
+    // unknown key types are rejected
+    let bridge = bridge_context.bridge.pubkey();
+    let eth = str_to_chain_id("ETH").unwrap();
+    let add_validator_instruction = instruction::add_validator(
+        &id(),
+        &bridge,
+        &pda::derive_blockchain(&id(), &bridge, eth).unwrap(),
+        &pda::derive_validator(&id(), &bridge, eth, 2).unwrap(),
+        &payer,
+        &bridge_context.bridge_authority,
+        &payer,
+        String::from("ETH"),
+        [7; 32],
+        KeyType::Secp256k1
+    ).unwrap();
+    let error = process_transaction(
+        &mut program_context,
+        &[with_data(add_validator_instruction, instruction::BridgeProgramInstruction::AddValidator { blockchain_id: eth, pub_key: [7; 32], key_type: 2 })],
+        &[]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InvalidArgument));
+}
+
+#[test]
+fn secp256k1_instruction_verifies_test() {
+    let validator = TestValidator::new(11);
+    let message_hash = [7; 32];
+    let message = [ETH_SIGNED_MESSAGE_PREFIX, message_hash.as_ref()].concat();
+    let signature = validator.sign(&message_hash);
+    let instruction = secp256k1_instruction(&validator, &message_hash, 1);
+
+    assert!(secp256k1_instruction_verifies(&instruction.data, 1, &validator.address(), &message, &signature));
+    let mut eth_signature = signature;
+    eth_signature[64] += 27;
+    assert!(secp256k1_instruction_verifies(&instruction.data, 1, &validator.address(), &message, &eth_signature));
+    assert!(!secp256k1_instruction_verifies(&instruction.data, 0, &validator.address(), &message, &signature));
+    assert!(!secp256k1_instruction_verifies(&instruction.data, 1, &TestValidator::new(12).address(), &message, &signature));
+    assert!(!secp256k1_instruction_verifies(&instruction.data, 1, &validator.address(), &message[1..], &signature));
+    assert!(!secp256k1_instruction_verifies(&instruction.data, 1, &validator.address(), &message, &[0; 65]));
+    assert!(!secp256k1_instruction_verifies(&instruction.data[..10], 1, &validator.address(), &message, &signature));
+}
 
 #[tokio::test]
-async fn add_blockchain_test() {
+async fn relayed_signature_test() {
     let mut program_context = program_test().start_with_context().await;
     let bridge_context = BridgeContext::init(&mut program_context).await;
-    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
 
-    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
-    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
-    println!("{:?}", blockchain_data);
-    assert_eq!(blockchain_data.version, 1);
-    assert_eq!(blockchain_data.bridge, bridge_context.bridge.pubkey());
-    assert_eq!(blockchain_data.blockchain_id, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(blockchain_data.validators, 0);
-    assert_eq!(blockchain_data.contract_address, [1;32]);
+    let validators = [TestValidator::new(11), TestValidator::new(12)];
+    for validator in validators.iter() {
+        bridge_context.add_validator(&mut program_context, String::from("ETH"), validator.address()).await;
+    }
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    let relayer = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &relayer.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+    let message_hash = lock_message_hash(
+        &str_to_chain_id("ETH").unwrap(),
+        &[3; 32],
+        &str_to_chain_id("ETH").unwrap(),
+        &[9; 64],
+        1,
+        &str_to_chain_id("ARBITRUM").unwrap(),
+        &[2; 32],
+        &[4; 32],
+        10000,
+        false,
+        1,
+    );
+    let (mut add_signature_instruction, (lock_pubkey, signature_pubkey, _, _, _, _)) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        validators[0].sign(&message_hash),
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false,
+        None,
+        false
+    ).await;
+    add_signature_instruction.accounts[16].pubkey = relayer.pubkey();
+
+    // a relayer has to prove the validator signature with a precompile instruction
+    let error = process_transaction(&mut program_context, &[add_signature_instruction.clone()], &[&relayer]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+
+    let error = process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[1], &message_hash, 0), add_signature_instruction.clone()],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+
+    // the relayer can't pick another nonce than the one the validator signed
+    let mut data = instruction::BridgeProgramInstruction::try_from_slice(&add_signature_instruction.data).unwrap();
+    if let instruction::BridgeProgramInstruction::AddSignature { ref mut nonce, .. } = data {
+        *nonce = u64::MAX;
+    }
+    let error = process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[0], &message_hash, 0), with_data(add_signature_instruction.clone(), data)],
+        &[&relayer]
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(1, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+
+    process_transaction(
+        &mut program_context,
+        &[secp256k1_instruction(&validators[0], &message_hash, 0), add_signature_instruction],
+        &[&relayer]
+    ).await.unwrap();
+
+    let signature_data = Signature::try_from_slice(&get_account(&mut program_context, &signature_pubkey).await.data).unwrap();
+    assert_eq!(signature_data.validator_index, 0);
+    assert_eq!(signature_data.signature, validators[0].sign(&message_hash));
+    let lock_data = Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap();
+    assert_eq!(lock_data.signatures, 1);
 }
 
 #[tokio::test]
-async fn add_validator_test() {
+async fn tx_index_test() {
     let mut program_context = program_test().start_with_context().await;
     let bridge_context = BridgeContext::init(&mut program_context).await;
-    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
-    let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
 
-    let validator_account = get_account(&mut program_context, &validator_pubkey).await;
-    let validator_data: Validator = Validator::try_from_slice(&validator_account.data).unwrap();
-    println!("{:?}", validator_data);
-    assert_eq!(validator_data.version, 1);
-    assert_eq!(validator_data.blockchain_id, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(validator_data.index, 0);
-    assert_eq!(validator_data.pub_key, [2;32]);
+    for validator_index in 0..2 {
+        bridge_context.add_signature(
+            &mut program_context,
+            [7 + validator_index as u8; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            [9; 64],
+            1,
+            String::from("ARBITRUM"),
+            [2; 32],
+            [4; 32],
+            10000,
+            validator_index,
+            false
+        ).await.unwrap();
+    }
 
-    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
-    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
-    assert_eq!(blockchain_data.validators, 1);
+    let lock_pubkey = pda::derive_lock(&id(), &bridge_context.bridge.pubkey(), str_to_chain_id("ETH").unwrap(), 1, false).unwrap();
+    let (tx_index_pubkey, _) = get_tx_index_address(&id(), &bridge_context.bridge.pubkey(), &str_to_chain_id("ETH").unwrap(), &[9; 64]);
+    let tx_index_account = get_account(&mut program_context, &tx_index_pubkey).await;
+    assert_eq!(tx_index_account.owner, id());
+    let tx_index_data = TxIndex::try_from_slice(&tx_index_account.data).unwrap();
+    tx_index_data.check_initialized(true).unwrap();
+    assert_eq!(tx_index_data.lock_account, lock_pubkey);
+    assert_eq!(tx_index_data.tx_id, [9; 64]);
+
+    // another lock id can't claim the same remote transaction
+    let error = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        2,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::TxAlreadyLocked as u32)));
 }
 
 #[tokio::test]
-async fn add_signature_test() {
+async fn validator_nonce_test() {
     let mut program_context = program_test().start_with_context().await;
     let bridge_context = BridgeContext::init(&mut program_context).await;
     bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
     let validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
 
-    let (
-        lock_pubkey,
-        signature_pubkey,
-        sender_pubkey,
-        recipient_pubkey,
-        sent_lock_pubkey,
-        received_lock_pubkey
-    ) = bridge_context.add_signature(
+    bridge_context.add_signature(
         &mut program_context,
         [7; 65],
         String::from("ETH"),
@@ -371,147 +5992,389 @@ async fn add_signature_test() {
         String::from("ETH"),
         [9; 64],
         1,
-        String::from("BSC"),
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+    let validator_data = Validator::try_from_slice(&get_account(&mut program_context, &validator_pubkey).await.data).unwrap();
+    assert_eq!(validator_data.nonce, 1);
+
+    let (instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [10; 64],
+        2,
+        String::from("ARBITRUM"),
         [2; 32],
         [4; 32],
         10000,
-        0
+        0,
+        false,
+        None,
+        false
     ).await;
+    let with_nonce = |nonce: u64| {
+        let mut data = instruction::BridgeProgramInstruction::try_from_slice(&instruction.data).unwrap();
+        if let instruction::BridgeProgramInstruction::AddSignature { nonce: ref mut data_nonce, .. } = data {
+            *data_nonce = nonce;
+        }
+        with_data(instruction.clone(), data)
+    };
 
+    // a nonce already used or older is refused
+    for stale_nonce in [0, 1] {
+        let error = process_transaction(&mut program_context, &[with_nonce(stale_nonce)], &[]).await.unwrap_err();
+        assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::StaleNonce as u32)));
+    }
 
-    let lock_account = get_account(&mut program_context, &lock_pubkey).await;
-    let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
-    println!("{:?}", lock_data);
-    assert_eq!(lock_data.version, 1);
-    assert_eq!(lock_data.index, 0);
-    assert_eq!(lock_data.lock_id, 1);
-    assert_eq!(lock_data.bridge, bridge_context.bridge.pubkey());
-    assert_eq!(lock_data.token_source_address, [3; 32]);
-    assert_eq!(lock_data.token_source, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(lock_data.source, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(lock_data.recipient, [4; 32]);
-    assert_eq!(lock_data.destination, [0x42, 0x53, 0x43, 0x0]);
-    assert_eq!(lock_data.amount, 10000);
-    assert_eq!(lock_data.signatures, 1);
+    // nonces may be skipped, the skipped ones can't be used afterwards
+    process_transaction(&mut program_context, &[with_nonce(3)], &[]).await.unwrap();
+    let validator_data = Validator::try_from_slice(&get_account(&mut program_context, &validator_pubkey).await.data).unwrap();
+    assert_eq!(validator_data.nonce, 3);
+    let error = process_transaction(&mut program_context, &[with_nonce(2)], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::StaleNonce as u32)));
+}
 
-    let signature_account = get_account(&mut program_context, &signature_pubkey).await;
-    let signature_data: Signature = Signature::try_from_slice(&signature_account.data).unwrap();
-    println!("{:?}", signature_data);
-    assert_eq!(signature_data.version, 1);
-    assert_eq!(signature_data.source, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(signature_data.lock_id, 1);
-    assert_eq!(signature_data.bridge, bridge_context.bridge.pubkey());
-    assert_eq!(signature_data.signature, [7; 65]);
-    assert_eq!(signature_data.validator, validator_pubkey);
-    assert_eq!(signature_data.validator_index, 0);
+fn packed_len<T: Packed + BorshDeserialize>(rent: &Rent) -> usize {
+    assert_eq!(T::rent_exempt_balance(rent), rent.minimum_balance(T::LEN));
+    T::try_from_slice(&vec![0; T::LEN]).unwrap();
+    T::LEN
+}
 
-    let sender_account = get_account(&mut program_context, &sender_pubkey).await;
-    let sender_data: User = User::try_from_slice(&sender_account.data).unwrap();
-    println!("{:?}", sender_data);
-    assert_eq!(sender_data.version, 1);
-    assert_eq!(sender_data.blockchain_id, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(sender_data.address, [2; 32]);
-    assert_eq!(sender_data.sent, 1);
-    assert_eq!(sender_data.received, 0);
+#[test]
+fn packed_len_test() {
+    let rent = Rent::default();
+    let lens = [
+        (packed_len::<Bridge>(&rent), Bridge::LEN),
+        (packed_len::<Blockchain>(&rent), Blockchain::LEN),
+        (packed_len::<Validator>(&rent), Validator::LEN),
+        (packed_len::<Lock>(&rent), Lock::LEN),
+        (packed_len::<Signature>(&rent), Signature::LEN),
+        (packed_len::<User>(&rent), User::LEN),
+        (packed_len::<LockTx>(&rent), LockTx::LEN),
+        (packed_len::<TokenInfo>(&rent), TokenInfo::LEN),
+        (packed_len::<SenderAllowed>(&rent), SenderAllowed::LEN),
+        (packed_len::<Route>(&rent), Route::LEN),
+        (packed_len::<TxIndex>(&rent), TxIndex::LEN),
+        (packed_len::<TipVault>(&rent), TipVault::LEN),
+    ];
+    for (packed, len) in lens.iter() {
+        assert_eq!(packed, len);
+    }
+}
 
-    let recipient_account = get_account(&mut program_context, &recipient_pubkey).await;
-    let recipient_data: User = User::try_from_slice(&recipient_account.data).unwrap();
-    println!("{:?}", recipient_data);
-    assert_eq!(recipient_data.version, 1);
-    assert_eq!(recipient_data.blockchain_id, [0x42, 0x53, 0x43, 0x0]);
-    assert_eq!(recipient_data.address, [4; 32]);
-    assert_eq!(recipient_data.sent, 0);
-    assert_eq!(recipient_data.received, 1);
+#[tokio::test]
+async fn max_validators_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain_with_max_validators(&mut program_context, String::from("ETH"), [1; 32], 2).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [3; 32]).await;
 
-    let sent_lock_account = get_account(&mut program_context, &sent_lock_pubkey).await;
-    let sent_lock_data: LockTx = LockTx::try_from_slice(&sent_lock_account.data).unwrap();
-    println!("{:?}", sent_lock_data);
-    assert_eq!(sent_lock_data.version, 1);
-    assert_eq!(sent_lock_data.tx_id, [9; 64]);
+    let validator_pubkey =
+        Pubkey::create_with_seed(&bridge_context.bridge_authority, "validator_ETH_2", &id()).unwrap();
+    let instruction = instruction::add_validator(
+        &id(),
+        &bridge_context.bridge.pubkey(),
+        &blockchain_pubkey,
+        &validator_pubkey,
+        &program_context.payer.pubkey(),
+        &bridge_context.bridge_authority,
+        &program_context.payer.pubkey(),
+        String::from("ETH"),
+        [4; 32],
+        KeyType::Secp256k1
+    ).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::ValidatorLimitReached as u32)));
 
-    let received_lock_account = get_account(&mut program_context, &received_lock_pubkey).await;
-    let received_lock_data: LockTx = LockTx::try_from_slice(&received_lock_account.data).unwrap();
-    println!("{:?}", received_lock_data);
-    assert_eq!(received_lock_data.version, 1);
-    assert_eq!(received_lock_data.tx_id, [9; 64]);
+    let blockchain_account = get_account(&mut program_context, &blockchain_pubkey).await;
+    let blockchain_data: Blockchain = Blockchain::try_from_slice(&blockchain_account.data).unwrap();
+    assert_eq!(blockchain_data.validators, 2);
+    assert_eq!(blockchain_data.max_validators, 2);
+}
+
+#[tokio::test]
+async fn underfunded_payer_test() {
+    let mut program_context = program_test().start_with_context().await;
 
+    let poor_payer = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &poor_payer.pubkey(), 1_000_000)], &[]).await.unwrap();
 
-    let (
-        lock_pubkey,
-        signature_pubkey,
-        sender_pubkey,
-        recipient_pubkey,
-        sent_lock_pubkey,
-        received_lock_pubkey
-    ) = bridge_context.add_signature(
+    // the bridge owner pays for the blockchain account
+    let bridge = Keypair::new();
+    let (bridge_authority, _) = pda::get_bridge_authority(&id(), &bridge.pubkey());
+    let rent = program_context.banks_client.get_rent().await.unwrap();
+    process_transaction(
         &mut program_context,
-        [17; 65],
+        &[
+            system_instruction::create_account(&payer, &bridge.pubkey(), rent.minimum_balance(Bridge::LEN), Bridge::LEN as u64, &id()),
+            instruction::init_bridge(&id(), &bridge.pubkey(), &poor_payer.pubkey()).unwrap(),
+        ],
+        &[&bridge, &poor_payer],
+    ).await.unwrap();
+
+    let blockchain_pubkey =
+        Pubkey::create_with_seed(&bridge_authority, "blockchain_ETH", &id()).unwrap();
+    let instruction = instruction::add_blockchain(
+        &id(),
+        &bridge.pubkey(),
+        &blockchain_pubkey,
+        &poor_payer.pubkey(),
+        &bridge_authority,
+        String::from("ETH"),
+        [1; 32],
+        MAX_VALIDATORS
+    ).unwrap();
+    let error = process_transaction(&mut program_context, &[instruction], &[&poor_payer]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::InsufficientFunds));
+}
+
+#[tokio::test]
+async fn validator_authorization_errors_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [5; 32]).await;
+    let arbitrum_validator_pubkey = bridge_context.add_validator(&mut program_context, String::from("ARBITRUM"), [2; 32]).await;
+    bridge_context.set_threshold(&mut program_context, String::from("ETH"), 2).await.unwrap();
+
+    // a validator of another blockchain can't sign ETH locks
+    let (mut instruction, _) = bridge_context.add_signature_instruction(
+        &mut program_context,
+        [7; 65],
         String::from("ETH"),
         [3; 32],
         String::from("ETH"),
         [9; 64],
         1,
-        String::from("BSC"),
+        String::from("ARBITRUM"),
         [2; 32],
         [4; 32],
         10000,
-        0
+        0,
+        false,
+        None,
+        false
     ).await;
+    instruction.accounts[6].pubkey = arbitrum_validator_pubkey;
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::ValidatorChainMismatch as u32)));
+
+    bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [9; 64],
+        1,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
+
+    // any other payer only relays a signature checked by a precompile, for existing locks too
+    let stranger = Keypair::new();
+    let payer = program_context.payer.pubkey();
+    process_transaction(&mut program_context, &[system_instruction::transfer(&payer, &stranger.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+    let (instruction, _) = bridge_context.add_signature_to_existing_instruction(&stranger.pubkey(), String::from("ETH"), 1, String::from("ARBITRUM"), [2; 32], [4; 32], false, 1, [8; 65], 1);
+    let error = process_transaction(&mut program_context, &[instruction], &[&stranger]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::Secp256InstructionLosing as u32)));
+}
+
+#[tokio::test]
+async fn route_fee_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("OPTIMISM"), [8; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+    bridge_context.set_destination_fee(&mut program_context, String::from("ARBITRUM"), 50).await.unwrap();
+    bridge_context.set_destination_fee(&mut program_context, String::from("OPTIMISM"), 50).await.unwrap();
+
+    let (route_pubkey, _) = Pubkey::find_program_address(
+        &[bridge_context.bridge.pubkey().as_ref(), b"route", str_to_chain_id("ETH").unwrap().as_ref(), str_to_chain_id("ARBITRUM").unwrap().as_ref()],
+        &id(),
+    );
+    let owner = program_context.payer.pubkey();
+    let instruction = with_data(
+        instruction::set_route_fee(&id(), &bridge_context.bridge.pubkey(), &route_pubkey, &owner, String::from("ETH"), String::from("ARBITRUM"), None).unwrap(),
+        instruction::BridgeProgramInstruction::SetRouteFee {
+            source: str_to_chain_id("ETH").unwrap(),
+            destination: str_to_chain_id("ARBITRUM").unwrap(),
+            fee_bps: Some(10001)
+        }
+    );
+    let error = process_transaction(&mut program_context, &[instruction], &[]).await.unwrap_err();
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(SolBridgeProgramError::InvalidFee as u32)));
+
+    assert_eq!(bridge_context.set_route_fee(&mut program_context, String::from("ETH"), String::from("ARBITRUM"), Some(20)).await.unwrap(), route_pubkey);
+    let route_account = get_account(&mut program_context, &route_pubkey).await;
+    assert_eq!(route_account.data.len(), Route::LEN);
+    let route_data: Route = Route::try_from_slice(&route_account.data).unwrap();
+    assert_eq!(route_data.source, str_to_chain_id("ETH").unwrap());
+    assert_eq!(route_data.destination, str_to_chain_id("ARBITRUM").unwrap());
+    assert_eq!(route_data.fee_override, true);
+    assert_eq!(route_data.fee_bps, 20);
+
+    let mut lock_id = 0;
+    for (destination, expected_fee) in [("ARBITRUM", 20), ("OPTIMISM", 50)] {
+        lock_id += 1;
+        let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+            &mut program_context,
+            [7; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            [8 + lock_id as u8; 64],
+            lock_id,
+            String::from(destination),
+            [2; 32],
+            [4; 32],
+            10000,
+            0,
+            false
+        ).await.unwrap();
+
+        let lock_account = get_account(&mut program_context, &lock_pubkey).await;
+        let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
+        assert_eq!(lock_data.destination_fee, expected_fee);
+    }
+
+    bridge_context.set_route_fee(&mut program_context, String::from("ETH"), String::from("ARBITRUM"), None).await.unwrap();
+
+    let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+        &mut program_context,
+        [7; 65],
+        String::from("ETH"),
+        [3; 32],
+        String::from("ETH"),
+        [11; 64],
+        3,
+        String::from("ARBITRUM"),
+        [2; 32],
+        [4; 32],
+        10000,
+        0,
+        false
+    ).await.unwrap();
 
     let lock_account = get_account(&mut program_context, &lock_pubkey).await;
     let lock_data: Lock = Lock::try_from_slice(&lock_account.data).unwrap();
-    println!("{:?}", lock_data);
-    assert_eq!(lock_data.version, 1);
-    assert_eq!(lock_data.index, 0);
-    assert_eq!(lock_data.lock_id, 1);
-    assert_eq!(lock_data.bridge, bridge_context.bridge.pubkey());
-    assert_eq!(lock_data.token_source_address, [3; 32]);
-    assert_eq!(lock_data.token_source, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(lock_data.source, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(lock_data.recipient, [4; 32]);
-    assert_eq!(lock_data.destination, [0x42, 0x53, 0x43, 0x0]);
-    assert_eq!(lock_data.amount, 10000);
-    assert_eq!(lock_data.signatures, 2);
+    assert_eq!(lock_data.destination_fee, 50);
+}
 
-    let signature_account = get_account(&mut program_context, &signature_pubkey).await;
-    let signature_data: Signature = Signature::try_from_slice(&signature_account.data).unwrap();
-    println!("{:?}", signature_data);
-    assert_eq!(signature_data.version, 1);
-    assert_eq!(signature_data.source, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(signature_data.lock_id, 1);
-    assert_eq!(signature_data.bridge, bridge_context.bridge.pubkey());
-    assert_eq!(signature_data.signature, [17; 65]);
-    assert_eq!(signature_data.validator, validator_pubkey);
-    assert_eq!(signature_data.validator_index, 0);
+#[cfg(feature = "client")]
+#[test]
+fn total_transfer_rent_test() {
+    let rent = solana_program::rent::Rent::default();
+    assert_eq!(
+        solbridge_master_contract::client::total_transfer_rent(&rent),
+        rent.minimum_balance(Lock::LEN)
+            + rent.minimum_balance(TxIndex::LEN)
+            + rent.minimum_balance(Signature::LEN)
+            + rent.minimum_balance(User::LEN) * 2
+            + rent.minimum_balance(LockTx::LEN) * 2
+    );
+}
 
-    let sender_account = get_account(&mut program_context, &sender_pubkey).await;
-    let sender_data: User = User::try_from_slice(&sender_account.data).unwrap();
-    println!("{:?}", sender_data);
-    assert_eq!(sender_data.version, 1);
-    assert_eq!(sender_data.blockchain_id, [0x45, 0x54, 0x48, 0x0]);
-    assert_eq!(sender_data.address, [2; 32]);
-    assert_eq!(sender_data.sent, 1);
-    assert_eq!(sender_data.received, 0);
+#[test]
+fn lock_status_test() {
+    let eth = str_to_chain_id("ETH").unwrap();
+    let mut lock = Lock::new(0, 1, [9; 64], Pubkey::new_unique(), [3; 32], eth, eth, [2; 32], [4; 32], str_to_chain_id("ARBITRUM").unwrap(), 10000, 0, 0, 0);
+    assert_eq!(lock.status(2), LockStatus { signatures: 0, threshold: 2, finalized: false, withdrawn: false });
 
-    let recipient_account = get_account(&mut program_context, &recipient_pubkey).await;
-    let recipient_data: User = User::try_from_slice(&recipient_account.data).unwrap();
-    println!("{:?}", recipient_data);
-    assert_eq!(recipient_data.version, 1);
-    assert_eq!(recipient_data.blockchain_id, [0x42, 0x53, 0x43, 0x0]);
-    assert_eq!(recipient_data.address, [4; 32]);
-    assert_eq!(recipient_data.sent, 0);
-    assert_eq!(recipient_data.received, 1);
+    lock.signatures = 2;
+    lock.finalized = true;
+    assert_eq!(lock.status(2), LockStatus { signatures: 2, threshold: 2, finalized: true, withdrawn: false });
 
-    assert_eq!(program_context
-                   .banks_client
-                   .get_account(sent_lock_pubkey)
-                   .await
-                   .expect("account not found"), None);
+    lock.withdrawn = true;
+    assert_eq!(lock.status(3), LockStatus { signatures: 2, threshold: 3, finalized: true, withdrawn: true });
+}
 
-    assert_eq!(program_context
-                   .banks_client
-                   .get_account(received_lock_pubkey)
-                   .await
-                   .expect("account not found"), None);
+#[test]
+fn unpack_truncated_test() {
+    let bridge = Bridge::new(Pubkey::new_unique(), 255).try_to_vec().unwrap();
+    assert_eq!(Bridge::unpack(&bridge).unwrap().version, PROGRAM_VERSION);
+    assert_eq!(Bridge::unpack(&bridge[..Bridge::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+
+    let blockchain = [0; Blockchain::LEN];
+    assert_eq!(Blockchain::unpack(&blockchain[..Blockchain::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
 
+    let validator = [0; Validator::LEN];
+    assert_eq!(Validator::unpack(&validator[..Validator::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+
+    let lock = [0; Lock::LEN];
+    assert_eq!(Lock::unpack(&lock[..Lock::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+
+    let signature = [0; Signature::LEN];
+    assert_eq!(Signature::unpack(&signature[..Signature::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+
+    let user = [0; User::LEN];
+    assert_eq!(User::unpack(&user[..User::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+
+    let lock_tx = [0; LockTx::LEN];
+    assert_eq!(LockTx::unpack(&lock_tx[..LockTx::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+
+    let tx_index = [0; TxIndex::LEN];
+    assert_eq!(TxIndex::unpack(&tx_index[..TxIndex::LEN - 1]).unwrap_err(), ProgramError::InvalidAccountData);
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn lock_index_test() {
+    let mut program_context = program_test().start_with_context().await;
+    let bridge_context = BridgeContext::init(&mut program_context).await;
+    let blockchain_pubkey = bridge_context.add_blockchain(&mut program_context, String::from("ETH"), [1; 32]).await;
+    bridge_context.add_blockchain(&mut program_context, String::from("ARBITRUM"), [6; 32]).await;
+    bridge_context.add_validator(&mut program_context, String::from("ETH"), [2; 32]).await;
+
+    let mut locks = vec![];
+    for (lock_id, tx_id) in [(1, [9; 64]), (2, [8; 64])] {
+        let (lock_pubkey, _, _, _, _, _) = bridge_context.add_signature(
+            &mut program_context,
+            [7; 65],
+            String::from("ETH"),
+            [3; 32],
+            String::from("ETH"),
+            tx_id,
+            lock_id,
+            String::from("ARBITRUM"),
+            [2; 32],
+            [4; 32],
+            10000,
+            0,
+            false
+        ).await.unwrap();
+        locks.push(Lock::try_from_slice(&get_account(&mut program_context, &lock_pubkey).await.data).unwrap());
+    }
+    assert_eq!(locks[0].index, 0);
+    assert_eq!(locks[1].index, 1);
+
+    let blockchain_data = Blockchain::try_from_slice(&get_account(&mut program_context, &blockchain_pubkey).await.data).unwrap();
+    assert_eq!(blockchain_data.locks, 2);
+
+    let source = str_to_chain_id("ETH").unwrap();
+    for lock in &locks {
+        let data = lock.try_to_vec().unwrap();
+        let matches = |index| solbridge_master_contract::client::lock_by_index_filters(&bridge_context.bridge.pubkey(), &source, index)
+            .iter()
+            .all(|(offset, bytes)| data[*offset..*offset + bytes.len()] == bytes[..]);
+        assert!(matches(lock.index));
+        assert!(!matches(lock.index + 1));
+    }
 }